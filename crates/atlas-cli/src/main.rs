@@ -0,0 +1,233 @@
+// src/main.rs
+use std::path::PathBuf;
+use std::process::ExitCode;
+use anyhow::{anyhow, Result};
+
+use atlas_core::Project;
+use atlas_core::analysis::{AnalysisResults, PpmEstimate, PpmMethod, SpecLimit, StackupAnalysis};
+
+const USAGE: &str = "\
+Usage: atlas-cli <project.ron> [options]
+
+Runs the stackup analyses of an Atlas project without opening the GUI.
+
+Options:
+  -a, --analysis <NAME|ID>  Run only this analysis (may be repeated)
+  -l, --list                List the analyses in the project and exit
+      --no-save             Do not write results into the project directory
+  -h, --help                Print this help
+
+Exit status: 0 if every analysis is within its spec limits, 1 if any
+analysis falls outside them, 2 on usage or project errors.";
+
+struct Options {
+    project_path: PathBuf,
+    selected: Vec<String>,
+    list_only: bool,
+    save: bool,
+}
+
+fn parse_args() -> Result<Option<Options>> {
+    let mut project_path = None;
+    let mut selected = Vec::new();
+    let mut list_only = false;
+    let mut save = true;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-l" | "--list" => list_only = true,
+            "--no-save" => save = false,
+            "-a" | "--analysis" => {
+                let value = args.next()
+                    .ok_or_else(|| anyhow!("{} requires an analysis name or id", arg))?;
+                selected.push(value);
+            }
+            _ if arg.starts_with('-') => return Err(anyhow!("Unknown option: {}", arg)),
+            _ => {
+                if project_path.is_some() {
+                    return Err(anyhow!("Only one project file may be given"));
+                }
+                project_path = Some(PathBuf::from(arg));
+            }
+        }
+    }
+
+    let project_path = project_path.ok_or_else(|| anyhow!("No project file given"))?;
+    Ok(Some(Options { project_path, selected, list_only, save }))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the selected analyses and returns whether all of them are within spec.
+fn run(options: &Options) -> Result<bool> {
+    let mut project = Project::open(&options.project_path)?;
+
+    if options.list_only {
+        println!("Project: {}", project.file.name);
+        for analysis in &project.analyses {
+            println!("  {}  {} ({} contributions)", analysis.id, analysis.name, analysis.contributions.len());
+        }
+        return Ok(true);
+    }
+
+    let mut indices = Vec::new();
+    for wanted in &options.selected {
+        let index = project.analysis_index(wanted)
+            .ok_or_else(|| anyhow!("No analysis named '{}' in {}", wanted, options.project_path.display()))?;
+        if !indices.contains(&index) {
+            indices.push(index);
+        }
+    }
+    if indices.is_empty() {
+        indices = (0..project.analyses.len()).collect();
+    }
+
+    println!("Project: {} ({} analyses)", project.file.name, indices.len());
+
+    let mut all_within_spec = true;
+    for index in indices {
+        let results = if options.save {
+            project.run_analysis(index)?
+        } else {
+            project.analyses[index].run_analysis(&project.components)?
+        };
+
+        if !print_summary(&project.analyses[index], &results) {
+            all_within_spec = false;
+        }
+    }
+
+    Ok(all_within_spec)
+}
+
+/// Prints the results of one analysis and returns whether it is within spec.
+fn print_summary(analysis: &StackupAnalysis, results: &AnalysisResults) -> bool {
+    println!();
+    println!("== {} ==", analysis.name);
+    if let Some(expression) = &analysis.expression {
+        println!("  Stack:        {}", expression);
+    }
+    println!("  Nominal:      {:.6}", results.nominal);
+
+    if let Some(wc) = &results.worst_case {
+        println!("  Worst Case:   [{:.6}, {:.6}]", wc.min, wc.max);
+    }
+    if let Some(rss) = &results.rss {
+        println!("  RSS (3σ):     [{:.6}, {:.6}]  mean {:.6}  std dev {:.6}", rss.min, rss.max, rss.mean, rss.std_dev);
+    }
+    if let Some(mrss) = &results.modified_rss {
+        println!("  MRSS (3σ):    [{:.6}, {:.6}]  std dev {:.6}  factor {:.3}",
+            mrss.min, mrss.max, mrss.std_dev, mrss.correction_factor);
+    }
+    if let Some(six_sigma) = &results.six_sigma {
+        println!("  Six Sigma:    [{:.6}, {:.6}]  {:?}  drift {:.6}  σ st {:.6}  σ lt {:.6}",
+            six_sigma.min, six_sigma.max, six_sigma.mode, six_sigma.mean_drift,
+            six_sigma.short_term_std_dev, six_sigma.long_term_std_dev);
+        if let Some(cpk) = six_sigma.cpk {
+            let ppm = six_sigma.ppm_above.unwrap_or(0.0) + six_sigma.ppm_below.unwrap_or(0.0);
+            println!("                predicted Cpk {:.3}  ppm {:.1}", cpk, ppm);
+        }
+    }
+    if let Some(mc) = &results.monte_carlo {
+        println!("  Monte Carlo:  mean {:.6}  std dev {:.6}  range [{:.6}, {:.6}]", mc.mean, mc.std_dev, mc.min, mc.max);
+        if let Some(independent) = mc.independent_std_dev {
+            println!("                independent std dev {:.6}  ({:+.1}% from correlation)",
+                independent, (mc.std_dev / independent - 1.0) * 100.0);
+        }
+        let convergence = match mc.converged {
+            Some(true) => "  converged",
+            Some(false) => "  NOT converged",
+            None => "",
+        };
+        println!("                {} {} samples{}  s.e. mean {:.6}  s.e. std dev {:.6}",
+            mc.iterations, mc.sampling.label(), convergence, mc.mean_std_error, mc.std_dev_std_error);
+        for interval in &mc.confidence_intervals {
+            print!("    {:>7.3}%:    [{:.6}, {:.6}]",
+                interval.confidence_level * 100.0, interval.lower_bound, interval.upper_bound);
+            if let (Some(lower), Some(upper)) = (interval.lower_band, interval.upper_band) {
+                print!("  95% bands [{:.6}, {:.6}] [{:.6}, {:.6}]", lower.0, lower.1, upper.0, upper.1);
+            }
+            println!();
+        }
+        for (label, estimate) in [("below", mc.ppm_below), ("above", mc.ppm_above)] {
+            if let Some(estimate) = estimate {
+                println!("    Sample PPM {}: {:.1}  95% [{:.1}, {:.1}]  ({} of {} samples)",
+                    label, estimate.ppm, estimate.lower, estimate.upper, estimate.count, mc.iterations);
+            }
+        }
+        for (label, estimate) in [("below", mc.tail_below), ("above", mc.tail_above)] {
+            if let Some(PpmEstimate { ppm, lower, upper, method: PpmMethod::ImportanceSampled { samples, shift }, .. }) = estimate {
+                println!("    Tail PPM {}:   {:.4}  95% [{:.4}, {:.4}]  (importance sampled, {} samples at {:.2}σ)",
+                    label, ppm, lower, upper, samples, shift);
+            }
+        }
+    }
+    if let Some(cap) = &results.process_capability {
+        if let (Some(cp), Some(cpk)) = (cap.cp, cap.cpk) {
+            println!("  Capability:   Cp {:.3}  Cpk {:.3}", cp, cpk);
+        }
+        if let (Some(below), Some(above)) = (cap.ppm_below, cap.ppm_above) {
+            print!("  Expected PPM: below {:.4}  above {:.4}", below, above);
+            if let (Some(below), Some(above)) = (cap.ppm_below_band, cap.ppm_above_band) {
+                print!("  95% [{:.4}, {:.4}] [{:.4}, {:.4}]", below.0, below.1, above.0, above.1);
+            }
+            println!();
+        }
+    }
+
+    if let Some(cost) = &results.cost {
+        print!("  Cost:         {:.3} for {} features", cost.total, cost.features.len() - cost.out_of_capability().count());
+        if !cost.unpriced.is_empty() {
+            print!("  ({} without a process)", cost.unpriced.len());
+        }
+        println!();
+        for feature in cost.out_of_capability() {
+            println!("    {}.{}: ±{:.6} is tighter than {} holds",
+                feature.component_id, feature.feature_id, feature.tolerance, feature.process.label());
+        }
+    }
+
+    let lsl = analysis.lower_spec_limit.map(|v| format!("{:.6}", v)).unwrap_or_else(|| "—".to_string());
+    let usl = analysis.upper_spec_limit.map(|v| format!("{:.6}", v)).unwrap_or_else(|| "—".to_string());
+    println!("  Spec Limits:  LSL {}  USL {}", lsl, usl);
+
+    let violations = analysis.check_spec_limits(results);
+    if violations.is_empty() {
+        println!("  Status:       PASS");
+        true
+    } else {
+        println!("  Status:       FAIL");
+        for violation in &violations {
+            let (label, relation) = match violation.limit {
+                SpecLimit::Upper => ("USL", "above"),
+                SpecLimit::Lower => ("LSL", "below"),
+            };
+            println!("    {:?}: {:.6} is {} {} {:.6}",
+                violation.method, violation.predicted, relation, label, violation.limit_value);
+        }
+        false
+    }
+}
@@ -0,0 +1,457 @@
+// src/analysis/allocation.rs
+
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Result};
+use crate::config::{Component, CostCurve, Dimension, Units};
+use super::stackup::StackupAnalysis;
+
+/// What the allocated tolerances have to achieve
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AllocationTarget {
+    /// Predicted Cpk of the stack at least this, with each feature's std dev
+    /// scaling with its tolerance
+    Cpk(f64),
+    /// Worst-case stack within the spec limits
+    WorstCase,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AllocationMethod {
+    /// Scale every current tolerance by the same factor
+    Proportional,
+    /// The same precision for every feature: tolerances in proportion to the
+    /// ISO 286 standard tolerance factor of the feature's size
+    EqualPrecision,
+    /// Least total cost under the cost model
+    MinimumCost,
+}
+
+/// Cost of holding a feature to tolerance `t`, times the feature's weight
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum CostModel {
+    /// 1 / t
+    Reciprocal,
+    /// exp(-t / t₀), with t₀ the current tolerance
+    Exponential,
+    /// Each feature's process cost curve; 1 / t for features without a process
+    Process,
+}
+
+/// Bounds and relative cost of one feature's tolerance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureAllocation {
+    pub component_id: String,
+    pub feature_id: String,
+    /// Tightest tolerance the process can hold; the process curve's when unset
+    pub min_tolerance: Option<f64>,
+    /// Loosest tolerance worth giving; the process curve's when unset
+    pub max_tolerance: Option<f64>,
+    pub cost_weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllocationSettings {
+    pub target: AllocationTarget,
+    pub method: AllocationMethod,
+    pub cost_model: CostModel,
+    /// Features without an entry are bounded by their process only, with weight 1
+    #[serde(default)]
+    pub features: Vec<FeatureAllocation>,
+}
+
+impl Default for AllocationSettings {
+    fn default() -> Self {
+        Self {
+            target: AllocationTarget::Cpk(1.33),
+            method: AllocationMethod::Proportional,
+            cost_model: CostModel::Reciprocal,
+            features: Vec::new(),
+        }
+    }
+}
+
+impl AllocationSettings {
+    pub fn feature(&self, component_id: &str, feature_id: &str) -> Option<&FeatureAllocation> {
+        self.features.iter().find(|f| f.component_id == component_id && f.feature_id == feature_id)
+    }
+
+    /// The entry for a feature, added with the defaults if missing
+    pub fn feature_mut(&mut self, component_id: &str, feature_id: &str) -> &mut FeatureAllocation {
+        let index = match self.features.iter().position(|f| f.component_id == component_id && f.feature_id == feature_id) {
+            Some(index) => index,
+            None => {
+                self.features.push(FeatureAllocation {
+                    component_id: component_id.to_string(),
+                    feature_id: feature_id.to_string(),
+                    min_tolerance: None,
+                    max_tolerance: None,
+                    cost_weight: 1.0,
+                });
+                self.features.len() - 1
+            },
+        };
+        &mut self.features[index]
+    }
+}
+
+/// New tolerances for one feature. The middle of the tolerance zone stays
+/// where it is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedTolerance {
+    pub component_id: String,
+    pub feature_id: String,
+    pub current_plus: f64,
+    pub current_minus: f64,
+    pub plus_tolerance: f64,
+    pub minus_tolerance: f64,
+    /// Weighted cost at the proposed tolerance
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllocationResult {
+    pub target: AllocationTarget,
+    pub proposals: Vec<ProposedTolerance>,
+    /// Worst-case half-range, or stack std dev for a Cpk target, with the
+    /// proposed tolerances
+    pub width: f64,
+    /// Largest `width` that meets the target
+    pub budget: f64,
+    pub met: bool,
+    pub current_cost: f64,
+    pub proposed_cost: f64,
+}
+
+impl AllocationResult {
+    /// Cpk the proposed tolerances give, for a Cpk target
+    pub fn predicted_cpk(&self) -> Option<f64> {
+        match self.target {
+            AllocationTarget::Cpk(cpk) if self.width > 0.0 => Some(cpk * self.budget / self.width),
+            _ => None,
+        }
+    }
+
+    /// Write the proposed tolerances into the features, refitting their
+    /// calculated distributions and dropping their tolerance classes
+    pub fn apply(&self, components: &mut [Component]) {
+        for proposal in &self.proposals {
+            let feature = components.iter_mut()
+                .filter(|c| c.name == proposal.component_id)
+                .flat_map(|c| c.features.iter_mut())
+                .find(|f| f.name == proposal.feature_id);
+
+            if let Some(feature) = feature {
+                feature.dimension.plus_tolerance = proposal.plus_tolerance;
+                feature.dimension.minus_tolerance = proposal.minus_tolerance;
+                // The allocated tolerance no longer follows a fit class
+                feature.tolerance_class = None;
+                if feature.distribution_params.as_ref().is_none_or(|p| p.calculated) {
+                    feature.distribution_params = Some(StackupAnalysis::calculate_distribution_params(feature));
+                }
+            }
+        }
+    }
+}
+
+/// One feature in the allocation, which may feed several contributions
+struct Allocated<'a> {
+    component_id: &'a str,
+    feature_id: &'a str,
+    dimension: &'a Dimension,
+    /// Stack width per unit of tolerance
+    sensitivity: f64,
+    lower: f64,
+    upper: f64,
+    weight: f64,
+    curve: Option<CostCurve>,
+}
+
+impl Allocated<'_> {
+    fn current(&self) -> f64 {
+        self.dimension.bilateral_tolerance()
+    }
+
+    fn cost(&self, model: CostModel, t: f64) -> f64 {
+        self.weight * match model {
+            CostModel::Reciprocal => 1.0 / t,
+            CostModel::Exponential => (-t / self.cost_scale()).exp(),
+            CostModel::Process => match &self.curve {
+                Some(curve) => curve.value(t.min(curve.max_tolerance)),
+                None => 1.0 / t,
+            },
+        }
+    }
+
+    fn cost_slope(&self, model: CostModel, t: f64) -> f64 {
+        self.weight * match model {
+            CostModel::Reciprocal => -1.0 / (t * t),
+            CostModel::Exponential => -(-t / self.cost_scale()).exp() / self.cost_scale(),
+            CostModel::Process => match &self.curve {
+                Some(curve) if t > curve.max_tolerance => 0.0,
+                Some(curve) => curve.slope(t),
+                None => -1.0 / (t * t),
+            },
+        }
+    }
+
+    /// Current tolerance, or the widest useful one when there is none
+    fn cost_scale(&self) -> f64 {
+        if self.current() > 0.0 { self.current() } else { self.upper }
+    }
+}
+
+impl StackupAnalysis {
+    /// Propose tolerances for the stack's features that meet `settings.target`
+    /// against the spec limits, with lengths in `units`. The stack is
+    /// linearized about the feature means, so re-run the analysis after
+    /// applying the proposal to a nonlinear stack.
+    pub fn allocate_tolerances(&self, components: &[Component], settings: &AllocationSettings, units: Units) -> Result<AllocationResult> {
+        let stack = self.resolve(components)?;
+        if stack.contributors.is_empty() {
+            bail!("Analysis {} has no contributions to allocate", self.name);
+        }
+
+        let means = stack.mean_values();
+        let mean = stack.evaluate(&means);
+        let margin = match (self.upper_spec_limit, self.lower_spec_limit) {
+            (Some(usl), Some(lsl)) => (usl - mean).min(mean - lsl),
+            (Some(usl), None) => usl - mean,
+            (None, Some(lsl)) => mean - lsl,
+            (None, None) => bail!("Analysis {} needs spec limits to allocate tolerances", self.name),
+        };
+        if margin <= 0.0 {
+            bail!("The stack mean {:.6} is outside the spec limits; no tolerances can meet them", mean);
+        }
+
+        let (budget, statistical) = match settings.target {
+            AllocationTarget::Cpk(cpk) if cpk > 0.0 => (margin / (3.0 * cpk), true),
+            AllocationTarget::Cpk(_) => bail!("The target Cpk must be positive"),
+            AllocationTarget::WorstCase => (margin, false),
+        };
+
+        // Per feature: stack width per unit tolerance, summed over its contributions
+        let mut features: Vec<Allocated> = Vec::new();
+        let coefficients = stack.coefficients(&means)?;
+        for (((contrib, feature), params), coefficient) in stack.contributors.iter().zip(&stack.distributions).zip(&coefficients) {
+            let tolerance = feature.dimension.bilateral_tolerance();
+            let per_tolerance = if !statistical {
+                1.0
+            } else if tolerance > 0.0 {
+                params.moments().1 / tolerance
+            } else {
+                1.0 / 3.0
+            };
+            let sensitivity = coefficient.abs() * per_tolerance;
+
+            match features.iter_mut().find(|f| f.component_id == contrib.component_id && f.feature_id == contrib.feature_id) {
+                Some(existing) => existing.sensitivity += sensitivity,
+                None => {
+                    let bounds = settings.feature(&contrib.component_id, &contrib.feature_id);
+                    let curve = feature.process.map(|p| p.curve);
+                    features.push(Allocated {
+                        component_id: &contrib.component_id,
+                        feature_id: &contrib.feature_id,
+                        dimension: &feature.dimension,
+                        sensitivity,
+                        lower: bounds.and_then(|b| b.min_tolerance)
+                            .or(curve.map(|c| c.min_tolerance))
+                            .unwrap_or(0.0)
+                            .max(0.0),
+                        upper: bounds.and_then(|b| b.max_tolerance)
+                            .or(curve.map(|c| c.max_tolerance))
+                            .unwrap_or(f64::INFINITY),
+                        weight: bounds.map_or(1.0, |b| b.cost_weight.max(0.0)),
+                        curve,
+                    });
+                },
+            }
+        }
+        for feature in &mut features {
+            if feature.upper < feature.lower {
+                bail!("{}.{}: the maximum tolerance is below the minimum", feature.component_id, feature.feature_id);
+            }
+            // No one feature may use more than the whole budget
+            if feature.sensitivity > 0.0 {
+                feature.upper = feature.upper.min(budget / feature.sensitivity).max(feature.lower);
+                feature.lower = feature.lower.max(feature.upper * 1e-9);
+            }
+        }
+
+        let width = |tolerances: &[f64]| {
+            let terms = features.iter().zip(tolerances).map(|(f, t)| f.sensitivity * t);
+            if statistical {
+                terms.map(|x| x * x).sum::<f64>().sqrt()
+            } else {
+                terms.sum::<f64>()
+            }
+        };
+
+        let tolerances: Vec<f64> = match settings.method {
+            AllocationMethod::Proportional | AllocationMethod::EqualPrecision => {
+                let base: Vec<f64> = features.iter()
+                    .map(|f| match settings.method {
+                        AllocationMethod::EqualPrecision => tolerance_factor(f.dimension.value.abs(), units),
+                        _ => f.current(),
+                    })
+                    .collect();
+                let scaled = |scale: f64| features.iter().zip(&base)
+                    .map(|(f, b)| if f.sensitivity > 0.0 { (scale * b).clamp(f.lower, f.upper) } else { f.current() })
+                    .collect::<Vec<f64>>();
+                // Width grows with the scale; widen the bracket until it passes the budget
+                let mut high = 1.0;
+                while width(&scaled(high)) < budget && high < 1e12 {
+                    high *= 2.0;
+                }
+                scaled(bisect(|scale| width(&scaled(scale)) - budget, 0.0, high))
+            },
+            AllocationMethod::MinimumCost => {
+                // Each tolerance minimizes cost + λ·(its share of the width); the
+                // constraint is linear for worst case and quadratic for RSS
+                let at = |lambda: f64| features.iter()
+                    .map(|f| {
+                        if f.sensitivity <= 0.0 {
+                            return f.current();
+                        }
+                        let slope = |t: f64| f.cost_slope(settings.cost_model, t) + lambda * if statistical {
+                            2.0 * f.sensitivity * f.sensitivity * t
+                        } else {
+                            f.sensitivity
+                        };
+                        if slope(f.lower) >= 0.0 {
+                            f.lower
+                        } else if slope(f.upper) <= 0.0 {
+                            f.upper
+                        } else {
+                            bisect(slope, f.lower, f.upper)
+                        }
+                    })
+                    .collect::<Vec<f64>>();
+                // Width shrinks as λ grows; search on log λ
+                let log_lambda = bisect(|log_lambda| budget - width(&at(log_lambda.exp())), -200.0, 200.0);
+                at(log_lambda.exp())
+            },
+        };
+
+        let proposals = features.iter().zip(&tolerances)
+            .map(|(f, t)| {
+                let offset = f.dimension.mean() - f.dimension.value;
+                ProposedTolerance {
+                    component_id: f.component_id.to_string(),
+                    feature_id: f.feature_id.to_string(),
+                    current_plus: f.dimension.plus_tolerance,
+                    current_minus: f.dimension.minus_tolerance,
+                    plus_tolerance: offset + t,
+                    minus_tolerance: t - offset,
+                    cost: f.cost(settings.cost_model, *t),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let achieved = width(&tolerances);
+        Ok(AllocationResult {
+            target: settings.target,
+            width: achieved,
+            budget,
+            met: achieved <= budget * (1.0 + 1e-6),
+            current_cost: features.iter().map(|f| f.cost(settings.cost_model, f.current())).sum(),
+            proposed_cost: proposals.iter().map(|p| p.cost).sum(),
+            proposals,
+        })
+    }
+}
+
+/// ISO 286 standard tolerance factor i for a size, both in `units`: one IT
+/// grade is a fixed multiple of it across all sizes
+fn tolerance_factor(size: f64, units: Units) -> f64 {
+    let millimetres = size * units.millimetres();
+    let microns = 0.45 * millimetres.cbrt() + 0.001 * millimetres;
+    microns / 1000.0 / units.millimetres()
+}
+
+/// Root of increasing `f` in [low, high], or the nearer end when it has none
+fn bisect(mut f: impl FnMut(f64) -> f64, mut low: f64, mut high: f64) -> f64 {
+    if f(low) >= 0.0 {
+        return low;
+    }
+    if f(high) <= 0.0 {
+        return high;
+    }
+    for _ in 0..200 {
+        let middle = 0.5 * (low + high);
+        if f(middle) < 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+        if high - low <= 1e-15 * high.abs().max(1.0) {
+            break;
+        }
+    }
+    0.5 * (low + high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::stackup::AnalysisMethod;
+    use crate::analysis::test_support::stack_of;
+
+    #[test]
+    fn test_tolerance_allocation() {
+        let (mut components, mut analysis) = stack_of(&[0.1, 0.2, 0.1]);
+        analysis.upper_spec_limit = Some(30.2);
+        analysis.lower_spec_limit = Some(29.8);
+        let mut settings = AllocationSettings::default();
+
+        // Proportional to Cpk 1.33: ratios kept, RSS brought to the budget
+        let result = analysis.allocate_tolerances(&components, &settings, Units::Metric).unwrap();
+        assert!(result.met);
+        assert!((result.predicted_cpk().unwrap() - 1.33).abs() < 1e-9);
+        let tolerances: Vec<f64> = result.proposals.iter().map(|p| p.plus_tolerance).collect();
+        assert!((tolerances[1] / tolerances[0] - 2.0).abs() < 1e-9);
+        assert!((tolerances.iter().map(|t| (t / 3.0).powi(2)).sum::<f64>().sqrt() - 0.2 / (3.0 * 1.33)).abs() < 1e-9);
+
+        // Worst case at least cost: equal weights split the margin evenly,
+        // unless a feature's bound stops it
+        settings.target = AllocationTarget::WorstCase;
+        settings.method = AllocationMethod::MinimumCost;
+        let result = analysis.allocate_tolerances(&components, &settings, Units::Metric).unwrap();
+        for proposal in &result.proposals {
+            assert!((proposal.plus_tolerance - 0.2 / 3.0).abs() < 1e-6);
+        }
+        settings.feature_mut("Part", "F0").max_tolerance = Some(0.03);
+        let result = analysis.allocate_tolerances(&components, &settings, Units::Metric).unwrap();
+        assert!((result.proposals[0].plus_tolerance - 0.03).abs() < 1e-9);
+        assert!((result.proposals[1].plus_tolerance - 0.085).abs() < 1e-6);
+        assert!((result.current_cost - 25.0).abs() < 1e-9);
+        assert!((result.proposed_cost - (1.0 / 0.03 + 2.0 / 0.085)).abs() < 1e-3);
+
+        // Applying writes the tolerances back and the stack then meets its limits
+        result.apply(&mut components);
+        analysis.methods = vec![AnalysisMethod::WorstCase];
+        let wc = analysis.run_analysis(&components).unwrap().worst_case.unwrap();
+        assert!(wc.max <= 30.2 + 1e-6 && wc.min >= 29.8 - 1e-6);
+
+        // Tolerances cannot fit a stack whose mean is out of spec
+        analysis.upper_spec_limit = Some(29.9);
+        assert!(analysis.allocate_tolerances(&components, &settings, Units::Metric).is_err());
+
+        // Equal precision follows ISO 286 sizes in mm whatever the project units
+        let equal_precision = |units: Units| {
+            let per_unit = units.millimetres();
+            let (mut components, mut analysis) = stack_of(&[0.1, 0.1]);
+            components[0].features[0].dimension.value = 10.0 / per_unit;
+            components[0].features[1].dimension.value = 100.0 / per_unit;
+            analysis.upper_spec_limit = Some(110.2 / per_unit);
+            analysis.lower_spec_limit = Some(109.8 / per_unit);
+            let settings = AllocationSettings { method: AllocationMethod::EqualPrecision, ..Default::default() };
+            let result = analysis.allocate_tolerances(&components, &settings, units).unwrap();
+            [result.proposals[0].plus_tolerance * per_unit, result.proposals[1].plus_tolerance * per_unit]
+        };
+        let metric = equal_precision(Units::Metric);
+        let imperial = equal_precision(Units::Imperial);
+        let factor = |size: f64| 0.45 * size.cbrt() + 0.001 * size;
+        assert!((metric[1] / metric[0] - factor(100.0) / factor(10.0)).abs() < 1e-9);
+        assert!((imperial[0] - metric[0]).abs() < 1e-9 && (imperial[1] - metric[1]).abs() < 1e-9);
+    }
+}
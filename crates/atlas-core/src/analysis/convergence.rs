@@ -0,0 +1,124 @@
+// src/analysis/convergence.rs
+
+use serde::{Serialize, Deserialize};
+
+/// Two-sided 95% normal quantile used for every band reported by Monte Carlo
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Adaptive Monte Carlo: after the first `iterations` samples, keep drawing
+/// batches until the estimates are stable or `max_iterations` is reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvergenceSettings {
+    pub batch_size: usize,
+    pub max_iterations: usize,
+    /// Largest standard error allowed on the mean, the std dev and the
+    /// percentile bounds at the configured confidence, as a fraction of the
+    /// stack std dev
+    pub tolerance: f64,
+}
+
+impl Default for ConvergenceSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 10_000,
+            max_iterations: 1_000_000,
+            tolerance: 0.02,
+        }
+    }
+}
+
+/// How a `PpmEstimate` was made
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum PpmMethod {
+    /// Fraction of the plain Monte Carlo samples
+    #[default]
+    Sampled,
+    /// Samples drawn around the most probable failure point and weighted back
+    /// to the true distribution; `shift` is that point's distance from the
+    /// mean in standard normal scores
+    ImportanceSampled { samples: usize, shift: f64 },
+}
+
+/// Sample estimate of a fraction out of spec, in parts per million, with a
+/// 95% band. For plain samples the band is the Wilson score interval; with no
+/// failures in the sample its upper end is the most the sample can rule out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PpmEstimate {
+    /// Failures seen in the sample
+    pub count: usize,
+    pub ppm: f64,
+    pub lower: f64,
+    pub upper: f64,
+    #[serde(default)]
+    pub method: PpmMethod,
+}
+
+impl PpmEstimate {
+    pub(crate) fn new(count: usize, n: usize) -> Self {
+        let n = n.max(1) as f64;
+        let p = count as f64 / n;
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half_width = Z_95 / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+        Self {
+            count,
+            ppm: p * 1e6,
+            lower: (center - half_width).max(0.0) * 1e6,
+            upper: (center + half_width).min(1.0) * 1e6,
+            method: PpmMethod::Sampled,
+        }
+    }
+
+    /// Importance sampling estimate from `n` samples, `count` of them failures
+    /// whose likelihood ratios sum to `sum` and their squares to `sum_squares`.
+    /// The band is normal on the weighted mean.
+    pub(crate) fn importance_sampled(count: usize, sum: f64, sum_squares: f64, n: usize, shift: f64) -> Self {
+        let samples = n;
+        let n = n.max(1) as f64;
+        let p = sum / n;
+        let std_error = ((sum_squares / n - p * p).max(0.0) / n).sqrt();
+
+        Self {
+            count,
+            ppm: p * 1e6,
+            lower: (p - Z_95 * std_error).max(0.0) * 1e6,
+            upper: (p + Z_95 * std_error).min(1.0) * 1e6,
+            method: PpmMethod::ImportanceSampled { samples, shift },
+        }
+    }
+}
+
+/// Standard error of the sample standard deviation, from the fourth central
+/// moment so it holds for non-normal stacks too
+pub(crate) fn std_dev_std_error(samples: &[f64], mean: f64, std_dev: f64) -> f64 {
+    let n = samples.len() as f64;
+    if n < 4.0 || std_dev <= 0.0 {
+        return 0.0;
+    }
+
+    let m4 = samples.iter().map(|x| (x - mean).powi(4)).sum::<f64>() / n;
+    let variance = std_dev * std_dev;
+    let variance_of_variance = (m4 - variance * variance * (n - 3.0) / (n - 1.0)) / n;
+    variance_of_variance.max(0.0).sqrt() / (2.0 * std_dev)
+}
+
+/// 95% distribution-free band for the `p` quantile of `sorted`, from the
+/// binomial spread of the order statistic's rank
+pub(crate) fn percentile_band(sorted: &[f64], p: f64) -> (f64, f64) {
+    let n = sorted.len();
+    if n == 0 {
+        return (f64::NAN, f64::NAN);
+    }
+
+    let rank = p * n as f64;
+    let spread = Z_95 * (n as f64 * p * (1.0 - p)).sqrt();
+    let lower = ((rank - spread).floor().max(0.0) as usize).min(n - 1);
+    let upper = ((rank + spread).ceil().max(0.0) as usize).min(n - 1);
+    (sorted[lower], sorted[upper])
+}
+
+/// Standard error implied by a 95% band
+pub(crate) fn band_std_error((lower, upper): (f64, f64)) -> f64 {
+    (upper - lower) / (2.0 * Z_95)
+}
@@ -0,0 +1,94 @@
+// src/analysis/correlation.rs
+
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Result};
+use super::stack_function::ResolvedStack;
+
+/// Contributors that vary together, e.g. features cut in the same fixture or
+/// molded in the same cavity. Every pair of members gets `coefficient`.
+///
+/// The coefficient is the correlation of the normal scores in a Gaussian
+/// copula, so each contributor keeps its own distribution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationGroup {
+    pub name: String,
+    pub coefficient: f64,
+    pub members: Vec<(String, String)>, // (component_id, feature_id)
+}
+
+impl CorrelationGroup {
+    pub fn new(name: String, coefficient: f64) -> Self {
+        Self {
+            name,
+            coefficient,
+            members: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, component_id: &str, feature_id: &str) -> bool {
+        self.members.iter().any(|(c, f)| c == component_id && f == feature_id)
+    }
+}
+
+/// Lower-triangular Cholesky factor of the correlation matrix the groups give
+/// the stack's contributors, or None when no two contributors are correlated.
+/// Later groups win where groups overlap.
+pub(crate) fn correlation_factor(stack: &ResolvedStack, groups: &[CorrelationGroup]) -> Result<Option<Vec<Vec<f64>>>> {
+    let n = stack.contributors.len();
+    let mut matrix: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    let mut correlated = false;
+
+    for group in groups {
+        if !(-1.0..=1.0).contains(&group.coefficient) {
+            bail!("Correlation group {} has a coefficient outside [-1, 1]", group.name);
+        }
+
+        let members: Vec<usize> = stack.contributors.iter()
+            .enumerate()
+            .filter(|(_, (contrib, _))| group.contains(&contrib.component_id, &contrib.feature_id))
+            .map(|(i, _)| i)
+            .collect();
+
+        for &i in &members {
+            for &j in &members {
+                if i != j {
+                    matrix[i][j] = group.coefficient;
+                    correlated |= group.coefficient != 0.0;
+                }
+            }
+        }
+    }
+
+    if !correlated {
+        return Ok(None);
+    }
+
+    cholesky(&matrix).map(Some)
+}
+
+fn cholesky(matrix: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut lower = vec![vec![0.0; n]; n];
+
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let diagonal = matrix[i][i] - sum;
+                // Perfectly correlated groups leave a zero pivot; allow for round-off
+                if diagonal < -1e-9 {
+                    bail!("Correlation groups do not form a valid correlation matrix (not positive semi-definite)");
+                }
+                lower[i][j] = diagonal.max(0.0).sqrt();
+            } else if lower[j][j] > 1e-12 {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            } else if (matrix[i][j] - sum).abs() > 1e-9 {
+                bail!("Correlation groups do not form a valid correlation matrix (not positive semi-definite)");
+            }
+        }
+    }
+
+    Ok(lower)
+}
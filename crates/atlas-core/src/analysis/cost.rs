@@ -0,0 +1,118 @@
+// src/analysis/cost.rs
+
+use serde::{Serialize, Deserialize};
+use crate::config::ManufacturingProcess;
+use super::stack_function::ResolvedStack;
+
+/// Cost of one feature of the stack at its current tolerance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureCost {
+    pub component_id: String,
+    pub feature_id: String,
+    pub process: ManufacturingProcess,
+    /// Bilateral tolerance the cost is for
+    pub tolerance: f64,
+    /// None when the tolerance is tighter than the process holds
+    pub cost: Option<f64>,
+}
+
+/// Estimated cost of making the stack's features, from their cost curves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Sum over the features the processes can make
+    pub total: f64,
+    pub features: Vec<FeatureCost>,
+    /// Features without a process, left out of the total
+    pub unpriced: Vec<(String, String)>,
+}
+
+impl CostEstimate {
+    /// Features tighter than their process can hold
+    pub fn out_of_capability(&self) -> impl Iterator<Item = &FeatureCost> {
+        self.features.iter().filter(|f| f.cost.is_none())
+    }
+}
+
+/// Cost of every feature in the stack, each counted once however many
+/// contributions use it. None when no feature has a process.
+pub(crate) fn estimate_cost(stack: &ResolvedStack) -> Option<CostEstimate> {
+    let mut features: Vec<FeatureCost> = Vec::new();
+    let mut unpriced: Vec<(String, String)> = Vec::new();
+
+    for (contrib, feature) in &stack.contributors {
+        let key = (contrib.component_id.clone(), contrib.feature_id.clone());
+        let seen = features.iter().any(|f| f.component_id == key.0 && f.feature_id == key.1)
+            || unpriced.contains(&key);
+        if seen {
+            continue;
+        }
+
+        match &feature.process {
+            Some(process) => {
+                let tolerance = feature.dimension.bilateral_tolerance();
+                features.push(FeatureCost {
+                    component_id: key.0,
+                    feature_id: key.1,
+                    process: process.process,
+                    tolerance,
+                    cost: process.curve.cost(tolerance),
+                });
+            },
+            None => unpriced.push(key),
+        }
+    }
+
+    if features.is_empty() {
+        return None;
+    }
+    Some(CostEstimate {
+        total: features.iter().filter_map(|f| f.cost).sum(),
+        features,
+        unpriced,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::allocation::{AllocationMethod, AllocationSettings, AllocationTarget, CostModel};
+    use crate::analysis::stackup::AnalysisMethod;
+    use crate::analysis::test_support::stack_of;
+    use crate::config::{FeatureProcess, Units};
+
+    #[test]
+    fn test_cost_estimate() {
+        let (mut components, mut analysis) = stack_of(&[0.05, 0.1, 0.01]);
+        let features = &mut components[0].features;
+        features[0].process = FeatureProcess::from_table(ManufacturingProcess::Turning, Units::Metric);
+        features[1].process = FeatureProcess::from_table(ManufacturingProcess::Milling, Units::Metric);
+        features[2].process = FeatureProcess::from_table(ManufacturingProcess::Milling, Units::Metric);
+        analysis.methods = vec![AnalysisMethod::Rss];
+
+        // Milling cannot hold ±0.01, so that feature is flagged and left out
+        let cost = analysis.run_analysis(&components).unwrap().cost.unwrap();
+        assert!((cost.total - (2.0 + 0.05 / 0.05 + 3.0 + 0.08 / 0.1)).abs() < 1e-12);
+        assert_eq!(cost.out_of_capability().map(|f| f.feature_id.as_str()).collect::<Vec<_>>(), ["F2"]);
+        assert!(cost.unpriced.is_empty());
+
+        // Imperial tables give the same cost for the same tolerance in inches
+        let imperial = ManufacturingProcess::Turning.table_curve(Units::Imperial).unwrap();
+        assert!((imperial.value(0.05 / 25.4) - 3.0).abs() < 1e-12);
+
+        // Allocating on the process curves keeps every feature within its process
+        analysis.upper_spec_limit = Some(30.3);
+        analysis.lower_spec_limit = Some(29.7);
+        let settings = AllocationSettings {
+            target: AllocationTarget::WorstCase,
+            method: AllocationMethod::MinimumCost,
+            cost_model: CostModel::Process,
+            features: Vec::new(),
+        };
+        let result = analysis.allocate_tolerances(&components, &settings, Units::Metric).unwrap();
+        assert!(result.met);
+        result.apply(&mut components);
+        let cost = analysis.run_analysis(&components).unwrap().cost.unwrap();
+        assert_eq!(cost.out_of_capability().count(), 0);
+        assert!((cost.total - result.proposed_cost).abs() < 1e-9);
+    }
+}
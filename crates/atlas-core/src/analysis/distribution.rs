@@ -0,0 +1,643 @@
+// src/analysis/distribution.rs
+
+use std::fmt;
+use serde::{Serialize, Deserialize, Deserializer};
+use serde::de::{self, Visitor};
+use rand::prelude::*;
+use rand_distr::{Beta as RandBeta, Distribution, Normal as RandNormal, Uniform, LogNormal};
+use statrs::distribution::{Beta as StatsBeta, Normal as StatsNormal, LogNormal as StatsLogNormal, ContinuousCDF};
+use statrs::function::gamma::gamma;
+use super::stackup::{DistributionParams, DistributionType};
+
+/// Fraction of a one-sided distribution allowed beyond the tolerance limit
+/// when deriving default parameters (the same 99.73% as ±3σ)
+pub(crate) const TAIL_FRACTION: f64 = 0.0027;
+
+/// One weighted distribution in a mixture, e.g. one cavity or one supplier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MixtureComponent {
+    pub weight: f64,
+    pub distribution: DistributionParams,
+}
+
+impl DistributionParams {
+    /// Weibull with lower bound `location`
+    pub fn new_weibull(location: f64, shape: f64, scale: f64) -> Self {
+        Self {
+            dist_type: DistributionType::Weibull,
+            min: location,
+            shape: Some(shape),
+            scale: Some(scale),
+            ..Self::new_normal(0.0, 0.0)
+        }
+    }
+
+    /// PERT: a beta distribution fitted to a min, most likely and max value
+    pub fn new_pert(min: f64, max: f64, mode: f64) -> Self {
+        Self {
+            dist_type: DistributionType::Beta,
+            ..Self::new_triangular(min, max, mode)
+        }
+    }
+
+    /// Rayleigh with lower bound `location`, e.g. radial position error
+    pub fn new_rayleigh(location: f64, scale: f64) -> Self {
+        Self {
+            dist_type: DistributionType::Rayleigh,
+            min: location,
+            scale: Some(scale),
+            ..Self::new_normal(0.0, 0.0)
+        }
+    }
+
+    /// Normal(`mean`, `std_dev`) folded up at `fold`, e.g. flatness or
+    /// runout bounded at zero
+    pub fn new_folded_normal(fold: f64, mean: f64, std_dev: f64) -> Self {
+        Self {
+            dist_type: DistributionType::FoldedNormal,
+            min: fold,
+            ..Self::new_normal(mean, std_dev)
+        }
+    }
+
+    /// Normal(`mean`, `std_dev`) with everything outside [min, max] removed,
+    /// e.g. a 100%-inspected part
+    pub fn new_truncated_normal(mean: f64, std_dev: f64, min: f64, max: f64) -> Self {
+        Self {
+            dist_type: DistributionType::TruncatedNormal,
+            min,
+            max,
+            ..Self::new_normal(mean, std_dev)
+        }
+    }
+
+    /// Weighted mixture, e.g. parts from several cavities or suppliers
+    pub fn new_mixture(components: Vec<MixtureComponent>) -> Self {
+        Self {
+            dist_type: DistributionType::Mixture,
+            components,
+            ..Self::new_normal(0.0, 0.0)
+        }
+    }
+
+    /// Parameters shown in the distribution editor, in order
+    pub fn get_required_params(&self) -> Vec<(&'static str, f64)> {
+        match self.dist_type {
+            DistributionType::Normal | DistributionType::LogNormal => vec![
+                ("Mean", self.mean),
+                ("Std Dev", self.std_dev),
+            ],
+            DistributionType::Uniform => vec![
+                ("Min", self.min),
+                ("Max", self.max),
+            ],
+            DistributionType::Triangular | DistributionType::Beta => vec![
+                ("Min", self.min),
+                ("Max", self.max),
+                ("Mode", self.mode.unwrap_or((self.min + self.max) / 2.0)),
+            ],
+            DistributionType::Weibull => vec![
+                ("Location", self.min),
+                ("Shape", self.shape.unwrap_or(2.0)),
+                ("Scale", self.scale.unwrap_or(0.0)),
+            ],
+            DistributionType::Rayleigh => vec![
+                ("Location", self.min),
+                ("Scale", self.scale.unwrap_or(0.0)),
+            ],
+            DistributionType::FoldedNormal => vec![
+                ("Fold", self.min),
+                ("Mean", self.mean),
+                ("Std Dev", self.std_dev),
+            ],
+            DistributionType::TruncatedNormal => vec![
+                ("Mean", self.mean),
+                ("Std Dev", self.std_dev),
+                ("Min", self.min),
+                ("Max", self.max),
+            ],
+            DistributionType::Mixture => self.components.iter()
+                .flat_map(|c| std::iter::once(("Weight", c.weight))
+                    .chain(c.distribution.get_required_params()))
+                .collect(),
+        }
+    }
+
+    /// Set the `index`th entry of `get_required_params`
+    pub fn set_required_param(&mut self, index: usize, value: f64) {
+        match self.dist_type {
+            DistributionType::Normal | DistributionType::LogNormal => match index {
+                0 => self.mean = value,
+                _ => self.std_dev = value,
+            },
+            DistributionType::Uniform => match index {
+                0 => self.min = value,
+                _ => self.max = value,
+            },
+            DistributionType::Triangular | DistributionType::Beta => match index {
+                0 => self.min = value,
+                1 => self.max = value,
+                _ => self.mode = Some(value),
+            },
+            DistributionType::Weibull => match index {
+                0 => self.min = value,
+                1 => self.shape = Some(value),
+                _ => self.scale = Some(value),
+            },
+            DistributionType::Rayleigh => match index {
+                0 => self.min = value,
+                _ => self.scale = Some(value),
+            },
+            DistributionType::FoldedNormal => match index {
+                0 => self.min = value,
+                1 => self.mean = value,
+                _ => self.std_dev = value,
+            },
+            DistributionType::TruncatedNormal => match index {
+                0 => self.mean = value,
+                1 => self.std_dev = value,
+                2 => self.min = value,
+                _ => self.max = value,
+            },
+            DistributionType::Mixture => {
+                let mut index = index;
+                for component in &mut self.components {
+                    let count = 1 + component.distribution.get_required_params().len();
+                    if index < count {
+                        match index {
+                            0 => component.weight = value,
+                            _ => component.distribution.set_required_param(index - 1, value),
+                        }
+                        return;
+                    }
+                    index -= count;
+                }
+            },
+        }
+    }
+
+    /// Whether every parameter the distribution type needs has a value
+    pub fn is_complete(&self) -> bool {
+        self.get_required_params().iter().all(|(_, value)| value.is_finite())
+            && (self.dist_type != DistributionType::Mixture || !self.components.is_empty())
+    }
+
+    /// Mean and standard deviation of the distribution
+    pub fn moments(&self) -> (f64, f64) {
+        match self.dist_type {
+            DistributionType::Normal | DistributionType::LogNormal => (self.mean, self.std_dev),
+            DistributionType::Uniform => (
+                (self.min + self.max) / 2.0,
+                (self.max - self.min) / 12f64.sqrt(),
+            ),
+            DistributionType::Triangular => {
+                let (a, b, c) = (self.min, self.max, self.triangle_mode());
+                let variance = (a * a + b * b + c * c - a * b - a * c - b * c) / 18.0;
+                ((a + b + c) / 3.0, variance.sqrt())
+            },
+            DistributionType::Beta => {
+                let (a, b, c) = (self.min, self.max, self.triangle_mode());
+                let mean = (a + 4.0 * c + b) / 6.0;
+                (mean, ((mean - a) * (b - mean) / 7.0).sqrt())
+            },
+            DistributionType::Weibull => {
+                let (k, lambda) = self.weibull_params();
+                let g1 = gamma(1.0 + 1.0 / k);
+                let g2 = gamma(1.0 + 2.0 / k);
+                (self.min + lambda * g1, lambda * (g2 - g1 * g1).max(0.0).sqrt())
+            },
+            DistributionType::Rayleigh => {
+                let sigma = self.scale.unwrap_or(0.0);
+                (
+                    self.min + sigma * (std::f64::consts::PI / 2.0).sqrt(),
+                    sigma * ((4.0 - std::f64::consts::PI) / 2.0).sqrt(),
+                )
+            },
+            DistributionType::FoldedNormal => {
+                let (mu, sigma) = (self.mean - self.min, self.std_dev);
+                if sigma <= 0.0 {
+                    return (self.min + mu.abs(), 0.0);
+                }
+                let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+                let folded_mean = sigma * (2.0 / std::f64::consts::PI).sqrt() * (-mu * mu / (2.0 * sigma * sigma)).exp()
+                    + mu * (1.0 - 2.0 * standard_normal.cdf(-mu / sigma));
+                let variance = mu * mu + sigma * sigma - folded_mean * folded_mean;
+                (self.min + folded_mean, variance.max(0.0).sqrt())
+            },
+            DistributionType::TruncatedNormal => {
+                if let Some(point) = self.truncated_normal_point() {
+                    return (point, 0.0);
+                }
+                let (mu, sigma) = (self.mean, self.std_dev);
+                let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+                let pdf = |z: f64| (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt();
+                let (alpha, beta) = ((self.min - mu) / sigma, (self.max - mu) / sigma);
+                let z = standard_normal.cdf(beta) - standard_normal.cdf(alpha);
+                // The infinite bounds of a one-sided truncation contribute nothing
+                let term = |x: f64| if x.is_finite() { x * pdf(x) } else { 0.0 };
+                let shift = (pdf(alpha) - pdf(beta)) / z;
+                let variance = sigma * sigma * (1.0 + (term(alpha) - term(beta)) / z - shift * shift);
+                (mu + sigma * shift, variance.max(0.0).sqrt())
+            },
+            DistributionType::Mixture => {
+                let total_weight: f64 = self.components.iter().map(|c| c.weight).sum();
+                if total_weight <= 0.0 {
+                    return (0.0, 0.0);
+                }
+                let (mean, second_moment) = self.components.iter()
+                    .fold((0.0, 0.0), |(mean, second), c| {
+                        let (m, s) = c.distribution.moments();
+                        let w = c.weight / total_weight;
+                        (mean + w * m, second + w * (s * s + m * m))
+                    });
+                (mean, (second_moment - mean * mean).max(0.0).sqrt())
+            },
+        }
+    }
+
+    pub fn sample(&self, rng: &mut StdRng) -> f64 {
+        match self.dist_type {
+            DistributionType::Normal => {
+                let normal = RandNormal::new(self.mean, self.std_dev).unwrap();
+                normal.sample(rng)
+            },
+            DistributionType::Uniform => {
+                let uniform = Uniform::new(self.min, self.max);
+                uniform.sample(rng)
+            },
+            DistributionType::LogNormal => {
+                let (mu, sigma) = self.lognormal_params();
+                let lognormal = LogNormal::new(mu, sigma).unwrap();
+                lognormal.sample(rng)
+            },
+            DistributionType::Beta => {
+                let Some(beta_dist) = self.pert_sampler() else {
+                    return self.triangle_mode();
+                };
+                self.min + (self.max - self.min) * beta_dist.sample(rng)
+            },
+            DistributionType::FoldedNormal => {
+                let normal = RandNormal::new(self.mean - self.min, self.std_dev).unwrap();
+                self.min + normal.sample(rng).abs()
+            },
+            DistributionType::Mixture => {
+                match self.pick_component(rng.gen()) {
+                    Some(component) => component.distribution.sample(rng),
+                    None => 0.0,
+                }
+            },
+            DistributionType::Triangular
+            | DistributionType::Weibull
+            | DistributionType::Rayleigh
+            | DistributionType::TruncatedNormal => self.inverse_cdf(rng.gen()),
+        }
+    }
+
+    /// `count` independent samples, setting the sampler up only once
+    pub fn sample_n(&self, rng: &mut StdRng, count: usize) -> Vec<f64> {
+        match self.dist_type {
+            DistributionType::Normal => {
+                let normal = RandNormal::new(self.mean, self.std_dev).unwrap();
+                normal.sample_iter(rng).take(count).collect()
+            },
+            DistributionType::Uniform => {
+                let uniform = Uniform::new(self.min, self.max);
+                uniform.sample_iter(rng).take(count).collect()
+            },
+            DistributionType::LogNormal => {
+                let (mu, sigma) = self.lognormal_params();
+                let lognormal = LogNormal::new(mu, sigma).unwrap();
+                lognormal.sample_iter(rng).take(count).collect()
+            },
+            DistributionType::Beta => {
+                let Some(beta_dist) = self.pert_sampler() else {
+                    return vec![self.triangle_mode(); count];
+                };
+                beta_dist.sample_iter(rng).take(count)
+                    .map(|x| self.min + (self.max - self.min) * x)
+                    .collect()
+            },
+            _ => (0..count).map(|_| self.sample(rng)).collect(),
+        }
+    }
+
+    /// Fraction of the distribution at or below `x`
+    pub fn cdf(&self, x: f64) -> f64 {
+        let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+        match self.dist_type {
+            DistributionType::Normal => {
+                if self.std_dev <= 0.0 {
+                    return if x >= self.mean { 1.0 } else { 0.0 };
+                }
+                standard_normal.cdf((x - self.mean) / self.std_dev)
+            },
+            DistributionType::Uniform => {
+                if self.max <= self.min {
+                    return if x >= self.min { 1.0 } else { 0.0 };
+                }
+                ((x - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+            },
+            DistributionType::Triangular => {
+                let (a, b, c) = (self.min, self.max, self.triangle_mode());
+                if x <= a {
+                    0.0
+                } else if x >= b {
+                    1.0
+                } else if x <= c {
+                    (x - a).powi(2) / ((b - a) * (c - a))
+                } else {
+                    1.0 - (b - x).powi(2) / ((b - a) * (b - c))
+                }
+            },
+            DistributionType::LogNormal => {
+                let (mu, sigma) = self.lognormal_params();
+                if x <= 0.0 {
+                    0.0
+                } else {
+                    StatsLogNormal::new(mu, sigma).map(|d| d.cdf(x)).unwrap_or(0.0)
+                }
+            },
+            DistributionType::Beta => {
+                if self.max <= self.min {
+                    return if x >= self.min { 1.0 } else { 0.0 };
+                }
+                let (alpha, beta) = self.pert_params();
+                let t = ((x - self.min) / (self.max - self.min)).clamp(0.0, 1.0);
+                StatsBeta::new(alpha, beta).map(|d| d.cdf(t)).unwrap_or(t)
+            },
+            DistributionType::Weibull => {
+                let (k, lambda) = self.weibull_params();
+                if x <= self.min {
+                    0.0
+                } else {
+                    1.0 - (-((x - self.min) / lambda).powf(k)).exp()
+                }
+            },
+            DistributionType::Rayleigh => {
+                let sigma = self.scale.unwrap_or(0.0);
+                if x <= self.min {
+                    0.0
+                } else {
+                    1.0 - (-(x - self.min).powi(2) / (2.0 * sigma * sigma)).exp()
+                }
+            },
+            DistributionType::FoldedNormal => {
+                let y = x - self.min;
+                if y < 0.0 {
+                    return 0.0;
+                }
+                let mu = self.mean - self.min;
+                standard_normal.cdf((y - mu) / self.std_dev) - standard_normal.cdf((-y - mu) / self.std_dev)
+            },
+            DistributionType::TruncatedNormal => {
+                if let Some(point) = self.truncated_normal_point() {
+                    return if x >= point { 1.0 } else { 0.0 };
+                }
+                if x <= self.min {
+                    return 0.0;
+                }
+                if x >= self.max {
+                    return 1.0;
+                }
+                let phi = |v: f64| standard_normal.cdf((v - self.mean) / self.std_dev);
+                let (lower, upper) = (phi(self.min), phi(self.max));
+                ((phi(x) - lower) / (upper - lower)).clamp(0.0, 1.0)
+            },
+            DistributionType::Mixture => {
+                let total_weight: f64 = self.components.iter().map(|c| c.weight).sum();
+                if total_weight <= 0.0 {
+                    return 0.0;
+                }
+                self.components.iter()
+                    .map(|c| c.weight / total_weight * c.distribution.cdf(x))
+                    .sum()
+            },
+        }
+    }
+
+    /// Value below which a fraction `u` of the distribution lies
+    pub fn inverse_cdf(&self, u: f64) -> f64 {
+        let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+        match self.dist_type {
+            DistributionType::Normal => self.mean + self.std_dev * standard_normal.inverse_cdf(u),
+            DistributionType::Uniform => self.min + u * (self.max - self.min),
+            DistributionType::Triangular => {
+                let (a, b, c) = (self.min, self.max, self.triangle_mode());
+                // Cumulative probability at the mode
+                let f_c = (c - a) / (b - a);
+                if u < f_c {
+                    a + (u * (c - a) * (b - a)).sqrt()
+                } else {
+                    b - ((1.0 - u) * (b - c) * (b - a)).sqrt()
+                }
+            },
+            DistributionType::LogNormal => {
+                let (mu, sigma) = self.lognormal_params();
+                (mu + sigma * standard_normal.inverse_cdf(u)).exp()
+            },
+            DistributionType::Beta => {
+                if self.max <= self.min {
+                    return self.min;
+                }
+                let (alpha, beta) = self.pert_params();
+                let t = StatsBeta::new(alpha, beta).map(|d| d.inverse_cdf(u)).unwrap_or(u);
+                self.min + (self.max - self.min) * t
+            },
+            DistributionType::Weibull => {
+                let (k, lambda) = self.weibull_params();
+                self.min + lambda * (-(1.0 - u).ln()).powf(1.0 / k)
+            },
+            DistributionType::Rayleigh => {
+                self.min + self.scale.unwrap_or(0.0) * (-2.0 * (1.0 - u).ln()).sqrt()
+            },
+            DistributionType::TruncatedNormal => {
+                if let Some(point) = self.truncated_normal_point() {
+                    return point;
+                }
+                let phi = |v: f64| standard_normal.cdf((v - self.mean) / self.std_dev);
+                let (lower, upper) = (phi(self.min), phi(self.max));
+                let p = (lower + u * (upper - lower)).clamp(f64::EPSILON, 1.0 - f64::EPSILON);
+                (self.mean + self.std_dev * standard_normal.inverse_cdf(p)).clamp(self.min, self.max)
+            },
+            DistributionType::FoldedNormal | DistributionType::Mixture => {
+                // No closed form; bisect the CDF over a bracket of the support
+                let (mean, std_dev) = self.moments();
+                let mut lo = mean - 10.0 * std_dev;
+                let mut hi = mean + 10.0 * std_dev;
+                if let DistributionType::Mixture = self.dist_type {
+                    for c in &self.components {
+                        let (m, s) = c.distribution.moments();
+                        lo = lo.min(m - 10.0 * s);
+                        hi = hi.max(m + 10.0 * s);
+                    }
+                } else {
+                    lo = lo.max(self.min);
+                }
+                for _ in 0..100 {
+                    let mid = 0.5 * (lo + hi);
+                    if self.cdf(mid) < u {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                0.5 * (lo + hi)
+            },
+        }
+    }
+
+    fn triangle_mode(&self) -> f64 {
+        // Ensure mode is between min and max
+        self.mode.unwrap_or((self.min + self.max) / 2.0).clamp(self.min, self.max)
+    }
+
+    /// Log-space mean and std dev giving the stored (linear) mean and std dev
+    fn lognormal_params(&self) -> (f64, f64) {
+        let variance = (1.0 + (self.std_dev / self.mean).powi(2)).ln();
+        (self.mean.ln() - variance / 2.0, variance.sqrt())
+    }
+
+    fn pert_params(&self) -> (f64, f64) {
+        let range = self.max - self.min;
+        let c = self.triangle_mode();
+        (1.0 + 4.0 * (c - self.min) / range, 1.0 + 4.0 * (self.max - c) / range)
+    }
+
+    /// Beta sampler of a PERT distribution; none when the range is empty
+    fn pert_sampler(&self) -> Option<RandBeta<f64>> {
+        if self.max <= self.min {
+            return None;
+        }
+        let (alpha, beta) = self.pert_params();
+        RandBeta::new(alpha, beta).ok()
+    }
+
+    /// The single value of a truncated normal with no spread, no width or no
+    /// probability between its bounds, the same value `moments` gives
+    fn truncated_normal_point(&self) -> Option<f64> {
+        if self.std_dev <= 0.0 {
+            return Some(self.mean.max(self.min).min(self.max.max(self.min)));
+        }
+        if self.max <= self.min {
+            return Some(self.min);
+        }
+        let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+        let phi = |v: f64| standard_normal.cdf((v - self.mean) / self.std_dev);
+        (phi(self.max) - phi(self.min) <= 0.0).then_some((self.min + self.max) / 2.0)
+    }
+
+    fn weibull_params(&self) -> (f64, f64) {
+        (self.shape.unwrap_or(2.0), self.scale.unwrap_or(0.0))
+    }
+
+    fn pick_component(&self, u: f64) -> Option<&MixtureComponent> {
+        let total_weight: f64 = self.components.iter().map(|c| c.weight).sum();
+        let mut threshold = u * total_weight;
+        for component in &self.components {
+            if threshold < component.weight {
+                return Some(component);
+            }
+            threshold -= component.weight;
+        }
+        self.components.last()
+    }
+}
+
+/// Reads a parameter written either as a plain number or, as older project
+/// files did, as `Some(number)` / `None`. `None` reads as NaN.
+pub(crate) fn number_or_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    struct NumberOrOption;
+
+    impl<'de> Visitor<'de> for NumberOrOption {
+        type Value = f64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number or an optional number")
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<f64, E> {
+            Ok(value)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<f64, E> {
+            Ok(f64::NAN)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<f64, E> {
+            Ok(f64::NAN)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<f64, D::Error> {
+            f64::deserialize(deserializer)
+        }
+    }
+
+    deserializer.deserialize_any(NumberOrOption)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::stackup::{AnalysisMethod, MonteCarloSettings, StackupAnalysis};
+    use crate::config::{Component, Feature, FeatureType};
+
+    #[test]
+    fn test_zero_width_distributions() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let pert = DistributionParams::new_pert(10.0, 10.0, 10.0);
+        assert_eq!(pert.sample(&mut rng), 10.0);
+        assert_eq!(pert.sample_n(&mut rng, 3), vec![10.0; 3]);
+        assert_eq!(pert.inverse_cdf(0.3), 10.0);
+        assert_eq!((pert.cdf(9.9), pert.cdf(10.0)), (0.0, 1.0));
+
+        // No spread at the lower bound, and a truncation with no width
+        for truncated in [
+            DistributionParams::new_truncated_normal(10.0, 0.0, 10.0, 10.5),
+            DistributionParams::new_truncated_normal(10.0, 0.1, 10.0, 10.0),
+        ] {
+            assert_eq!(truncated.inverse_cdf(0.3), 10.0);
+            assert_eq!(truncated.sample(&mut rng), 10.0);
+            assert_eq!((truncated.cdf(9.9), truncated.cdf(10.0)), (0.0, 1.0));
+            assert_eq!(truncated.moments(), (10.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_zero_tolerance_feature_monte_carlo() {
+        for dist_type in [DistributionType::Beta, DistributionType::TruncatedNormal] {
+            let mut fixed = Feature::new("Fixed".to_string(), FeatureType::External, 10.0, 0.0, 0.0);
+            fixed.update_distribution(dist_type);
+            let mut loose = Feature::new("Loose".to_string(), FeatureType::External, 5.0, 0.1, 0.1);
+            loose.update_distribution(dist_type);
+            let components = vec![Component {
+                name: "Part".to_string(),
+                description: None,
+                features: vec![fixed, loose],
+            }];
+
+            let mut analysis = StackupAnalysis::new("Stack".to_string());
+            analysis.methods = vec![AnalysisMethod::MonteCarlo];
+            analysis.monte_carlo_settings = Some(MonteCarloSettings {
+                iterations: 2000,
+                seed: Some(5),
+                ..Default::default()
+            });
+            for feature in ["Fixed", "Loose"] {
+                analysis.add_contribution("Part".to_string(), feature.to_string(), 1.0, false, None);
+            }
+
+            let mc = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+            assert!((mc.mean - 15.0).abs() < 0.01, "{:?} mean {}", dist_type, mc.mean);
+            assert!(mc.min >= 14.9 - 1e-9 && mc.max <= 15.1 + 1e-9);
+        }
+    }
+}
@@ -0,0 +1,631 @@
+// src/analysis/doe.rs
+
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Result};
+use rand::prelude::*;
+use crate::config::{Component, Units};
+use crate::utils::find_feature;
+use super::stackup::{AnalysisMethod, StackupAnalysis};
+use super::study::{set_parameter, StudyParameter};
+use super::job::AnalysisProgress;
+
+/// Most runs a design may have; each one is a full analysis
+pub const MAX_DOE_RUNS: usize = 1_024;
+
+/// One feature parameter in a designed experiment, between two levels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoeFactor {
+    pub component_id: String,
+    pub feature_id: String,
+    pub parameter: StudyParameter,
+    /// Value at coded level -1
+    pub low: f64,
+    /// Value at coded level +1
+    pub high: f64,
+}
+
+impl DoeFactor {
+    pub fn label(&self) -> String {
+        format!("{}.{} {}", self.component_id, self.feature_id, self.parameter.label())
+    }
+
+    /// Actual value at coded level `coded`
+    pub fn actual(&self, coded: f64) -> f64 {
+        (self.low + self.high) / 2.0 + coded * (self.high - self.low) / 2.0
+    }
+
+    /// Coded level of the actual value `actual`
+    pub fn coded(&self, actual: f64) -> f64 {
+        match self.high - self.low {
+            range if range != 0.0 => (2.0 * actual - self.low - self.high) / range,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Distance of the axial points from the center in a central composite design
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum CcdAlpha {
+    /// Axial points on the faces of the cube, within the factor levels
+    FaceCentered,
+    /// (2^k)^¼, for equal prediction variance at equal distance from the
+    /// center; the axial points lie beyond the factor levels
+    Rotatable,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DesignType {
+    /// Every combination of the two levels
+    FullFactorial,
+    /// 2^(k-p) runs, the last `generators` factors set from the highest-order
+    /// interactions of the others
+    FractionalFactorial { generators: usize },
+    /// Full factorial plus axial points; fits curvature
+    CentralComposite { alpha: CcdAlpha },
+}
+
+impl DesignType {
+    pub fn label(&self) -> String {
+        match self {
+            DesignType::FullFactorial => "Full factorial".to_string(),
+            DesignType::FractionalFactorial { generators } => format!("Fractional factorial 2^(k-{})", generators),
+            DesignType::CentralComposite { alpha: CcdAlpha::FaceCentered } => "Central composite, face centered".to_string(),
+            DesignType::CentralComposite { alpha: CcdAlpha::Rotatable } => "Central composite, rotatable".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoeSettings {
+    pub factors: Vec<DoeFactor>,
+    pub design: DesignType,
+    /// Runs added at the center of the design
+    pub center_points: usize,
+    /// Stack mean the robust design aims for; the middle of the spec limits
+    /// when unset, or the predicted mean at the current settings without
+    /// both limits
+    pub target: Option<f64>,
+}
+
+impl Default for DoeSettings {
+    fn default() -> Self {
+        Self {
+            factors: Vec::new(),
+            design: DesignType::FullFactorial,
+            center_points: 1,
+            target: None,
+        }
+    }
+}
+
+/// A term of the response surface, over coded factor levels
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ModelTerm {
+    Intercept,
+    Linear(usize),
+    Interaction(usize, usize),
+    Quadratic(usize),
+}
+
+impl ModelTerm {
+    fn value(&self, coded: &[f64]) -> f64 {
+        match *self {
+            ModelTerm::Intercept => 1.0,
+            ModelTerm::Linear(i) => coded[i],
+            ModelTerm::Interaction(i, j) => coded[i] * coded[j],
+            ModelTerm::Quadratic(i) => coded[i] * coded[i],
+        }
+    }
+
+    /// Name of the term, with `names` for the factors
+    pub fn label(&self, names: &[String]) -> String {
+        match *self {
+            ModelTerm::Intercept => "Intercept".to_string(),
+            ModelTerm::Linear(i) => names[i].clone(),
+            ModelTerm::Interaction(i, j) => format!("{} × {}", names[i], names[j]),
+            ModelTerm::Quadratic(i) => format!("{}²", names[i]),
+        }
+    }
+}
+
+/// Least-squares polynomial in the coded factor levels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseSurface {
+    pub terms: Vec<ModelTerm>,
+    pub coefficients: Vec<f64>,
+    /// Terms the design cannot tell apart from earlier ones, left out
+    pub aliased: Vec<ModelTerm>,
+    pub r_squared: f64,
+}
+
+impl ResponseSurface {
+    /// Fit `terms` to the responses, dropping terms aliased with earlier ones
+    fn fit(terms: &[ModelTerm], coded: &[Vec<f64>], responses: &[f64]) -> Self {
+        let columns: Vec<Vec<f64>> = terms.iter()
+            .map(|term| coded.iter().map(|run| term.value(run)).collect())
+            .collect();
+        let (kept, coefficients) = least_squares(&columns, responses);
+
+        let surface = Self {
+            terms: kept.iter().map(|&i| terms[i]).collect(),
+            coefficients,
+            aliased: (0..terms.len()).filter(|i| !kept.contains(i)).map(|i| terms[i]).collect(),
+            r_squared: 1.0,
+        };
+
+        let mean = responses.iter().sum::<f64>() / responses.len() as f64;
+        let total: f64 = responses.iter().map(|y| (y - mean).powi(2)).sum();
+        let residual: f64 = coded.iter().zip(responses)
+            .map(|(run, y)| (y - surface.predict(run)).powi(2))
+            .sum();
+        Self {
+            r_squared: if total > 0.0 { (1.0 - residual / total).max(0.0) } else { 1.0 },
+            ..surface
+        }
+    }
+
+    /// Predicted response at coded factor levels
+    pub fn predict(&self, coded: &[f64]) -> f64 {
+        self.terms.iter().zip(&self.coefficients)
+            .map(|(term, coefficient)| coefficient * term.value(coded))
+            .sum()
+    }
+}
+
+/// Least squares by modified Gram-Schmidt. Columns that depend on earlier
+/// ones are skipped. Returns the indices of the columns kept and their
+/// coefficients.
+fn least_squares(columns: &[Vec<f64>], y: &[f64]) -> (Vec<usize>, Vec<f64>) {
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+
+    let mut kept = Vec::new();
+    let mut q: Vec<Vec<f64>> = Vec::new();
+    // r[k][j]: projection of kept column j on q[k]
+    let mut r: Vec<Vec<f64>> = Vec::new();
+
+    for (index, column) in columns.iter().enumerate() {
+        let norm = dot(column, column).sqrt();
+        let mut v = column.clone();
+        let mut projections = vec![0.0; q.len()];
+        // Twice over for orthogonality to round-off
+        for _ in 0..2 {
+            for (k, qk) in q.iter().enumerate() {
+                let p = dot(qk, &v);
+                projections[k] += p;
+                v.iter_mut().zip(qk).for_each(|(x, q)| *x -= p * q);
+            }
+        }
+
+        let remaining = dot(&v, &v).sqrt();
+        if norm == 0.0 || remaining <= 1e-9 * norm {
+            continue;
+        }
+        for (k, p) in projections.into_iter().enumerate() {
+            r[k].push(p);
+        }
+        r.push(vec![0.0; kept.len()]);
+        r.last_mut().unwrap().push(remaining);
+        q.push(v.into_iter().map(|x| x / remaining).collect());
+        kept.push(index);
+    }
+
+    // Back substitution of R·β = Qᵀy
+    let qty: Vec<f64> = q.iter().map(|qk| dot(qk, y)).collect();
+    let n = kept.len();
+    let mut beta = vec![0.0; n];
+    for k in (0..n).rev() {
+        let known: f64 = (k + 1..n).map(|j| r[k][j] * beta[j]).sum();
+        beta[k] = (qty[k] - known) / r[k][k];
+    }
+    (kept, beta)
+}
+
+/// One run of the design and the stack it gave
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoeRun {
+    pub coded: Vec<f64>,
+    pub actual: Vec<f64>,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+/// Factor settings that put the stack mean on target with the least variance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustSetting {
+    /// Actual value of every factor; only nominals are moved
+    pub values: Vec<f64>,
+    pub predicted_mean: f64,
+    pub predicted_std_dev: f64,
+    /// The predicted mean reaches the target within the factor levels
+    pub on_target: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoeResult {
+    pub factors: Vec<DoeFactor>,
+    pub design: DesignType,
+    pub runs: Vec<DoeRun>,
+    pub mean_model: ResponseSurface,
+    /// Model of the stack variance
+    pub variance_model: ResponseSurface,
+    pub target: f64,
+    /// None without a nominal among the factors, or when cancelled
+    pub recommendation: Option<RobustSetting>,
+    /// Stopped before every run was made; the models are fitted to the runs
+    /// made
+    pub cancelled: bool,
+}
+
+impl DoeResult {
+    pub fn factor_labels(&self) -> Vec<String> {
+        self.factors.iter().map(|factor| factor.label()).collect()
+    }
+}
+
+/// Number of runs of the design, before the center points; None when it
+/// does not fit in a usize
+fn design_run_count(design: DesignType, k: usize) -> Option<usize> {
+    let factorial = |k: usize| 2usize.checked_pow(u32::try_from(k).ok()?);
+    match design {
+        DesignType::FullFactorial => factorial(k),
+        DesignType::FractionalFactorial { generators } => factorial(k.saturating_sub(generators)),
+        DesignType::CentralComposite { .. } => factorial(k)?.checked_add(2 * k),
+    }
+}
+
+/// Coded runs of the design, before the center points
+fn design_points(design: DesignType, k: usize) -> Result<Vec<Vec<f64>>> {
+    let factorial = |k: usize| -> Vec<Vec<f64>> {
+        // Standard order: the first factor alternates fastest
+        (0..1usize << k)
+            .map(|run| (0..k).map(|j| if run >> j & 1 == 1 { 1.0 } else { -1.0 }).collect())
+            .collect()
+    };
+
+    Ok(match design {
+        DesignType::FullFactorial => factorial(k),
+        DesignType::FractionalFactorial { generators } => {
+            if generators == 0 {
+                return design_points(DesignType::FullFactorial, k);
+            }
+            let base = k.saturating_sub(generators);
+            if base < 2 {
+                bail!("A 2^({}-{}) design leaves fewer than two base factors", k, generators);
+            }
+
+            // Interactions of two or more base factors, highest order first
+            let mut words: Vec<u32> = (1u32..1 << base).filter(|word| word.count_ones() >= 2).collect();
+            words.sort_by_key(|word| (std::cmp::Reverse(word.count_ones()), *word));
+            if words.len() < generators {
+                bail!("A 2^({}-{}) design needs more base factors", k, generators);
+            }
+
+            factorial(base).into_iter()
+                .map(|mut run| {
+                    for word in &words[..generators] {
+                        let product = (0..base)
+                            .filter(|j| word >> j & 1 == 1)
+                            .map(|j| run[j])
+                            .product();
+                        run.push(product);
+                    }
+                    run
+                })
+                .collect()
+        },
+        DesignType::CentralComposite { alpha } => {
+            let alpha = match alpha {
+                CcdAlpha::FaceCentered => 1.0,
+                CcdAlpha::Rotatable => ((1usize << k) as f64).powf(0.25),
+            };
+            let mut runs = factorial(k);
+            for j in 0..k {
+                for sign in [-1.0, 1.0] {
+                    let mut run = vec![0.0; k];
+                    run[j] = sign * alpha;
+                    runs.push(run);
+                }
+            }
+            runs
+        },
+    })
+}
+
+impl StackupAnalysis {
+    /// Run the stack at every point of the designed experiment, fit response
+    /// surfaces to its mean and variance, and find the nominals that keep the
+    /// mean on target with the least variance. The mean and variance come
+    /// from Monte Carlo when the analysis includes it, otherwise from RSS.
+    /// Lengths are in `units`.
+    /// Reports runs to `progress` and stops between runs once it is
+    /// cancelled, fitting the models to the runs made.
+    pub fn run_doe(&self, components: &[Component], settings: &DoeSettings, units: Units, progress: &AnalysisProgress) -> Result<DoeResult> {
+        let k = settings.factors.len();
+        if k == 0 {
+            bail!("The design needs at least one factor");
+        }
+
+        let count = design_run_count(settings.design, k)
+            .and_then(|runs| runs.checked_add(settings.center_points));
+        match count {
+            Some(count) if count <= MAX_DOE_RUNS => {},
+            Some(count) => bail!("The design has {} runs; at most {} can be run", count, MAX_DOE_RUNS),
+            None => bail!("The design has too many runs; at most {} can be run", MAX_DOE_RUNS),
+        }
+
+        let mut coded = design_points(settings.design, k)?;
+        coded.extend((0..settings.center_points).map(|_| vec![0.0; k]));
+
+        let mut analysis = self.clone();
+        let monte_carlo = self.methods.contains(&AnalysisMethod::MonteCarlo) && self.monte_carlo_settings.is_some();
+        analysis.methods = if monte_carlo { vec![AnalysisMethod::MonteCarlo] } else { vec![AnalysisMethod::Rss] };
+
+        progress.start(coded.len());
+        let count = coded.len();
+        let mut runs = Vec::with_capacity(count);
+        for levels in coded {
+            if progress.is_cancelled() {
+                break;
+            }
+
+            let actual: Vec<f64> = settings.factors.iter().zip(&levels)
+                .map(|(factor, level)| factor.actual(*level))
+                .collect();
+            let mut varied = components.to_vec();
+            for (factor, value) in settings.factors.iter().zip(&actual) {
+                set_parameter(&mut varied, &factor.component_id, &factor.feature_id, factor.parameter, *value, units)?;
+            }
+
+            let results = analysis.run_analysis(&varied)?;
+            let (mean, std_dev) = match (&results.monte_carlo, &results.rss) {
+                (Some(mc), _) => (mc.mean, mc.std_dev),
+                (None, Some(rss)) => (rss.mean, rss.std_dev),
+                (None, None) => bail!("Run at {:?} gave no stack mean", actual),
+            };
+            runs.push(DoeRun { coded: levels, actual, mean, std_dev });
+            progress.advance(1);
+        }
+
+        // Quadratic terms only where the design has more than two levels
+        let mut terms = vec![ModelTerm::Intercept];
+        terms.extend((0..k).map(ModelTerm::Linear));
+        terms.extend((0..k).flat_map(|i| (i + 1..k).map(move |j| ModelTerm::Interaction(i, j))));
+        if matches!(settings.design, DesignType::CentralComposite { .. }) {
+            terms.extend((0..k).map(ModelTerm::Quadratic));
+        }
+
+        let levels: Vec<Vec<f64>> = runs.iter().map(|run| run.coded.clone()).collect();
+        let means: Vec<f64> = runs.iter().map(|run| run.mean).collect();
+        let variances: Vec<f64> = runs.iter().map(|run| run.std_dev.powi(2)).collect();
+        let mean_model = ResponseSurface::fit(&terms, &levels, &means);
+        let variance_model = ResponseSurface::fit(&terms, &levels, &variances);
+
+        // Where the factors are now, in coded units
+        let current: Vec<f64> = settings.factors.iter()
+            .map(|factor| {
+                let value = find_feature(components, &factor.component_id, &factor.feature_id)
+                    .map(|feature| factor.parameter.value(feature))
+                    .filter(|value| value.is_finite())
+                    .unwrap_or((factor.low + factor.high) / 2.0);
+                factor.coded(value)
+            })
+            .collect();
+
+        let target = match (settings.target, self.upper_spec_limit, self.lower_spec_limit) {
+            (Some(target), _, _) => target,
+            (None, Some(usl), Some(lsl)) => (usl + lsl) / 2.0,
+            _ => mean_model.predict(&current),
+        };
+
+        let variance_scale = Some(variances.iter().sum::<f64>() / variances.len() as f64)
+            .filter(|scale| *scale > 0.0)
+            .unwrap_or(1.0);
+        let seed = self.monte_carlo_settings.as_ref().and_then(|s| s.seed);
+        let cancelled = runs.len() < count;
+        let recommendation = if cancelled {
+            None
+        } else {
+            robust_setting(&settings.factors, &mean_model, &variance_model, &current, target, variance_scale, seed)
+        };
+
+        Ok(DoeResult {
+            factors: settings.factors.clone(),
+            design: settings.design,
+            runs,
+            mean_model,
+            variance_model,
+            target,
+            recommendation,
+            cancelled,
+        })
+    }
+}
+
+/// Minimize the predicted variance with the predicted mean held at `target`,
+/// moving only the nominal factors within their levels. The constraint is
+/// imposed with a growing quadratic penalty, each stage a compass search
+/// from the best of a set of starting points.
+fn robust_setting(
+    factors: &[DoeFactor],
+    mean_model: &ResponseSurface,
+    variance_model: &ResponseSurface,
+    current: &[f64],
+    target: f64,
+    variance_scale: f64,
+    seed: Option<u64>,
+) -> Option<RobustSetting> {
+    let free: Vec<usize> = (0..factors.len())
+        .filter(|&i| factors[i].parameter == StudyParameter::Nominal)
+        .collect();
+    if free.is_empty() {
+        return None;
+    }
+    let mean_scale = variance_scale.sqrt();
+
+    let at = |x: &[f64]| {
+        let mut coded = current.to_vec();
+        for (i, value) in free.iter().zip(x) {
+            coded[*i] = *value;
+        }
+        coded
+    };
+    let objective = |x: &[f64], penalty: f64| {
+        let coded = at(x);
+        let variance = variance_model.predict(&coded).max(0.0) / variance_scale;
+        let miss = (mean_model.predict(&coded) - target) / mean_scale;
+        variance + penalty * miss * miss
+    };
+
+    // Starting points: a grid over the free factors, or random points when
+    // the grid gets large
+    let mut rng = StdRng::seed_from_u64(seed.unwrap_or(0));
+    let grid = u32::try_from(free.len()).ok()
+        .and_then(|n| 5usize.checked_pow(n))
+        .filter(|points| *points <= 3_125);
+    let starts: Vec<Vec<f64>> = if let Some(points) = grid {
+        (0..points)
+            .map(|index| (0..free.len())
+                .map(|j| (index / 5usize.pow(j as u32) % 5) as f64 / 2.0 - 1.0)
+                .collect())
+            .collect()
+    } else {
+        (0..3_125).map(|_| (0..free.len()).map(|_| rng.gen_range(-1.0..=1.0)).collect()).collect()
+    };
+
+    let mut x = starts.into_iter()
+        .min_by(|a, b| objective(a, 1e2).total_cmp(&objective(b, 1e2)))?;
+    for penalty in [1e2, 1e4, 1e6, 1e8] {
+        let mut step = 0.25;
+        let mut best = objective(&x, penalty);
+        while step > 1e-7 {
+            let mut improved = false;
+            for j in 0..x.len() {
+                for sign in [-1.0, 1.0] {
+                    let mut trial = x.clone();
+                    trial[j] = (trial[j] + sign * step).clamp(-1.0, 1.0);
+                    let value = objective(&trial, penalty);
+                    if value < best {
+                        best = value;
+                        x = trial;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                step /= 2.0;
+            }
+        }
+    }
+
+    let coded = at(&x);
+    let predicted_mean = mean_model.predict(&coded);
+    Some(RobustSetting {
+        values: factors.iter().zip(&coded).map(|(factor, level)| factor.actual(*level)).collect(),
+        predicted_mean,
+        predicted_std_dev: variance_model.predict(&coded).max(0.0).sqrt(),
+        on_target: (predicted_mean - target).abs() <= 1e-3 * mean_scale,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_support::stack_of;
+
+    #[test]
+    fn test_designed_experiment() {
+        let factor = |feature: &str, low, high| DoeFactor {
+            component_id: "Part".to_string(),
+            feature_id: feature.to_string(),
+            parameter: StudyParameter::Nominal,
+            low,
+            high,
+        };
+        let run = |analysis: &StackupAnalysis, components: &[Component], settings: &DoeSettings| {
+            analysis.run_doe(components, settings, Units::Metric, &AnalysisProgress::default()).unwrap()
+        };
+
+        // Linear stack: the mean surface is the sum of the nominals
+        let (components, mut analysis) = stack_of(&[0.1, 0.1, 0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::Rss];
+        let mut settings = DoeSettings {
+            factors: vec![factor("F0", 9.0, 11.0), factor("F1", 9.0, 11.0), factor("F2", 9.0, 11.0)],
+            ..Default::default()
+        };
+        let result = run(&analysis, &components, &settings);
+        assert_eq!(result.runs.len(), 9);
+        assert!(result.mean_model.r_squared > 1.0 - 1e-12);
+        assert!((result.mean_model.predict(&[1.0, -1.0, 1.0]) - 41.0).abs() < 1e-9);
+
+        // Half fraction: the fourth factor is set from the three-factor interaction
+        settings.factors.push(factor("F3", 9.0, 11.0));
+        settings.design = DesignType::FractionalFactorial { generators: 1 };
+        settings.center_points = 0;
+        let result = run(&analysis, &components, &settings);
+        assert_eq!(result.runs.len(), 8);
+        assert!(result.runs.iter().all(|run| run.coded[3] == run.coded[0] * run.coded[1] * run.coded[2]));
+        assert!(!result.mean_model.aliased.is_empty());
+
+        // a·b with equal tolerances: on target ab = 100 the variance
+        // σ²(a² + b²) is least at a = b = 10
+        let (mut components, mut analysis) = stack_of(&[0.1, 0.1]);
+        components[0].features[0].dimension.value = 8.0;
+        components[0].features[1].dimension.value = 12.0;
+        for (contrib, name) in analysis.contributions.iter_mut().zip(["a", "b"]) {
+            contrib.variable = Some(name.to_string());
+        }
+        analysis.expression = Some("a*b".to_string());
+        analysis.methods = vec![AnalysisMethod::Rss];
+        let settings = DoeSettings {
+            factors: vec![factor("F0", 5.0, 15.0), factor("F1", 5.0, 15.0)],
+            design: DesignType::CentralComposite { alpha: CcdAlpha::FaceCentered },
+            center_points: 1,
+            target: Some(100.0),
+        };
+        let result = run(&analysis, &components, &settings);
+        assert_eq!(result.runs.len(), 9);
+        assert!(result.variance_model.r_squared > 1.0 - 1e-9);
+        let robust = result.recommendation.unwrap();
+        assert!(robust.on_target);
+        assert!((robust.values[0] - 10.0).abs() < 1e-3 && (robust.values[1] - 10.0).abs() < 1e-3);
+        assert!((robust.predicted_std_dev - 0.1 / 3.0 * 200f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_designed_experiment_limits() {
+        let (components, mut analysis) = stack_of(&[0.1; 70]);
+        analysis.methods = vec![AnalysisMethod::Rss];
+        let factors = |count: usize| (0..count)
+            .map(|i| DoeFactor {
+                component_id: "Part".to_string(),
+                feature_id: format!("F{}", i),
+                parameter: StudyParameter::Nominal,
+                low: 9.0,
+                high: 11.0,
+            })
+            .collect::<Vec<_>>();
+
+        // Too many runs to count, let alone build
+        let settings = DoeSettings { factors: factors(70), ..Default::default() };
+        let error = analysis.run_doe(&components, &settings, Units::Metric, &AnalysisProgress::default()).unwrap_err();
+        assert!(error.to_string().contains("too many runs"), "{}", error);
+
+        // A 2^(30-25) fraction is small, with too many nominals for a grid of
+        // starting points
+        let settings = DoeSettings {
+            factors: factors(30),
+            design: DesignType::FractionalFactorial { generators: 25 },
+            center_points: 0,
+            target: None,
+        };
+        let result = analysis.run_doe(&components, &settings, Units::Metric, &AnalysisProgress::default()).unwrap();
+        assert_eq!(result.runs.len(), 32);
+        assert!(!result.cancelled && result.recommendation.is_some());
+
+        // Cancelled designs keep the runs made, with no recommendation
+        let progress = AnalysisProgress::default();
+        progress.cancel();
+        let result = analysis.run_doe(&components, &settings, Units::Metric, &progress).unwrap();
+        assert!(result.cancelled && result.runs.is_empty() && result.recommendation.is_none());
+    }
+}
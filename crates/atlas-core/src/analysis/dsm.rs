@@ -0,0 +1,118 @@
+// src/analysis/dsm.rs
+
+/// Rows of a design structure matrix reordered so that strongly coupled
+/// items sit next to each other, with the blocks they form
+#[derive(Debug, Clone, PartialEq)]
+pub struct DsmClustering {
+    /// Original index of each row, in display order
+    pub order: Vec<usize>,
+    /// Clusters as ranges of `order`, in display order; items coupled to
+    /// nothing are clusters of one
+    pub clusters: Vec<std::ops::Range<usize>>,
+}
+
+/// Agglomerative clustering of a square matrix of coupling strengths (such
+/// as mate counts), read symmetrically. The two clusters with the strongest
+/// average coupling are merged, end to end the way round that puts their
+/// most strongly coupled members together, until no pair is coupled by at
+/// least `min_coupling` on average. Clusters are listed by their first
+/// original row.
+pub fn cluster(weights: &[Vec<f64>], min_coupling: f64) -> DsmClustering {
+    let n = weights.len();
+    let weight = |i: usize, j: usize| {
+        weights[i].get(j).copied().unwrap_or(0.0) + weights[j].get(i).copied().unwrap_or(0.0)
+    };
+
+    let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    // Total coupling between clusters
+    let mut coupling: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 0.0 } else { weight(i, j) }).collect())
+        .collect();
+    let mut alive = vec![true; n];
+
+    loop {
+        let mut best: Option<(usize, usize, f64)> = None;
+        for a in (0..n).filter(|a| alive[*a]) {
+            for b in (a + 1..n).filter(|b| alive[*b]) {
+                let average = coupling[a][b] / (members[a].len() * members[b].len()) as f64;
+                if average > 0.0 && average >= min_coupling && best.is_none_or(|(_, _, s)| average > s) {
+                    best = Some((a, b, average));
+                }
+            }
+        }
+        let Some((a, b, _)) = best else { break };
+
+        let mut first = std::mem::take(&mut members[a]);
+        let mut second = std::mem::take(&mut members[b]);
+        let (first_head, first_tail) = (first[0], first[first.len() - 1]);
+        let (second_head, second_tail) = (second[0], second[second.len() - 1]);
+        let joins = [
+            weight(first_tail, second_head),
+            weight(first_tail, second_tail),
+            weight(first_head, second_head),
+            weight(first_head, second_tail),
+        ];
+        let strongest = (0..4).fold(0, |best, i| if joins[i] > joins[best] { i } else { best });
+        match strongest {
+            1 => second.reverse(),
+            2 => first.reverse(),
+            3 => std::mem::swap(&mut first, &mut second),
+            _ => {},
+        }
+        first.extend(second);
+        members[a] = first;
+
+        alive[b] = false;
+        let merged: Vec<f64> = (0..n).map(|c| coupling[a][c] + coupling[b][c]).collect();
+        for (row, total) in coupling.iter_mut().zip(&merged) {
+            row[a] = *total;
+        }
+        coupling[a] = merged;
+        coupling[a][a] = 0.0;
+    }
+
+    let mut groups: Vec<Vec<usize>> = members.into_iter().filter(|m| !m.is_empty()).collect();
+    groups.sort_by_key(|m| m.iter().copied().min());
+
+    let mut order = Vec::with_capacity(n);
+    let mut clusters = Vec::with_capacity(groups.len());
+    for group in groups {
+        let start = order.len();
+        order.extend(group);
+        clusters.push(start..order.len());
+    }
+    DsmClustering { order, clusters }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dsm_clustering() {
+        // Two strongly mated pairs joined by a single mate, and a loose pair;
+        // either triangle of the matrix counts and the diagonal does not
+        let mut weights = vec![vec![0.0; 6]; 6];
+        weights[0][3] = 2.0;
+        weights[4][1] = 2.0;
+        weights[3][4] = 1.0;
+        weights[2][5] = 1.0;
+        weights[2][2] = 5.0;
+
+        let tight = cluster(&weights, 0.5);
+        assert_eq!(tight.order, vec![0, 3, 1, 4, 2, 5]);
+        assert_eq!(tight.clusters, vec![0..2, 2..4, 4..6]);
+
+        // Looser blocks join the pairs end to end through their shared mate
+        let loose = cluster(&weights, 0.1);
+        assert_eq!(loose.order, vec![0, 3, 4, 1, 2, 5]);
+        assert_eq!(loose.clusters, vec![0..4, 4..6]);
+
+        // Items coupled to nothing stay on their own
+        let mut isolated = weights.clone();
+        isolated.push(vec![0.0; 7]);
+        let clustering = cluster(&isolated, 0.0);
+        assert_eq!(clustering.clusters.last(), Some(&(6..7)));
+        assert!(cluster(&[], 0.0).order.is_empty());
+    }
+}
@@ -0,0 +1,477 @@
+// src/analysis/expression.rs
+
+use std::f64::consts::{FRAC_PI_2, PI};
+use anyhow::{anyhow, bail, Result};
+
+/// Closed interval of values, used for worst-case evaluation of an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    pub fn new(lo: f64, hi: f64) -> Self {
+        Self { lo: lo.min(hi), hi: lo.max(hi) }
+    }
+
+    pub fn point(value: f64) -> Self {
+        Self { lo: value, hi: value }
+    }
+
+    fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// Whether `offset + k·period` lies inside the interval for some integer k
+    fn contains_periodic(&self, offset: f64, period: f64) -> bool {
+        ((self.lo - offset) / period).ceil() <= ((self.hi - offset) / period).floor()
+    }
+
+    fn map_increasing(&self, f: impl Fn(f64) -> f64) -> Self {
+        Self { lo: f(self.lo), hi: f(self.hi) }
+    }
+
+    fn mul(&self, other: &Interval) -> Self {
+        let products = [
+            self.lo * other.lo,
+            self.lo * other.hi,
+            self.hi * other.lo,
+            self.hi * other.hi,
+        ];
+        Self {
+            lo: products.iter().copied().fold(f64::INFINITY, f64::min),
+            hi: products.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+
+    fn div(&self, other: &Interval) -> Result<Self> {
+        if other.contains(0.0) {
+            bail!("division by a value that can be zero");
+        }
+        Ok(self.mul(&Interval::new(1.0 / other.hi, 1.0 / other.lo)))
+    }
+
+    fn powi(&self, n: i32) -> Result<Self> {
+        if n < 0 {
+            return Interval::point(1.0).div(&self.powi(-n)?);
+        }
+        if n % 2 == 1 || self.lo >= 0.0 {
+            Ok(self.map_increasing(|x| x.powi(n)))
+        } else if self.hi <= 0.0 {
+            Ok(Interval::new(self.hi.powi(n), self.lo.powi(n)))
+        } else {
+            Ok(Interval::new(0.0, self.lo.powi(n).max(self.hi.powi(n))))
+        }
+    }
+
+    fn sin(&self) -> Self {
+        if self.hi - self.lo >= 2.0 * PI {
+            return Interval::new(-1.0, 1.0);
+        }
+        let mut result = Interval::new(self.lo.sin(), self.hi.sin());
+        if self.contains_periodic(FRAC_PI_2, 2.0 * PI) {
+            result.hi = 1.0;
+        }
+        if self.contains_periodic(-FRAC_PI_2, 2.0 * PI) {
+            result.lo = -1.0;
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sqrt,
+    Abs,
+    Exp,
+    Ln,
+    Min,
+    Max,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sin" => Function::Sin,
+            "cos" => Function::Cos,
+            "tan" => Function::Tan,
+            "asin" => Function::Asin,
+            "acos" => Function::Acos,
+            "atan" => Function::Atan,
+            "sqrt" => Function::Sqrt,
+            "abs" => Function::Abs,
+            "exp" => Function::Exp,
+            "ln" => Function::Ln,
+            "min" => Function::Min,
+            "max" => Function::Max,
+            _ => return None,
+        })
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Function::Min | Function::Max => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Stack function parsed from text such as `a + b*cos(theta) - d/2`.
+///
+/// Supports `+ - * / ^`, parentheses, the constant `pi` and the functions
+/// sin, cos, tan, asin, acos, atan (radians), sqrt, abs, exp, ln, min and max.
+/// Variables are resolved to indices into the value slice when parsing.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Number(f64),
+    Variable(usize),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    /// Parse `source`, looking variable names up with `variable_index`
+    pub fn parse(source: &str, variable_index: impl Fn(&str) -> Option<usize>) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0, variable_index: &variable_index };
+        let expr = parser.expression()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected '{}' in stack expression", token);
+        }
+        Ok(expr)
+    }
+
+    pub fn evaluate(&self, values: &[f64]) -> f64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::Variable(i) => values[*i],
+            Expr::Neg(e) => -e.evaluate(values),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.evaluate(values), b.evaluate(values));
+                match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Pow => a.powf(b),
+                }
+            },
+            Expr::Call(function, args) => {
+                let x = args[0].evaluate(values);
+                match function {
+                    Function::Sin => x.sin(),
+                    Function::Cos => x.cos(),
+                    Function::Tan => x.tan(),
+                    Function::Asin => x.asin(),
+                    Function::Acos => x.acos(),
+                    Function::Atan => x.atan(),
+                    Function::Sqrt => x.sqrt(),
+                    Function::Abs => x.abs(),
+                    Function::Exp => x.exp(),
+                    Function::Ln => x.ln(),
+                    Function::Min => x.min(args[1].evaluate(values)),
+                    Function::Max => x.max(args[1].evaluate(values)),
+                }
+            },
+        }
+    }
+
+    /// Bounds of the expression when each variable ranges over its interval.
+    /// The bounds are guaranteed but may be wider than the true range when a
+    /// variable appears more than once.
+    pub fn evaluate_interval(&self, intervals: &[Interval]) -> Result<Interval> {
+        Ok(match self {
+            Expr::Number(n) => Interval::point(*n),
+            Expr::Variable(i) => intervals[*i],
+            Expr::Neg(e) => {
+                let x = e.evaluate_interval(intervals)?;
+                Interval::new(-x.hi, -x.lo)
+            },
+            Expr::Binary(op, a, b) => {
+                let x = a.evaluate_interval(intervals)?;
+                match op {
+                    BinaryOp::Pow => return Self::interval_pow(&x, b, intervals),
+                    _ => {
+                        let y = b.evaluate_interval(intervals)?;
+                        match op {
+                            BinaryOp::Add => Interval::new(x.lo + y.lo, x.hi + y.hi),
+                            BinaryOp::Sub => Interval::new(x.lo - y.hi, x.hi - y.lo),
+                            BinaryOp::Mul => x.mul(&y),
+                            BinaryOp::Div => x.div(&y)?,
+                            BinaryOp::Pow => unreachable!(),
+                        }
+                    }
+                }
+            },
+            Expr::Call(function, args) => {
+                let x = args[0].evaluate_interval(intervals)?;
+                match function {
+                    Function::Sin => x.sin(),
+                    Function::Cos => Interval::new(x.lo + FRAC_PI_2, x.hi + FRAC_PI_2).sin(),
+                    Function::Tan => {
+                        if x.hi - x.lo >= PI || x.contains_periodic(FRAC_PI_2, PI) {
+                            bail!("tan is unbounded over the tolerance zone");
+                        }
+                        x.map_increasing(f64::tan)
+                    },
+                    Function::Asin | Function::Acos => {
+                        if x.lo < -1.0 || x.hi > 1.0 {
+                            bail!("{:?} argument leaves [-1, 1] within the tolerance zone", function);
+                        }
+                        if *function == Function::Asin {
+                            x.map_increasing(f64::asin)
+                        } else {
+                            Interval::new(x.hi.acos(), x.lo.acos())
+                        }
+                    },
+                    Function::Atan => x.map_increasing(f64::atan),
+                    Function::Sqrt => {
+                        if x.lo < 0.0 {
+                            bail!("sqrt of a value that can be negative");
+                        }
+                        x.map_increasing(f64::sqrt)
+                    },
+                    Function::Abs => {
+                        if x.contains(0.0) {
+                            Interval::new(0.0, x.lo.abs().max(x.hi.abs()))
+                        } else {
+                            Interval::new(x.lo.abs(), x.hi.abs())
+                        }
+                    },
+                    Function::Exp => x.map_increasing(f64::exp),
+                    Function::Ln => {
+                        if x.lo <= 0.0 {
+                            bail!("ln of a value that can be zero or negative");
+                        }
+                        x.map_increasing(f64::ln)
+                    },
+                    Function::Min | Function::Max => {
+                        let y = args[1].evaluate_interval(intervals)?;
+                        if *function == Function::Min {
+                            Interval::new(x.lo.min(y.lo), x.hi.min(y.hi))
+                        } else {
+                            Interval::new(x.lo.max(y.lo), x.hi.max(y.hi))
+                        }
+                    },
+                }
+            },
+        })
+    }
+
+    fn interval_pow(base: &Interval, exponent: &Expr, intervals: &[Interval]) -> Result<Interval> {
+        let exp = exponent.evaluate_interval(intervals)?;
+        if exp.lo == exp.hi && exp.lo.fract() == 0.0 {
+            return base.powi(exp.lo as i32);
+        }
+        if base.lo <= 0.0 {
+            bail!("non-integer power of a value that can be zero or negative");
+        }
+        // x^y = exp(y·ln x), monotone in both arguments once the base is positive
+        let ln_base = base.map_increasing(f64::ln);
+        Ok(ln_base.mul(&exp).map_increasing(f64::exp))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Op(c) => write!(f, "{}", c),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent, e.g. 1.5e-3
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse()
+                .map_err(|_| anyhow!("Invalid number '{}' in stack expression", text))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(match c {
+                '+' | '-' | '*' | '/' | '^' => Token::Op(c),
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                _ => bail!("Unexpected character '{}' in stack expression", c),
+            });
+            i += 1;
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    variable_index: &'a dyn Fn(&str) -> Option<usize>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => bail!("Expected '{}' but found '{}' in stack expression", expected, token),
+            None => bail!("Expected '{}' at end of stack expression", expected),
+        }
+    }
+
+    // expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<Expr> {
+        let mut lhs = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            let op = if op == '+' { BinaryOp::Add } else { BinaryOp::Sub };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            let op = if op == '*' { BinaryOp::Mul } else { BinaryOp::Div };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            },
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            },
+            _ => self.power(),
+        }
+    }
+
+    // power := primary ('^' unary)?, right associative
+    fn power(&mut self) -> Result<Expr> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::LParen) => {
+                let expr = self.expression()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            },
+            Some(Token::Ident(name)) => {
+                if let Some(Token::LParen) = self.peek() {
+                    let function = Function::from_name(&name)
+                        .ok_or_else(|| anyhow!("Unknown function '{}' in stack expression", name))?;
+                    self.pos += 1;
+                    let mut args = vec![self.expression()?];
+                    while let Some(Token::Comma) = self.peek() {
+                        self.pos += 1;
+                        args.push(self.expression()?);
+                    }
+                    self.expect(Token::RParen)?;
+                    if args.len() != function.arity() {
+                        bail!("{}() takes {} argument(s), got {}", name, function.arity(), args.len());
+                    }
+                    Ok(Expr::Call(function, args))
+                } else if name == "pi" {
+                    Ok(Expr::Number(PI))
+                } else {
+                    (self.variable_index)(&name)
+                        .map(Expr::Variable)
+                        .ok_or_else(|| anyhow!("Unknown variable '{}' in stack expression", name))
+                }
+            },
+            Some(token) => bail!("Unexpected '{}' in stack expression", token),
+            None => bail!("Stack expression ended unexpectedly"),
+        }
+    }
+}
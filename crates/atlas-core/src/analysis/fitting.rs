@@ -0,0 +1,297 @@
+// src/analysis/fitting.rs
+
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Result};
+use std::f64::consts::PI;
+use crate::config::Feature;
+use super::stackup::{cpk, DistributionParams, DistributionType};
+
+/// Fewest measurements distributions are fitted to
+pub const MIN_FIT_SAMPLES: usize = 5;
+
+/// A candidate distribution fitted to measurements by maximum likelihood,
+/// with its goodness of fit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionFit {
+    pub params: DistributionParams,
+    pub log_likelihood: f64,
+    /// Anderson-Darling A²; smaller is better, weighting the tails
+    pub anderson_darling: f64,
+    /// Kolmogorov-Smirnov D, the largest gap between the fitted and
+    /// empirical CDFs
+    pub ks_statistic: f64,
+    /// Asymptotic KS p-value; optimistic, since the parameters were fitted
+    /// to the same data
+    pub ks_p_value: f64,
+}
+
+impl DistributionFit {
+    pub fn label(&self) -> &'static str {
+        match self.params.dist_type {
+            DistributionType::Normal => "Normal",
+            DistributionType::LogNormal => "Lognormal",
+            DistributionType::Uniform => "Uniform",
+            DistributionType::Weibull => "Weibull (3-parameter)",
+            _ => "Other",
+        }
+    }
+}
+
+/// Normal, lognormal, uniform and three-parameter Weibull fitted to `data`,
+/// best first by Anderson-Darling. Families that cannot describe the data,
+/// such as lognormal for values at or below zero, are left out.
+pub fn fit_distributions(data: &[f64]) -> Result<Vec<DistributionFit>> {
+    if data.len() < MIN_FIT_SAMPLES {
+        bail!("At least {} measurements are needed to fit a distribution; there are {}", MIN_FIT_SAMPLES, data.len());
+    }
+    if data.iter().any(|x| !x.is_finite()) {
+        bail!("The measurements include values that are not numbers");
+    }
+
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    if sorted[0] == sorted[sorted.len() - 1] {
+        bail!("Every measurement is {}; there is no spread to fit", sorted[0]);
+    }
+
+    let mut fits: Vec<DistributionFit> = [fit_normal(&sorted), fit_lognormal(&sorted), fit_uniform(&sorted), fit_weibull(&sorted)]
+        .into_iter()
+        .flatten()
+        .map(|(params, log_likelihood)| goodness_of_fit(&sorted, params, log_likelihood))
+        .collect();
+    fits.sort_by(|a, b| a.anderson_darling.total_cmp(&b.anderson_darling));
+    Ok(fits)
+}
+
+fn mean_and_std_dev(data: &[f64]) -> (f64, f64) {
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+fn fit_normal(data: &[f64]) -> Option<(DistributionParams, f64)> {
+    let n = data.len() as f64;
+    let (mean, std_dev) = mean_and_std_dev(data);
+    let log_likelihood = -n / 2.0 * (2.0 * PI * std_dev * std_dev).ln() - n / 2.0;
+    Some((DistributionParams::new_normal(mean, std_dev), log_likelihood))
+}
+
+/// Normal fit to the logs, stored as the mean and std dev of the values
+fn fit_lognormal(data: &[f64]) -> Option<(DistributionParams, f64)> {
+    if data[0] <= 0.0 {
+        return None;
+    }
+    let n = data.len() as f64;
+    let logs: Vec<f64> = data.iter().map(|x| x.ln()).collect();
+    let (mu, sigma) = mean_and_std_dev(&logs);
+    let log_likelihood = -logs.iter().sum::<f64>() - n / 2.0 * (2.0 * PI * sigma * sigma).ln() - n / 2.0;
+
+    let mean = (mu + sigma * sigma / 2.0).exp();
+    let std_dev = mean * (sigma * sigma).exp_m1().sqrt();
+    Some((DistributionParams::new_lognormal(mean, std_dev), log_likelihood))
+}
+
+/// The maximum likelihood range is the sample range, which puts the extreme
+/// measurements at CDF 0 and 1; it is widened by a gap each way to the
+/// unbiased estimate
+fn fit_uniform(data: &[f64]) -> Option<(DistributionParams, f64)> {
+    let n = data.len();
+    let (low, high) = (data[0], data[n - 1]);
+    let gap = (high - low) / (n - 1) as f64;
+    let (min, max) = (low - gap, high + gap);
+    Some((DistributionParams::new_uniform(min, max), -(n as f64) * (max - min).ln()))
+}
+
+/// Shape and scale by maximum likelihood for each of a range of locations
+/// below the smallest measurement, keeping the most likely
+fn fit_weibull(data: &[f64]) -> Option<(DistributionParams, f64)> {
+    let range = data[data.len() - 1] - data[0];
+    (0..=40)
+        .filter_map(|i| {
+            let location = data[0] - range * 10f64.powf(-3.0 + i as f64 / 10.0);
+            fit_weibull_at(data, location).map(|(shape, scale, ll)| {
+                (DistributionParams::new_weibull(location, shape, scale), ll)
+            })
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Two-parameter Weibull fit to `data - location`: the shape solves the
+/// likelihood equation by bisection, the scale follows from it
+fn fit_weibull_at(data: &[f64], location: f64) -> Option<(f64, f64, f64)> {
+    let y: Vec<f64> = data.iter().map(|x| x - location).collect();
+    let n = y.len() as f64;
+    // Scaled to at most 1 so y^k does not overflow for large shapes
+    let top = y.iter().cloned().fold(0.0, f64::max);
+    let z: Vec<f64> = y.iter().map(|v| v / top).collect();
+    let mean_log = z.iter().map(|v| v.ln()).sum::<f64>() / n;
+
+    // Increasing in k; its root is the shape
+    let equation = |k: f64| {
+        let (sum, weighted) = z.iter().fold((0.0, 0.0), |(s, w), v| {
+            let p = v.powf(k);
+            (s + p, w + p * v.ln())
+        });
+        weighted / sum - 1.0 / k - mean_log
+    };
+
+    let (mut low, mut high) = (0.05f64, 2_000.0f64);
+    if equation(low) > 0.0 || equation(high) < 0.0 {
+        return None;
+    }
+    for _ in 0..100 {
+        let middle = (low * high).sqrt();
+        if equation(middle) < 0.0 { low = middle } else { high = middle }
+    }
+    let shape = (low * high).sqrt();
+    let scale = top * (z.iter().map(|v| v.powf(shape)).sum::<f64>() / n).powf(1.0 / shape);
+
+    let log_likelihood = y.iter()
+        .map(|v| shape.ln() - scale.ln() + (shape - 1.0) * (v / scale).ln() - (v / scale).powf(shape))
+        .sum::<f64>();
+    log_likelihood.is_finite().then_some((shape, scale, log_likelihood))
+}
+
+/// Anderson-Darling and Kolmogorov-Smirnov statistics of `params` against
+/// the sorted data
+fn goodness_of_fit(sorted: &[f64], params: DistributionParams, log_likelihood: f64) -> DistributionFit {
+    let n = sorted.len();
+    let cdf: Vec<f64> = sorted.iter()
+        .map(|x| params.cdf(*x).clamp(1e-300, 1.0 - 1e-16))
+        .collect();
+
+    let sum: f64 = (0..n)
+        .map(|i| (2 * i + 1) as f64 * (cdf[i].ln() + (1.0 - cdf[n - 1 - i]).ln()))
+        .sum();
+    let anderson_darling = -(n as f64) - sum / n as f64;
+
+    let ks_statistic = cdf.iter().enumerate()
+        .map(|(i, f)| ((i + 1) as f64 / n as f64 - f).max(f - i as f64 / n as f64))
+        .fold(0.0, f64::max);
+
+    DistributionFit {
+        params,
+        log_likelihood,
+        anderson_darling,
+        ks_statistic,
+        ks_p_value: kolmogorov_p_value(ks_statistic, n),
+    }
+}
+
+/// P(D ≥ d) for n samples, from the Kolmogorov distribution with Stephens'
+/// small-sample correction
+fn kolmogorov_p_value(d: f64, n: usize) -> f64 {
+    let root_n = (n as f64).sqrt();
+    let lambda = (root_n + 0.12 + 0.11 / root_n) * d;
+    if lambda < 0.2 {
+        // The series converges too slowly here; the tail is all but 1
+        return 1.0;
+    }
+    let sum: f64 = (1..=100)
+        .map(|k| {
+            let k = k as f64;
+            let sign = if k as u64 % 2 == 1 { 1.0 } else { -1.0 };
+            sign * (-2.0 * k * k * lambda * lambda).exp()
+        })
+        .sum();
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+/// Points of a probability plot: each measurement against the fitted
+/// distribution's quantile at its median rank. A good fit lies on the
+/// line y = x.
+pub fn probability_plot(data: &[f64], params: &DistributionParams) -> Vec<[f64; 2]> {
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+
+    sorted.iter().enumerate()
+        .map(|(i, x)| {
+            // Benard's approximation to the median rank
+            let p = (i as f64 + 1.0 - 0.3) / (n + 0.4);
+            [params.inverse_cdf(p), *x]
+        })
+        .collect()
+}
+
+/// How the measured parts sit in the drawing tolerance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MeasuredCapability {
+    pub count: usize,
+    pub mean: f64,
+    /// Sample standard deviation
+    pub std_dev: f64,
+    pub cp: f64,
+    pub cpk: f64,
+    /// Measurements outside the tolerance
+    pub out_of_tolerance: usize,
+}
+
+/// Cp and Cpk of the feature's measurements against its tolerance limits.
+/// None with fewer than two measurements or no spread.
+pub fn measured_capability(feature: &Feature) -> Option<MeasuredCapability> {
+    let data = &feature.measurements;
+    let n = data.len();
+    if n < 2 {
+        return None;
+    }
+    let mean = data.iter().sum::<f64>() / n as f64;
+    let std_dev = (data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt();
+    if std_dev <= 0.0 {
+        return None;
+    }
+
+    let upper = feature.dimension.value + feature.dimension.plus_tolerance;
+    let lower = feature.dimension.value - feature.dimension.minus_tolerance;
+    Some(MeasuredCapability {
+        count: n,
+        mean,
+        std_dev,
+        cp: (upper - lower) / (6.0 * std_dev),
+        cpk: cpk(mean, std_dev, Some(upper), Some(lower))?,
+        out_of_tolerance: data.iter().filter(|x| **x > upper || **x < lower).count(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use crate::config::FeatureType;
+
+    #[test]
+    fn test_measurement_fitting() {
+        let mut rng = StdRng::seed_from_u64(11);
+        // Deviations from nominal, so lognormal cannot stand in for normal
+        let normal: Vec<f64> = DistributionParams::new_normal(0.0, 0.02).sample_n(&mut rng, 500);
+        let uniform: Vec<f64> = DistributionParams::new_uniform(4.9, 5.1).sample_n(&mut rng, 500);
+
+        // Each sample is best described by the family it came from
+        let fits = fit_distributions(&normal).unwrap();
+        assert_eq!(fits[0].params.dist_type, DistributionType::Normal);
+        assert!(fits[0].params.mean.abs() < 0.005);
+        assert!((fits[0].params.std_dev / 0.02 - 1.0).abs() < 0.1);
+        assert!(fits[0].ks_p_value > 0.01);
+        let uniform_fit = fits.iter().find(|f| f.params.dist_type == DistributionType::Uniform).unwrap();
+        assert!(uniform_fit.ks_p_value < 1e-3);
+
+        let fits = fit_distributions(&uniform).unwrap();
+        assert_eq!(fits[0].params.dist_type, DistributionType::Uniform);
+        assert!((fits[0].params.min - 4.9).abs() < 0.005 && (fits[0].params.max - 5.1).abs() < 0.005);
+        assert!(fit_distributions(&[1.0, 1.0, 1.0, 1.0, 1.0]).is_err());
+
+        // A good fit plots along y = x
+        let fits = fit_distributions(&normal).unwrap();
+        let plot = probability_plot(&normal, &fits[0].params);
+        assert!(plot[250].iter().all(|v| v.abs() < 0.005));
+
+        // Measured capability against 10 ± 0.05
+        let mut feature = Feature::new("F0".to_string(), FeatureType::External, 10.0, 0.05, 0.05);
+        feature.measurements = normal.iter().map(|x| 10.0 + x).collect();
+        let capability = measured_capability(&feature).unwrap();
+        assert_eq!(capability.count, 500);
+        assert!((capability.cp - 0.1 / (6.0 * 0.02)).abs() < 0.1);
+        assert!(capability.cpk <= capability.cp);
+    }
+}
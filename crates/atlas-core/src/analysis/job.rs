@@ -0,0 +1,217 @@
+// src/analysis/job.rs
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use anyhow::{anyhow, Result};
+use crate::config::{Component, Units};
+use super::stackup::{AnalysisResults, StackupAnalysis};
+use super::study::{StudyResult, StudySettings};
+use super::doe::{DoeResult, DoeSettings};
+
+/// Progress of a running analysis, shared with whoever started it.
+/// Counts Monte Carlo iterations; the analytic methods are instant.
+#[derive(Debug, Default)]
+pub struct AnalysisProgress {
+    completed: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl AnalysisProgress {
+    pub fn completed(&self) -> usize {
+        self.completed.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Fraction done in [0, 1]
+    pub fn fraction(&self) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => (self.completed() as f32 / total as f32).min(1.0),
+        }
+    }
+
+    /// Ask the analysis to stop; it returns what it has so far
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn start(&self, total: usize) {
+        self.completed.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    /// More work found along the way
+    pub(crate) fn extend(&self, count: usize) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn advance(&self, count: usize) {
+        self.completed.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+/// An analysis running on a worker thread
+#[derive(Debug)]
+pub struct AnalysisJob {
+    pub analysis: StackupAnalysis,
+    pub progress: Arc<AnalysisProgress>,
+    receiver: Receiver<Result<AnalysisResults>>,
+}
+
+impl AnalysisJob {
+    /// Start `analysis` on a new thread against a snapshot of the components
+    pub fn spawn(analysis: StackupAnalysis, components: Vec<Component>) -> Self {
+        let progress = Arc::new(AnalysisProgress::default());
+        let (sender, receiver) = mpsc::channel();
+
+        let worker_analysis = analysis.clone();
+        let worker_progress = Arc::clone(&progress);
+        thread::spawn(move || {
+            let results = worker_analysis.run_analysis_with_progress(&components, &worker_progress);
+            // The receiver is gone if the job was dropped; nothing to report to
+            let _ = sender.send(results);
+        });
+
+        Self { analysis, progress, receiver }
+    }
+
+    /// The results once the worker is done
+    pub fn try_finish(&self) -> Option<Result<AnalysisResults>> {
+        match self.receiver.try_recv() {
+            Ok(results) => Some(results),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!(
+                "Analysis {} stopped unexpectedly", self.analysis.name
+            ))),
+        }
+    }
+}
+
+/// Work on an analysis other than running it, such as a study or a designed
+/// experiment, on a worker thread
+#[derive(Debug)]
+pub struct BackgroundJob<T> {
+    pub analysis: StackupAnalysis,
+    pub progress: Arc<AnalysisProgress>,
+    receiver: Receiver<Result<T>>,
+}
+
+impl<T: Send + 'static> BackgroundJob<T> {
+    /// Run `work` on a new thread with a copy of `analysis`
+    pub fn spawn<F>(analysis: StackupAnalysis, work: F) -> Self
+    where
+        F: FnOnce(&StackupAnalysis, &AnalysisProgress) -> Result<T> + Send + 'static,
+    {
+        let progress = Arc::new(AnalysisProgress::default());
+        let (sender, receiver) = mpsc::channel();
+
+        let worker_analysis = analysis.clone();
+        let worker_progress = Arc::clone(&progress);
+        thread::spawn(move || {
+            let _ = sender.send(work(&worker_analysis, &worker_progress));
+        });
+
+        Self { analysis, progress, receiver }
+    }
+
+    /// The result once the worker is done
+    pub fn try_finish(&self) -> Option<Result<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!(
+                "Work on {} stopped unexpectedly", self.analysis.name
+            ))),
+        }
+    }
+}
+
+/// A parameter study; its progress counts grid points
+pub type StudyJob = BackgroundJob<StudyResult>;
+
+impl StudyJob {
+    /// Start `study` of `analysis` against a snapshot of the components
+    pub fn spawn_study(analysis: StackupAnalysis, components: Vec<Component>, study: StudySettings, units: Units) -> Self {
+        Self::spawn(analysis, move |analysis, progress| analysis.run_study(&components, &study, units, progress))
+    }
+}
+
+/// A designed experiment; its progress counts runs
+pub type DoeJob = BackgroundJob<DoeResult>;
+
+impl DoeJob {
+    /// Start the designed experiment on `analysis` against a snapshot of the components
+    pub fn spawn_doe(analysis: StackupAnalysis, components: Vec<Component>, settings: DoeSettings, units: Units) -> Self {
+        Self::spawn(analysis, move |analysis, progress| analysis.run_doe(&components, &settings, units, progress))
+    }
+}
+
+/// Analyses waiting to run, one at a time; Monte Carlo already uses every core
+#[derive(Debug, Default)]
+pub struct AnalysisQueue {
+    running: Option<AnalysisJob>,
+    pending: VecDeque<(StackupAnalysis, Vec<Component>)>,
+}
+
+impl AnalysisQueue {
+    /// Queue `analysis`, starting it straight away when nothing else is running.
+    /// An analysis already waiting in the queue is not queued twice.
+    pub fn enqueue(&mut self, analysis: StackupAnalysis, components: Vec<Component>) {
+        if self.is_pending(&analysis.id) {
+            return;
+        }
+        self.pending.push_back((analysis, components));
+        self.start_next();
+    }
+
+    /// Cancel the running job for `analysis_id`, or drop it from the queue
+    pub fn cancel(&mut self, analysis_id: &str) {
+        match &self.running {
+            Some(job) if job.analysis.id == analysis_id => job.progress.cancel(),
+            _ => self.pending.retain(|(analysis, _)| analysis.id != analysis_id),
+        }
+    }
+
+    pub fn running(&self) -> Option<&AnalysisJob> {
+        self.running.as_ref()
+    }
+
+    pub fn is_pending(&self, analysis_id: &str) -> bool {
+        self.pending.iter().any(|(analysis, _)| analysis.id == analysis_id)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.running.is_some() || !self.pending.is_empty()
+    }
+
+    /// Finished job, if any, and start the next one
+    pub fn poll(&mut self) -> Option<(StackupAnalysis, Result<AnalysisResults>)> {
+        let results = self.running.as_ref()?.try_finish()?;
+        let job = self.running.take()?;
+        self.start_next();
+        Some((job.analysis, results))
+    }
+
+    fn start_next(&mut self) {
+        if self.running.is_none() {
+            if let Some((analysis, components)) = self.pending.pop_front() {
+                self.running = Some(AnalysisJob::spawn(analysis, components));
+            }
+        }
+    }
+}
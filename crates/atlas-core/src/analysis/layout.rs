@@ -0,0 +1,109 @@
+// src/analysis/layout.rs
+
+/// Pull of every node toward the origin, relative to the spring pull of an
+/// edge, so that parts of the graph with no edges between them stay in view
+const GRAVITY: f64 = 0.1;
+
+/// One step of a Fruchterman–Reingold layout. Every pair of nodes repels
+/// with k²/d, every edge pulls its ends together with d²/k and a weak
+/// gravity holds the graph around the origin, so linked nodes settle about
+/// `spacing` (k) apart. Each node moves at most `step` along its net force
+/// and pinned nodes stay put; nodes beyond `pinned` are free. Returns the
+/// largest move, so callers can stop once the layout settles.
+pub fn force_layout_step(
+    positions: &mut [[f64; 2]],
+    edges: &[(usize, usize)],
+    pinned: &[bool],
+    spacing: f64,
+    step: f64,
+) -> f64 {
+    let n = positions.len();
+    let k = spacing.max(f64::EPSILON);
+    let mut forces = vec![[0.0f64; 2]; n];
+
+    for i in 0..n {
+        for j in i + 1..n {
+            let mut delta = [positions[i][0] - positions[j][0], positions[i][1] - positions[j][1]];
+            let mut distance = delta[0].hypot(delta[1]);
+            if distance < 1e-9 {
+                // Part coincident nodes in a direction fixed by their indices
+                let angle = (i * n + j) as f64;
+                delta = [angle.cos() * 1e-3 * k, angle.sin() * 1e-3 * k];
+                distance = 1e-3 * k;
+            }
+            let push = k * k / distance;
+            for axis in 0..2 {
+                let f = delta[axis] / distance * push;
+                forces[i][axis] += f;
+                forces[j][axis] -= f;
+            }
+        }
+    }
+
+    for &(a, b) in edges {
+        if a == b || a >= n || b >= n {
+            continue;
+        }
+        let delta = [positions[a][0] - positions[b][0], positions[a][1] - positions[b][1]];
+        let distance = delta[0].hypot(delta[1]);
+        if distance < 1e-9 {
+            continue;
+        }
+        let pull = distance * distance / k;
+        for axis in 0..2 {
+            let f = delta[axis] / distance * pull;
+            forces[a][axis] -= f;
+            forces[b][axis] += f;
+        }
+    }
+
+    let mut largest = 0.0f64;
+    for (i, (position, force)) in positions.iter_mut().zip(&forces).enumerate() {
+        if pinned.get(i).copied().unwrap_or(false) {
+            continue;
+        }
+        let force = [force[0] - GRAVITY * position[0], force[1] - GRAVITY * position[1]];
+        let magnitude = force[0].hypot(force[1]);
+        if magnitude < 1e-12 {
+            continue;
+        }
+        let moved = magnitude.min(step);
+        position[0] += force[0] / magnitude * moved;
+        position[1] += force[1] / magnitude * moved;
+        largest = largest.max(moved);
+    }
+    largest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_force_layout() {
+        let distance = |p: &[[f64; 2]], a: usize, b: usize| (p[a][0] - p[b][0]).hypot(p[a][1] - p[b][1]);
+
+        // A linked pair, a node linked to the first of them and a loose node,
+        // two of them starting on top of each other
+        let mut positions = vec![[0.0, 0.0], [0.0, 0.0], [50.0, 10.0], [-20.0, 30.0]];
+        let edges = [(0, 1), (0, 2)];
+        let mut moved = f64::INFINITY;
+        for iteration in 0..500 {
+            let step = 20.0 * (1.0 - iteration as f64 / 500.0) + 0.5;
+            moved = force_layout_step(&mut positions, &edges, &[], 100.0, step);
+        }
+        assert!(moved < 1.0, "layout did not settle, last move {}", moved);
+        for (a, b) in edges {
+            let length = distance(&positions, a, b);
+            assert!((50.0..150.0).contains(&length), "edge {}-{} is {} long", a, b, length);
+        }
+        assert!(distance(&positions, 1, 2) > distance(&positions, 0, 1));
+        assert!(positions.iter().all(|p| p[0].is_finite() && p[1].is_finite()));
+
+        // Pinned nodes do not move
+        let before = positions.clone();
+        force_layout_step(&mut positions, &[(0, 3)], &[true, false, false, false], 100.0, 20.0);
+        assert_eq!(positions[0], before[0]);
+        assert_ne!(positions[3], before[3]);
+    }
+}
@@ -0,0 +1,341 @@
+// src/analysis/loops.rs
+
+use std::collections::HashSet;
+use anyhow::{bail, Result};
+use petgraph::algo::has_path_connecting;
+use petgraph::graph::{NodeIndex, UnGraph};
+use petgraph::visit::EdgeRef;
+use crate::config::Component;
+use crate::config::mate::Mate;
+use super::stackup::StackupAnalysis;
+
+/// Most loops offered for one gap
+pub const MAX_LOOPS: usize = 20;
+
+/// Most chains explored before the shortest are picked
+const MAX_SEARCHED: usize = 500;
+/// Most features stepped onto by the search, so dense mate graphs where few
+/// chains reach the end still return promptly
+const MAX_VISITED: usize = 100_000;
+
+/// How two consecutive features of a loop are joined
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoopLink {
+    /// Across the named component, from one of its features to another
+    Component(String),
+    /// Through the mate with this id, whose two features touch
+    Mate(String),
+}
+
+/// A chain of features from the start of a gap to its end. Each feature's
+/// value is taken as its position from its component's datum along the
+/// stack, and mated features as touching.
+#[derive(Debug, Clone)]
+pub struct StackLoop {
+    /// (component, feature) from the start of the gap to its end
+    pub features: Vec<(String, String)>,
+    /// `links[i]` joins `features[i]` and `features[i + 1]`
+    pub links: Vec<LoopLink>,
+}
+
+impl StackLoop {
+    /// (component, feature, direction) of each dimension in the loop: +1
+    /// where it leaves a component and -1 where it enters, so the stack is
+    /// the end of the gap less its start
+    pub fn contributions(&self) -> Vec<(String, String, f64)> {
+        let mut contributions = Vec::new();
+        let mut entry = 0;
+        for exit in 0..self.features.len() {
+            let leaves = self.links.get(exit).is_none_or(|link| matches!(link, LoopLink::Mate(_)));
+            if !leaves {
+                continue;
+            }
+            // Crossing a component from a feature to itself adds nothing
+            if exit != entry {
+                let (component, feature) = &self.features[entry];
+                contributions.push((component.clone(), feature.clone(), -1.0));
+                let (component, feature) = &self.features[exit];
+                contributions.push((component.clone(), feature.clone(), 1.0));
+            }
+            entry = exit + 1;
+        }
+        contributions
+    }
+
+    /// A new analysis of the gap with the loop's contributions
+    pub fn to_analysis(&self, name: String) -> StackupAnalysis {
+        let mut analysis = StackupAnalysis::new(name);
+        for (component, feature, direction) in self.contributions() {
+            analysis.add_contribution(component, feature, direction, false, None);
+        }
+        analysis
+    }
+
+    /// The features in order, e.g. `Block.Top → Block.Bottom ⇄ Spacer.Top`,
+    /// with ⇄ for mates
+    pub fn describe(&self) -> String {
+        let mut text = String::new();
+        for (i, (component, feature)) in self.features.iter().enumerate() {
+            if i > 0 {
+                text.push_str(match self.links[i - 1] {
+                    LoopLink::Component(_) => " → ",
+                    LoopLink::Mate(_) => " ⇄ ",
+                });
+            }
+            text.push_str(&format!("{}.{}", component, feature));
+        }
+        text
+    }
+}
+
+/// The loops that join the `start` and `end` features, given as (component,
+/// feature), through the components and their mates. Each component is
+/// crossed at most once. Fewest contributions first; empty if no chain of
+/// mates joins them. In dense assemblies only the shorter chains are
+/// searched.
+pub fn find_loops(components: &[Component], mates: &[Mate], start: (&str, &str), end: (&str, &str)) -> Result<Vec<StackLoop>> {
+    if start == end {
+        bail!("The start and end of the gap are the same feature");
+    }
+
+    // One node per feature; every pair of features on a component is joined
+    // through the component, and mated features through their mate
+    let mut graph: UnGraph<(String, String), LoopLink> = UnGraph::new_undirected();
+    let mut node_of = std::collections::HashMap::new();
+    for component in components {
+        let nodes: Vec<NodeIndex> = component.features.iter()
+            .map(|feature| {
+                let node = graph.add_node((component.name.clone(), feature.name.clone()));
+                node_of.insert((component.name.as_str(), feature.name.as_str()), node);
+                node
+            })
+            .collect();
+        for (i, a) in nodes.iter().enumerate() {
+            for b in &nodes[i + 1..] {
+                graph.add_edge(*a, *b, LoopLink::Component(component.name.clone()));
+            }
+        }
+    }
+    for mate in mates {
+        let a = node_of.get(&(mate.component_a.as_str(), mate.feature_a.as_str()));
+        let b = node_of.get(&(mate.component_b.as_str(), mate.feature_b.as_str()));
+        if let (Some(a), Some(b)) = (a, b) {
+            graph.add_edge(*a, *b, LoopLink::Mate(mate.id.clone()));
+        }
+    }
+
+    let Some(&start_node) = node_of.get(&start) else {
+        bail!("Feature {}.{} not found", start.0, start.1);
+    };
+    let Some(&end_node) = node_of.get(&end) else {
+        bail!("Feature {}.{} not found", end.0, end.1);
+    };
+    if !has_path_connecting(&graph, start_node, end_node, None) {
+        return Ok(Vec::new());
+    }
+
+    let mut search = LoopSearch {
+        graph: &graph,
+        end: end_node,
+        path: vec![start_node],
+        links: Vec::new(),
+        crossed: HashSet::from([start.0.to_string()]),
+        found: Vec::new(),
+        visited: 0,
+        depth: 1,
+        deeper: true,
+    };
+    // Deepen one feature at a time, so the shortest loops are found before
+    // the search runs out
+    while search.deeper && search.found.len() < MAX_SEARCHED && search.visited < MAX_VISITED {
+        search.depth += 1;
+        search.deeper = false;
+        search.extend();
+    }
+
+    // Ties go to the loop through the mates listed first
+    let mut loops = search.found;
+    let mate_order = |l: &StackLoop| -> Vec<usize> {
+        l.links.iter()
+            .filter_map(|link| match link {
+                LoopLink::Mate(id) => mates.iter().position(|m| m.id == *id),
+                LoopLink::Component(_) => None,
+            })
+            .collect()
+    };
+    loops.sort_by_cached_key(|l| (l.contributions().len(), l.features.len(), mate_order(l)));
+    loops.truncate(MAX_LOOPS);
+    Ok(loops)
+}
+
+/// Depth-limited walk over the feature graph, alternating between crossing
+/// a component and following a mate
+struct LoopSearch<'a> {
+    graph: &'a UnGraph<(String, String), LoopLink>,
+    end: NodeIndex,
+    path: Vec<NodeIndex>,
+    links: Vec<LoopLink>,
+    /// Components the path has been on
+    crossed: HashSet<String>,
+    found: Vec<StackLoop>,
+    /// Features stepped onto so far
+    visited: usize,
+    /// Features in the loops this pass records
+    depth: usize,
+    /// Whether a chain was cut short at `depth`
+    deeper: bool,
+}
+
+impl LoopSearch<'_> {
+    fn extend(&mut self) {
+        self.visited += 1;
+        let here = *self.path.last().unwrap();
+        if here == self.end {
+            // Shorter loops were recorded by earlier passes
+            if self.path.len() == self.depth {
+                self.found.push(StackLoop {
+                    features: self.path.iter().map(|n| self.graph[*n].clone()).collect(),
+                    links: self.links.clone(),
+                });
+            }
+            return;
+        }
+        if self.path.len() >= self.depth {
+            self.deeper = true;
+            return;
+        }
+        let after_component = matches!(self.links.last(), Some(LoopLink::Component(_)));
+
+        let steps: Vec<(NodeIndex, LoopLink)> = self.graph.edges(here)
+            .map(|edge| {
+                let next = if edge.source() == here { edge.target() } else { edge.source() };
+                (next, edge.weight().clone())
+            })
+            .collect();
+        for (next, link) in steps {
+            if self.found.len() >= MAX_SEARCHED || self.visited >= MAX_VISITED {
+                break;
+            }
+            if self.path.contains(&next) {
+                continue;
+            }
+            let entered = match &link {
+                // Crossing a component twice in a row is one crossing
+                LoopLink::Component(_) if after_component => continue,
+                LoopLink::Component(_) => None,
+                LoopLink::Mate(_) => {
+                    let component = &self.graph[next].0;
+                    if self.crossed.contains(component) {
+                        continue;
+                    }
+                    Some(component.clone())
+                },
+            };
+
+            if let Some(component) = &entered {
+                self.crossed.insert(component.clone());
+            }
+            self.path.push(next);
+            self.links.push(link);
+            self.extend();
+            self.path.pop();
+            self.links.pop();
+            if let Some(component) = &entered {
+                self.crossed.remove(component);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::stackup::AnalysisMethod;
+    use crate::config::{Feature, FeatureType};
+    use crate::config::mate::FitType;
+
+    #[test]
+    fn test_loops_from_mates() {
+        // A block on a spacer in a housing, the spacer on either of two
+        // housing faces; the gap is from the block's top to the housing rim
+        let part = |name: &str, features: &[(&str, f64)]| Component {
+            name: name.to_string(),
+            description: None,
+            features: features.iter()
+                .map(|(f, value)| Feature::new(f.to_string(), FeatureType::External, *value, 0.1, 0.1))
+                .collect(),
+        };
+        let components = vec![
+            part("Housing", &[("Floor", 0.0), ("Ledge", 2.0), ("Rim", 50.0)]),
+            part("Spacer", &[("Bottom", 0.0), ("Top", 20.0)]),
+            part("Block", &[("Bottom", 0.0), ("Top", 25.0)]),
+            part("Lid", &[("Face", 0.0)]),
+        ];
+        let mate = |id: &str, a: (&str, &str), b: (&str, &str)| Mate::new(id.to_string(),
+            a.0.to_string(), a.1.to_string(), b.0.to_string(), b.1.to_string(), FitType::Clearance);
+        let mates = vec![
+            mate("1", ("Block", "Bottom"), ("Spacer", "Top")),
+            mate("2", ("Spacer", "Bottom"), ("Housing", "Floor")),
+            mate("3", ("Spacer", "Bottom"), ("Housing", "Ledge")),
+        ];
+
+        let loops = find_loops(&components, &mates, ("Block", "Top"), ("Housing", "Rim")).unwrap();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].describe(),
+            "Block.Top → Block.Bottom ⇄ Spacer.Top → Spacer.Bottom ⇄ Housing.Floor → Housing.Rim");
+
+        // Entering a component subtracts, leaving it adds
+        let contributions = loops[0].contributions();
+        assert_eq!(contributions.len(), 6);
+        assert!(contributions.contains(&("Block".to_string(), "Top".to_string(), -1.0)));
+        assert!(contributions.contains(&("Housing".to_string(), "Rim".to_string(), 1.0)));
+
+        let mut analysis = loops[0].to_analysis("Gap".to_string());
+        analysis.methods = vec![AnalysisMethod::WorstCase];
+        let worst_case = analysis.run_analysis(&components).unwrap().worst_case.unwrap();
+        assert!((worst_case.min - (5.0 - 0.6)).abs() < 1e-9 && (worst_case.max - (5.0 + 0.6)).abs() < 1e-9);
+        let through_ledge = loops[1].to_analysis("Gap".to_string())
+            .run_analysis(&components).unwrap().worst_case.unwrap();
+        assert!((through_ledge.min - (3.0 - 0.6)).abs() < 1e-9);
+
+        // Nothing joins the lid to the rest
+        assert!(find_loops(&components, &mates, ("Lid", "Face"), ("Housing", "Rim")).unwrap().is_empty());
+        assert!(find_loops(&components, &mates, ("Lid", "Face"), ("Lid", "Face")).is_err());
+    }
+
+    #[test]
+    fn test_loop_search_limits() {
+        // Ten densely mated parts hang off the start feature, but the gap
+        // closes only through the start part's other face
+        let part = |name: String| Component {
+            name,
+            description: None,
+            features: ["A", "B"].iter()
+                .map(|f| Feature::new(f.to_string(), FeatureType::External, 0.0, 0.1, 0.1))
+                .collect(),
+        };
+        let mut components: Vec<Component> = (0..10).map(|i| part(format!("C{}", i))).collect();
+        components.push(part("Start".to_string()));
+        components.push(part("End".to_string()));
+        components.push(part("Loose".to_string()));
+        let mut mates = Vec::new();
+        let mut mate = |a: (String, &str), b: (String, &str)| {
+            let id = mates.len().to_string();
+            mates.push(Mate::new(id, a.0, a.1.to_string(), b.0, b.1.to_string(), FitType::Clearance));
+        };
+        for i in 0..10 {
+            mate(("Start".to_string(), "A"), (format!("C{}", i), "A"));
+            for j in (0..10).filter(|j| *j != i) {
+                mate((format!("C{}", i), "B"), (format!("C{}", j), "A"));
+            }
+        }
+        mate(("Start".to_string(), "B"), ("End".to_string(), "A"));
+
+        let loops = find_loops(&components, &mates, ("Start", "A"), ("End", "B")).unwrap();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].describe(), "Start.A → Start.B ⇄ End.A → End.B");
+
+        // Nothing reaches a part with no mates, however dense the rest
+        assert!(find_loops(&components, &mates, ("Start", "A"), ("Loose", "A")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+// src/analysis/mate_fit.rs
+
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Result};
+use rand::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+use crate::config::{Feature, FeatureType};
+use crate::config::mate::{FitType, Mate};
+use super::stackup::DistributionParams;
+
+/// Monte Carlo samples drawn for a mate's clearance
+pub const MATE_FIT_SAMPLES: usize = 100_000;
+
+/// Bins in the clearance histogram
+const HISTOGRAM_BINS: usize = 40;
+
+/// Clearance of a fit from one method; negative clearance is interference
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ClearanceEstimate {
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Probability the clearance is below zero
+    pub interference: f64,
+}
+
+/// Statistical evaluation of a mate: the clearance (internal minus external
+/// feature) by RSS and by Monte Carlo from the features' distributions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MateFitResult {
+    pub fit_type: FitType,
+    /// Distributions the clearance was drawn from
+    pub hole: DistributionParams,
+    pub shaft: DistributionParams,
+    /// Normal approximation from the features' means and std devs
+    pub rss: ClearanceEstimate,
+    pub monte_carlo: ClearanceEstimate,
+    pub samples: usize,
+    pub min: f64,
+    pub max: f64,
+    /// (bin start, count) over [min, max]
+    pub histogram: Vec<(f64, usize)>,
+}
+
+impl MateFitResult {
+    /// Probability the fit is not what its type calls for: interference in a
+    /// clearance fit, clearance in an interference fit. None for transition
+    /// fits, which allow both.
+    pub fn failure_probability(&self, estimate: &ClearanceEstimate) -> Option<f64> {
+        match self.fit_type {
+            FitType::Clearance => Some(estimate.interference),
+            FitType::Interference => Some(1.0 - estimate.interference),
+            FitType::Transition => None,
+        }
+    }
+}
+
+/// Clearance distribution of `mate` from its features' distributions.
+/// `feature_a` and `feature_b` are the mate's features, one internal and one
+/// external.
+pub fn analyze_fit(mate: &Mate, feature_a: &Feature, feature_b: &Feature, samples: usize, seed: u64) -> Result<MateFitResult> {
+    let (hole, shaft) = match (feature_a.feature_type, feature_b.feature_type) {
+        (FeatureType::Internal, FeatureType::External) => (feature_a, feature_b),
+        (FeatureType::External, FeatureType::Internal) => (feature_b, feature_a),
+        _ => bail!("Mate {}.{} / {}.{} needs one internal and one external feature",
+            mate.component_a, mate.feature_a, mate.component_b, mate.feature_b),
+    };
+    if samples == 0 {
+        bail!("At least one Monte Carlo sample is needed");
+    }
+    let (hole, shaft) = (hole.effective_distribution(), shaft.effective_distribution());
+
+    let (hole_mean, hole_std_dev) = hole.moments();
+    let (shaft_mean, shaft_std_dev) = shaft.moments();
+    let mean = hole_mean - shaft_mean;
+    let std_dev = hole_std_dev.hypot(shaft_std_dev);
+    let interference = match Normal::new(mean, std_dev) {
+        Ok(normal) => normal.cdf(0.0),
+        Err(_) => if mean < 0.0 { 1.0 } else { 0.0 },
+    };
+    let rss = ClearanceEstimate { mean, std_dev, interference };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let clearances: Vec<f64> = hole.sample_n(&mut rng, samples).into_iter()
+        .zip(shaft.sample_n(&mut rng, samples))
+        .map(|(h, s)| h - s)
+        .collect();
+
+    let n = samples as f64;
+    let mc_mean = clearances.iter().sum::<f64>() / n;
+    let mc_std_dev = (clearances.iter().map(|c| (c - mc_mean).powi(2)).sum::<f64>() / n).sqrt();
+    let monte_carlo = ClearanceEstimate {
+        mean: mc_mean,
+        std_dev: mc_std_dev,
+        interference: clearances.iter().filter(|c| **c < 0.0).count() as f64 / n,
+    };
+
+    let min = clearances.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = clearances.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = (max - min) / HISTOGRAM_BINS as f64;
+    let mut counts = vec![0; HISTOGRAM_BINS];
+    for c in &clearances {
+        let bin = if width > 0.0 { ((c - min) / width) as usize } else { 0 };
+        counts[bin.min(HISTOGRAM_BINS - 1)] += 1;
+    }
+
+    Ok(MateFitResult {
+        fit_type: mate.fit_type.clone(),
+        hole,
+        shaft,
+        rss,
+        monte_carlo,
+        samples,
+        min,
+        max,
+        histogram: counts.into_iter().enumerate()
+            .map(|(i, count)| (min + i as f64 * width, count))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistical_mate_fit() {
+        // Fails worst case (clearance -0.03 to 0.09) but rarely interferes
+        let bore = Feature::new("Bore".to_string(), FeatureType::Internal, 20.0, 0.03, 0.03);
+        let pin = Feature::new("Pin".to_string(), FeatureType::External, 19.97, 0.03, 0.03);
+        let mate = Mate::new("m".to_string(), "Pin".to_string(), "Pin".to_string(),
+            "Housing".to_string(), "Bore".to_string(), FitType::Clearance);
+        assert!(!mate.validate(&pin, &bore).is_valid);
+
+        let result = analyze_fit(&mate, &pin, &bore, 200_000, 5).unwrap();
+        let expected = Normal::new(0.03, 0.01f64.hypot(0.01)).unwrap().cdf(0.0);
+        assert!((result.rss.mean - 0.03).abs() < 1e-12);
+        assert!((result.rss.interference - expected).abs() < 1e-9);
+        assert!((result.monte_carlo.interference - expected).abs() < 0.002);
+        assert!((result.monte_carlo.std_dev / result.rss.std_dev - 1.0).abs() < 0.01);
+        assert_eq!(result.failure_probability(&result.rss), Some(result.rss.interference));
+        assert_eq!(result.histogram.iter().map(|(_, count)| count).sum::<usize>(), 200_000);
+        assert!(result.min < 0.0 && result.max > 0.06);
+
+        // Two external features cannot fit together
+        assert!(analyze_fit(&mate, &pin, &pin, 1_000, 5).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+// src/analysis/mod.rs
+pub mod stackup;
+pub mod six_sigma;
+pub mod expression;
+pub mod correlation;
+pub mod distribution;
+pub mod convergence;
+mod monte_carlo;
+pub mod job;
+pub mod sampling;
+pub mod allocation;
+pub mod cost;
+pub mod study;
+pub mod doe;
+pub mod fitting;
+pub mod mate_fit;
+pub mod loops;
+pub mod dsm;
+pub mod layout;
+mod stack_function;
+mod tail;
+#[cfg(test)]
+mod test_support;
+
+// Re-export commonly used types
+pub use stackup::{
+    AnalysisMethod,
+    StackupAnalysis,
+    AnalysisResults,
+    MonteCarloResult,
+    StackupContribution,
+    ModifiedRssSettings,
+    MrssCorrection,
+    SpecLimit,
+    SpecViolation,
+};
+pub use six_sigma::{MeanShiftMode, SixSigmaResult, SixSigmaSettings};
+pub use correlation::CorrelationGroup;
+pub use convergence::{ConvergenceSettings, PpmEstimate, PpmMethod};
+pub use job::{AnalysisJob, AnalysisProgress, AnalysisQueue, BackgroundJob, DoeJob, StudyJob};
+pub use sampling::SamplingStrategy;
+pub use allocation::{AllocationMethod, AllocationResult, AllocationSettings, AllocationTarget, CostModel};
+pub use cost::{CostEstimate, FeatureCost};
+pub use study::{StudyAxis, StudyMetric, StudyParameter, StudyResult, StudySettings};
+pub use doe::{CcdAlpha, DesignType, DoeFactor, DoeResult, DoeSettings};
+pub use fitting::{DistributionFit, MeasuredCapability};
+pub use mate_fit::{ClearanceEstimate, MateFitResult};
+pub use loops::{LoopLink, StackLoop};
+pub use dsm::DsmClustering;
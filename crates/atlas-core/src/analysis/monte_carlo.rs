@@ -0,0 +1,300 @@
+// src/analysis/monte_carlo.rs
+
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+use statrs::distribution::{Normal as StatsNormal, ContinuousCDF};
+use super::stack_function::ResolvedStack;
+use super::stackup::MonteCarloSettings;
+use super::sampling::UnitSampler;
+use super::convergence::PpmEstimate;
+use super::job::AnalysisProgress;
+use super::tail::design_point;
+
+/// Samples drawn from one seed. Chunk k of a run always gets the same seed,
+/// so a seeded run reproduces exactly however many threads share the work.
+const CHUNK_SIZE: usize = 8192;
+
+/// Samples drawn between progress updates; whole chunks, so splitting a run
+/// into steps does not change what it draws
+pub(crate) const PROGRESS_STEP: usize = 16 * CHUNK_SIZE;
+
+/// Most sample pairs used for the Sobol indices
+const SOBOL_SAMPLES: usize = 50_000;
+
+/// Monte Carlo samples stored column by column
+#[derive(Default)]
+pub(crate) struct Samples {
+    /// Sampled values, one column per contributor
+    pub columns: Vec<Vec<f64>>,
+    /// Stack value of each sample
+    pub results: Vec<f64>,
+}
+
+impl Samples {
+    fn append(&mut self, mut other: Samples) {
+        if self.columns.is_empty() {
+            self.columns = other.columns;
+        } else {
+            for (column, more) in self.columns.iter_mut().zip(&mut other.columns) {
+                column.append(more);
+            }
+        }
+        self.results.append(&mut other.results);
+    }
+}
+
+/// Draws samples of a resolved stack in parallel chunks
+pub(crate) struct Sampler<'a> {
+    stack: &'a ResolvedStack<'a>,
+    /// Cholesky factor of the correlation matrix; correlated samples go
+    /// through a Gaussian copula
+    correlation: Option<&'a [Vec<f64>]>,
+    /// Point set for the strategies other than plain random sampling
+    unit: Option<UnitSampler>,
+    seed: u64,
+    chunks_drawn: u64,
+    samples_drawn: u64,
+}
+
+impl<'a> Sampler<'a> {
+    pub fn new(stack: &'a ResolvedStack<'a>, correlation: Option<&'a [Vec<f64>]>, settings: &MonteCarloSettings) -> Self {
+        let seed = settings.seed.unwrap_or_else(|| StdRng::from_entropy().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        Self {
+            stack,
+            correlation,
+            unit: UnitSampler::new(settings.sampling, stack.distributions.len(), &mut rng),
+            seed,
+            chunks_drawn: 0,
+            samples_drawn: 0,
+        }
+    }
+
+    /// Draw `count` more samples onto the end of `samples`
+    pub fn draw(&mut self, count: usize, samples: &mut Samples) {
+        let chunks = count.div_ceil(CHUNK_SIZE);
+        let first = self.chunks_drawn;
+        let start = self.samples_drawn;
+
+        let drawn: Vec<Samples> = (0..chunks)
+            .into_par_iter()
+            .map(|i| self.draw_chunk(
+                first + i as u64,
+                start + (i * CHUNK_SIZE) as u64,
+                CHUNK_SIZE.min(count - i * CHUNK_SIZE),
+            ))
+            .collect();
+
+        self.chunks_drawn += chunks as u64;
+        self.samples_drawn += count as u64;
+        for chunk in drawn {
+            samples.append(chunk);
+        }
+    }
+
+    /// Chunk `index`, holding samples `start..start + size` of the run
+    fn draw_chunk(&self, index: u64, start: u64, size: usize) -> Samples {
+        // Spread consecutive chunk indices across the seed space
+        let mut rng = StdRng::seed_from_u64(self.seed ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let distributions = &self.stack.distributions;
+        let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+
+        let columns: Vec<Vec<f64>> = match (&self.unit, self.correlation) {
+            (Some(unit), correlation) => {
+                let mut points = unit.points(distributions.len(), start, size, &mut rng);
+                if let Some(lower) = correlation {
+                    // Correlate the points' normal scores, as in the copula below
+                    let scores: Vec<Vec<f64>> = points.iter()
+                        .map(|column| column.iter().map(|u| standard_normal.inverse_cdf(*u)).collect())
+                        .collect();
+                    points = (0..distributions.len())
+                        .map(|i| (0..size)
+                            .map(|k| {
+                                let z: f64 = lower[i].iter().zip(&scores).map(|(l, column)| l * column[k]).sum();
+                                standard_normal.cdf(z).clamp(f64::EPSILON, 1.0 - f64::EPSILON)
+                            })
+                            .collect())
+                        .collect();
+                }
+
+                distributions.iter()
+                    .zip(points)
+                    .map(|(params, column)| column.into_iter().map(|u| params.inverse_cdf(u)).collect())
+                    .collect()
+            },
+            (None, None) => distributions.iter()
+                .map(|params| params.sample_n(&mut rng, size))
+                .collect(),
+            (None, Some(lower)) => {
+                let scores: Vec<Vec<f64>> = distributions.iter()
+                    .map(|_| (0..size).map(|_| rng.sample(StandardNormal)).collect())
+                    .collect();
+
+                distributions.iter().enumerate()
+                    .map(|(i, params)| (0..size)
+                        .map(|k| {
+                            let z: f64 = lower[i].iter().zip(&scores).map(|(l, column)| l * column[k]).sum();
+                            params.inverse_cdf(standard_normal.cdf(z).clamp(f64::EPSILON, 1.0 - f64::EPSILON))
+                        })
+                        .collect())
+                    .collect()
+            },
+        };
+
+        let results = self.stack.evaluate_columns(&columns, size);
+        Samples { columns, results }
+    }
+
+    /// Importance sampling estimate of the fraction beyond `limit`: `count`
+    /// samples drawn with the independent normal scores shifted to the most
+    /// probable failure point, each weighted by its likelihood ratio. None if
+    /// the stack cannot be pushed toward the limit, no shifted sample fails,
+    /// or the run is cancelled.
+    pub fn estimate_tail(&mut self, limit: f64, above: bool, count: usize, progress: &AnalysisProgress) -> Option<PpmEstimate> {
+        let dimensions = self.stack.distributions.len();
+        let shift = design_point(|z| self.stack.evaluate(&self.values_at(z)), dimensions, limit, above)?;
+        let distance = shift.iter().map(|s| s * s).sum::<f64>().sqrt();
+
+        let (mut failures, mut sum, mut sum_squares) = (0, 0.0, 0.0);
+        let mut remaining = count;
+        while remaining > 0 {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let step = remaining.min(PROGRESS_STEP);
+            let chunks = step.div_ceil(CHUNK_SIZE);
+            let first = self.chunks_drawn;
+
+            let tallies: Vec<(usize, f64, f64)> = (0..chunks)
+                .into_par_iter()
+                .map(|i| self.draw_shifted_chunk(first + i as u64, CHUNK_SIZE.min(step - i * CHUNK_SIZE), &shift, limit, above))
+                .collect();
+            for (chunk_failures, chunk_sum, chunk_squares) in tallies {
+                failures += chunk_failures;
+                sum += chunk_sum;
+                sum_squares += chunk_squares;
+            }
+
+            self.chunks_drawn += chunks as u64;
+            progress.advance(step);
+            remaining -= step;
+        }
+
+        (failures > 0).then(|| PpmEstimate::importance_sampled(failures, sum, sum_squares, count, distance))
+    }
+
+    /// Failures among `size` samples around `shift`, with the sum of their
+    /// likelihood ratios and of the squares
+    fn draw_shifted_chunk(&self, index: u64, size: usize, shift: &[f64], limit: f64, above: bool) -> (usize, f64, f64) {
+        let mut rng = StdRng::seed_from_u64(self.seed ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let offset = shift.iter().map(|s| s * s).sum::<f64>() / 2.0;
+
+        let scores: Vec<Vec<f64>> = shift.iter()
+            .map(|s| (0..size).map(|_| s + rng.sample::<f64, _>(StandardNormal)).collect())
+            .collect();
+        let mut columns = vec![Vec::with_capacity(size); shift.len()];
+        let mut z = vec![0.0; shift.len()];
+        for k in 0..size {
+            for (value, column) in z.iter_mut().zip(&scores) {
+                *value = column[k];
+            }
+            for (column, value) in columns.iter_mut().zip(self.values_at(&z)) {
+                column.push(value);
+            }
+        }
+
+        let results = self.stack.evaluate_columns(&columns, size);
+        let mut tally = (0, 0.0, 0.0);
+        for (k, result) in results.into_iter().enumerate() {
+            if (above && result > limit) || (!above && result < limit) {
+                // Density of the unshifted over the shifted scores
+                let exponent: f64 = offset - shift.iter().zip(&scores).map(|(s, column)| s * column[k]).sum::<f64>();
+                let weight = exponent.exp();
+                tally.0 += 1;
+                tally.1 += weight;
+                tally.2 += weight * weight;
+            }
+        }
+        tally
+    }
+
+    /// First-order and total-effect Sobol indices of each contributor, with
+    /// the Saltelli and Jansen estimators. The first half of the samples is
+    /// matrix A and the second half, its rows shuffled so it is independent of
+    /// A whatever the sampling strategy, matrix B. None with correlation
+    /// groups, whose contributors are not independent, or too few samples.
+    pub fn sobol_indices(&self, columns: &[Vec<f64>], results: &[f64]) -> Option<Vec<(f64, f64)>> {
+        let n = (results.len() / 2).min(SOBOL_SAMPLES);
+        if self.correlation.is_some() || n < 2 {
+            return None;
+        }
+
+        // A stream of its own, apart from the chunk seeds
+        let mut order: Vec<usize> = (n..2 * n).collect();
+        order.shuffle(&mut StdRng::seed_from_u64(self.seed.rotate_left(32)));
+
+        let f_a = &results[..n];
+        let f_b: Vec<f64> = order.iter().map(|k| results[*k]).collect();
+        let (mean, variance) = mean_and_variance(&[f_a, &f_b[..]].concat());
+        if variance <= 0.0 {
+            return Some(vec![(0.0, 0.0); columns.len()]);
+        }
+
+        let indices = (0..columns.len())
+            .into_par_iter()
+            .map(|i| {
+                // A with contributor i taken from B
+                let mixed: Vec<Vec<f64>> = columns.iter().enumerate()
+                    .map(|(j, column)| if j == i {
+                        order.iter().map(|k| column[*k]).collect()
+                    } else {
+                        column[..n].to_vec()
+                    })
+                    .collect();
+                let f_ab = self.stack.evaluate_columns(&mixed, n);
+
+                let (mut first, mut total) = (0.0, 0.0);
+                for ((a, b), ab) in f_a.iter().zip(&f_b).zip(&f_ab) {
+                    // Centered, or the stack's offset swamps the estimate
+                    first += (b - mean) * (ab - a);
+                    total += (a - ab).powi(2);
+                }
+                (first / n as f64 / variance, total / (2.0 * n as f64) / variance)
+            })
+            .collect();
+        Some(indices)
+    }
+
+    /// Contributor values at independent standard normal scores `z`,
+    /// correlated through the copula when there is one
+    fn values_at(&self, z: &[f64]) -> Vec<f64> {
+        let standard_normal = StatsNormal::new(0.0, 1.0).unwrap();
+        self.stack.distributions.iter().enumerate()
+            .map(|(i, params)| {
+                let score = match self.correlation {
+                    Some(lower) => lower[i].iter().zip(z).map(|(l, x)| l * x).sum(),
+                    None => z[i],
+                };
+                params.inverse_cdf(standard_normal.cdf(score).clamp(f64::EPSILON, 1.0 - f64::EPSILON))
+            })
+            .collect()
+    }
+}
+
+/// Mean and sample variance of `values`
+pub(crate) fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len();
+    if n == 0 {
+        return (0.0, 0.0);
+    }
+
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let variance = if n > 1 {
+        values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+    } else {
+        0.0
+    };
+    (mean, variance)
+}
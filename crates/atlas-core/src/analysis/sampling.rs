@@ -0,0 +1,249 @@
+// src/analysis/sampling.rs
+
+use serde::{Serialize, Deserialize};
+use rand::prelude::*;
+
+/// How Monte Carlo picks its points. Everything but `Random` builds points
+/// in the unit hypercube, one dimension per contributor, and maps them
+/// through each distribution's inverse CDF.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum SamplingStrategy {
+    /// Independent pseudo-random draws
+    #[default]
+    Random,
+    /// Every contributor's range split into equally likely strata, one sample
+    /// per stratum, paired at random; stratified within each parallel chunk
+    LatinHypercube,
+    /// Sobol low-discrepancy sequence with a random linear scramble and
+    /// digital shift
+    Sobol,
+    /// Halton low-discrepancy sequence with randomly permuted digits
+    Halton,
+}
+
+impl SamplingStrategy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SamplingStrategy::Random => "Random",
+            SamplingStrategy::LatinHypercube => "Latin hypercube",
+            SamplingStrategy::Sobol => "Sobol",
+            SamplingStrategy::Halton => "Halton",
+        }
+    }
+}
+
+const SOBOL_BITS: usize = 32;
+
+/// Degree `s`, inner coefficients `a` and initial direction numbers `m` of the
+/// primitive polynomials for Sobol dimensions 2 to 21 (Joe & Kuo). Further
+/// dimensions use the next primitive polynomials with random odd `m`.
+const SOBOL_TABLE: &[(u32, u32, &[u32])] = &[
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+/// Point generator in the unit hypercube for the non-random strategies
+pub(crate) enum UnitSampler {
+    LatinHypercube,
+    /// Scrambled direction numbers and digital shift per dimension
+    Sobol(Vec<([u32; SOBOL_BITS], u32)>),
+    /// Base and digit permutation per dimension
+    Halton(Vec<(u32, Vec<u32>)>),
+}
+
+impl UnitSampler {
+    /// None for `Random`. The scrambles are drawn from `rng`.
+    pub fn new(strategy: SamplingStrategy, dimensions: usize, rng: &mut StdRng) -> Option<Self> {
+        match strategy {
+            SamplingStrategy::Random => None,
+            SamplingStrategy::LatinHypercube => Some(UnitSampler::LatinHypercube),
+            SamplingStrategy::Sobol => Some(UnitSampler::Sobol(
+                sobol_directions(dimensions, rng).into_iter()
+                    .map(|directions| (scramble(&directions, rng), rng.gen()))
+                    .collect()
+            )),
+            SamplingStrategy::Halton => Some(UnitSampler::Halton(
+                primes(dimensions).into_iter()
+                    .map(|base| {
+                        let mut permutation: Vec<u32> = (0..base).collect();
+                        permutation.shuffle(rng);
+                        (base, permutation)
+                    })
+                    .collect()
+            )),
+        }
+    }
+
+    /// Points `start..start + size` of the sequence, one column per
+    /// dimension, strictly inside (0, 1)
+    pub fn points(&self, dimensions: usize, start: u64, size: usize, rng: &mut StdRng) -> Vec<Vec<f64>> {
+        let columns: Vec<Vec<f64>> = match self {
+            UnitSampler::LatinHypercube => (0..dimensions)
+                .map(|_| {
+                    let mut strata: Vec<usize> = (0..size).collect();
+                    strata.shuffle(rng);
+                    strata.into_iter()
+                        .map(|stratum| (stratum as f64 + rng.gen::<f64>()) / size as f64)
+                        .collect()
+                })
+                .collect(),
+            UnitSampler::Sobol(dimensions) => dimensions.iter()
+                .map(|(directions, shift)| (start..start + size as u64)
+                    .map(|index| {
+                        // Gray code order: one direction number per set bit
+                        let gray = index ^ (index >> 1);
+                        let x = (0..SOBOL_BITS)
+                            .filter(|bit| gray >> bit & 1 == 1)
+                            .fold(*shift, |x, bit| x ^ directions[bit]);
+                        (x as f64 + 0.5) / 2f64.powi(SOBOL_BITS as i32)
+                    })
+                    .collect())
+                .collect(),
+            UnitSampler::Halton(dimensions) => dimensions.iter()
+                .map(|(base, permutation)| (start..start + size as u64)
+                    .map(|index| radical_inverse(index, *base, permutation))
+                    .collect())
+                .collect(),
+        };
+
+        columns.into_iter()
+            .map(|column| column.into_iter().map(|u| u.clamp(f64::EPSILON, 1.0 - f64::EPSILON)).collect())
+            .collect()
+    }
+}
+
+/// Digits of `index` in `base`, permuted and reflected about the radix point. Leading
+/// zeros are permuted too, out to double precision.
+fn radical_inverse(mut index: u64, base: u32, permutation: &[u32]) -> f64 {
+    let base = base as u64;
+    let inverse_base = 1.0 / base as f64;
+    let mut scale = inverse_base;
+    let mut value = 0.0;
+
+    while scale > f64::EPSILON {
+        value += permutation[(index % base) as usize] as f64 * scale;
+        index /= base;
+        scale *= inverse_base;
+    }
+    value
+}
+
+/// Direction numbers, as the top bits of a u32, for the first `dimensions`
+/// Sobol dimensions
+fn sobol_directions(dimensions: usize, rng: &mut StdRng) -> Vec<[u32; SOBOL_BITS]> {
+    let mut polynomials = primitive_polynomials();
+    (0..dimensions)
+        .map(|dimension| {
+            let mut v = [0u32; SOBOL_BITS];
+            if dimension == 0 {
+                // Van der Corput
+                for (bit, v) in v.iter_mut().enumerate() {
+                    *v = 1 << (SOBOL_BITS - 1 - bit);
+                }
+                return v;
+            }
+
+            let (s, a, m): (u32, u32, Vec<u32>) = match SOBOL_TABLE.get(dimension - 1) {
+                Some((s, a, m)) => (*s, *a, m.to_vec()),
+                None => {
+                    let (s, a) = polynomials.next().expect("primitive polynomials run out");
+                    (s, a, (1..=s).map(|k| rng.gen_range(0..1u32 << (k - 1)) * 2 + 1).collect())
+                },
+            };
+            let s = s as usize;
+
+            for bit in 0..SOBOL_BITS.min(s) {
+                v[bit] = m[bit] << (SOBOL_BITS - 1 - bit);
+            }
+            for bit in s..SOBOL_BITS {
+                let mut value = v[bit - s] ^ (v[bit - s] >> s);
+                for k in 1..s {
+                    if (a >> (s - 1 - k)) & 1 == 1 {
+                        value ^= v[bit - k];
+                    }
+                }
+                v[bit] = value;
+            }
+            v
+        })
+        .collect()
+}
+
+/// Primitive polynomials over GF(2) past those in the table, in order of
+/// degree, as (degree, inner coefficients)
+fn primitive_polynomials() -> impl Iterator<Item = (u32, u32)> {
+    let (last_degree, last_a, _) = *SOBOL_TABLE.last().unwrap();
+    (last_degree..SOBOL_BITS as u32)
+        .flat_map(|degree| (0..1u32 << (degree - 1)).map(move |a| (degree, a)))
+        .filter(move |&(degree, a)| (degree, a) > (last_degree, last_a))
+        .filter(|&(degree, a)| is_primitive(degree, a))
+}
+
+/// Whether x^degree + (a's bits) x^(degree-1..1) + 1 has order 2^degree - 1
+fn is_primitive(degree: u32, a: u32) -> bool {
+    let polynomial = (1u64 << degree) | ((a as u64) << 1) | 1;
+    let period = (1u64 << degree) - 1;
+
+    // x^k mod polynomial, by repeated multiplication by x
+    let multiply_by_x = |value: u64| {
+        let shifted = value << 1;
+        if shifted >> degree & 1 == 1 { shifted ^ polynomial } else { shifted }
+    };
+
+    let mut value = 1u64;
+    for k in 1..=period {
+        value = multiply_by_x(value);
+        if value == 1 {
+            return k == period;
+        }
+    }
+    false
+}
+
+/// Random linear scramble: a lower-triangular binary matrix with a unit
+/// diagonal applied to every direction number
+fn scramble(directions: &[u32; SOBOL_BITS], rng: &mut StdRng) -> [u32; SOBOL_BITS] {
+    // Row for output bit i (counted from the top) mixes in the bits above it
+    let rows: Vec<u32> = (0..SOBOL_BITS)
+        .map(|i| {
+            let diagonal = 1u32 << (SOBOL_BITS - 1 - i);
+            let above = if i == 0 { 0 } else { rng.gen::<u32>() & !(u32::MAX >> i) };
+            diagonal | above
+        })
+        .collect();
+
+    directions.map(|v| rows.iter()
+        .enumerate()
+        .fold(0, |x, (i, row)| x | (((row & v).count_ones() & 1) << (SOBOL_BITS - 1 - i))))
+}
+
+/// The first `count` primes
+fn primes(count: usize) -> Vec<u32> {
+    let mut primes = Vec::with_capacity(count);
+    let mut candidate = 2u32;
+    while primes.len() < count {
+        if primes.iter().take_while(|&&p| p * p <= candidate).all(|p| !candidate.is_multiple_of(*p)) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
@@ -0,0 +1,157 @@
+// src/analysis/six_sigma.rs
+
+use serde::{Serialize, Deserialize};
+use anyhow::Result;
+use statrs::distribution::{Normal as StatsNormal, ContinuousCDF};
+use crate::config::CapabilityTarget;
+use super::stack_function::ResolvedStack;
+use super::stackup::{cpk, ContributorSensitivity, StackupAnalysis};
+
+/// How contributor mean shifts are combined (Harry & Stewart)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum MeanShiftMode {
+    /// Every mean sits at its worst shift at once; the drifts add linearly
+    /// on top of the short-term RSS variation
+    Static,
+    /// Shifts are treated as extra variation: each contributor uses its
+    /// long-term σ = tolerance / (3·Cpk) and the stack stays centered
+    Dynamic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SixSigmaSettings {
+    pub mode: MeanShiftMode,
+    /// Used for features that carry no capability target of their own
+    pub default_capability: CapabilityTarget,
+}
+
+impl Default for SixSigmaSettings {
+    fn default() -> Self {
+        Self {
+            mode: MeanShiftMode::Dynamic,
+            // Six Sigma design: tolerance at ±6σ with a 1.5σ long-term drift
+            default_capability: CapabilityTarget::MeanShift { cp: 2.0, k: 1.5 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SixSigmaResult {
+    pub mode: MeanShiftMode,
+    pub mean: f64,
+    pub mean_drift: f64,           // Worst-case drift of the stack mean
+    pub short_term_std_dev: f64,
+    pub long_term_std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub cp: Option<f64>,
+    pub cpk: Option<f64>,
+    pub ppm_above: Option<f64>,
+    pub ppm_below: Option<f64>,
+    pub sensitivity: Vec<ContributorSensitivity>,
+}
+
+/// Smallest Cpk a contributor is allowed; keeps the long-term σ finite
+const MIN_CPK: f64 = 0.01;
+
+impl StackupAnalysis {
+    pub(crate) fn calculate_six_sigma(&self, stack: &ResolvedStack, settings: &SixSigmaSettings) -> Result<SixSigmaResult> {
+        let mean = stack.evaluate(&stack.mean_values());
+        let mut short_term_variance = 0.0;
+        let mut long_term_variance = 0.0;
+        let mut mean_drift = 0.0;
+        let mut contributors = Vec::new();
+
+        for ((contrib, feature), tolerance) in stack.contributors.iter().zip(stack.rss_tolerances()?) {
+            let capability = feature.capability.unwrap_or(settings.default_capability);
+
+            let cp = capability.cp().max(MIN_CPK);
+            let cpk = capability.cpk().clamp(MIN_CPK, cp);
+            let short_term = tolerance / (3.0 * cp);
+            let long_term = tolerance / (3.0 * cpk);
+
+            short_term_variance += short_term.powi(2);
+            long_term_variance += long_term.powi(2);
+            mean_drift += capability.shift_sigmas().abs() * short_term;
+            contributors.push((contrib, feature, long_term));
+        }
+
+        let short_term_std_dev = short_term_variance.sqrt();
+        let long_term_std_dev = long_term_variance.sqrt();
+
+        // The static model keeps short-term spread around a drifted mean, the
+        // dynamic model a centered mean with long-term spread
+        let (std_dev, drift) = match settings.mode {
+            MeanShiftMode::Static => (short_term_std_dev, mean_drift),
+            MeanShiftMode::Dynamic => (long_term_std_dev, 0.0),
+        };
+
+        let mut sensitivity: Vec<ContributorSensitivity> = contributors.into_iter()
+            .map(|(contrib, feature, long_term)| ContributorSensitivity {
+                component_id: contrib.component_id.clone(),
+                feature_id: contrib.feature_id.clone(),
+                contribution_percent: if long_term_variance > 0.0 {
+                    long_term.powi(2) / long_term_variance * 100.0
+                } else {
+                    0.0
+                },
+                nominal_value: feature.dimension.value,
+                variation_range: (mean - 3.0 * long_term, mean + 3.0 * long_term),
+                correlation: None,
+                samples: None,
+                sobol_first_order: None,
+                sobol_total: None,
+            })
+            .collect();
+        sensitivity.sort_by(|a, b| b.contribution_percent
+            .partial_cmp(&a.contribution_percent)
+            .unwrap_or(std::cmp::Ordering::Equal));
+
+        let (cp, cpk, ppm_above, ppm_below) = self.predicted_capability(mean, drift, std_dev);
+
+        Ok(SixSigmaResult {
+            mode: settings.mode,
+            mean,
+            mean_drift,
+            short_term_std_dev,
+            long_term_std_dev,
+            min: mean - drift - 3.0 * std_dev,
+            max: mean + drift + 3.0 * std_dev,
+            cp,
+            cpk,
+            ppm_above,
+            ppm_below,
+            sensitivity,
+        })
+    }
+
+    /// Cp, Cpk and ppm outside each limit for a stack whose mean may sit
+    /// anywhere within `mean ± drift`, taking the worst position for each figure
+    fn predicted_capability(&self, mean: f64, drift: f64, std_dev: f64)
+        -> (Option<f64>, Option<f64>, Option<f64>, Option<f64>)
+    {
+        if std_dev <= 0.0 {
+            return (None, None, None, None);
+        }
+
+        let normal = StatsNormal::new(0.0, 1.0).unwrap();
+        let ppm_above = self.upper_spec_limit
+            .map(|usl| (1.0 - normal.cdf((usl - mean - drift) / std_dev)) * 1_000_000.0);
+        let ppm_below = self.lower_spec_limit
+            .map(|lsl| normal.cdf((lsl - mean + drift) / std_dev) * 1_000_000.0);
+
+        // The mean at the drifted position nearest each limit
+        let cpk = cpk(
+            mean,
+            std_dev,
+            self.upper_spec_limit.map(|usl| usl - drift),
+            self.lower_spec_limit.map(|lsl| lsl + drift),
+        );
+        let cp = match (self.upper_spec_limit, self.lower_spec_limit) {
+            (Some(usl), Some(lsl)) => Some((usl - lsl) / (6.0 * std_dev)),
+            _ => None,
+        };
+
+        (cp, cpk, ppm_above, ppm_below)
+    }
+}
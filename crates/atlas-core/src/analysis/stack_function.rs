@@ -0,0 +1,176 @@
+// src/analysis/stack_function.rs
+
+use anyhow::{bail, Context, Result};
+use crate::config::{Component, Feature};
+use super::expression::{Expr, Interval};
+use super::stackup::{DistributionParams, StackupAnalysis, StackupContribution};
+
+/// A stackup with its contributions resolved to features and distributions,
+/// and the function that turns one value per contributor into the stack value
+pub(crate) struct ResolvedStack<'a> {
+    pub contributors: Vec<(&'a StackupContribution, &'a Feature)>,
+    pub distributions: Vec<DistributionParams>,
+    function: StackFunction,
+}
+
+enum StackFunction {
+    /// Sum of value × sensitivity coefficient
+    Linear(Vec<f64>),
+    /// User expression over the contributors' variables
+    Expression(Expr),
+}
+
+impl StackupAnalysis {
+    /// Resolve the contributions against `components`. Contributions whose
+    /// feature no longer exists are left out.
+    pub(crate) fn resolve<'a>(&'a self, components: &'a [Component]) -> Result<ResolvedStack<'a>> {
+        let contributors: Vec<_> = self.contributions.iter()
+            .filter_map(|contrib| self.get_feature(components, contrib).map(|feature| (contrib, feature)))
+            .collect();
+
+        let expression = self.expression.as_deref()
+            .map(str::trim)
+            .filter(|source| !source.is_empty());
+
+        let function = match expression {
+            None => StackFunction::Linear(contributors.iter()
+                .map(|(contrib, _)| contrib.sensitivity())
+                .collect()),
+            Some(source) => {
+                let names: Vec<Option<&str>> = contributors.iter()
+                    .map(|(contrib, _)| contrib.variable.as_deref())
+                    .collect();
+                for (i, name) in names.iter().enumerate() {
+                    if let Some(name) = name {
+                        if names[..i].contains(&Some(*name)) {
+                            bail!("Variable '{}' is used by more than one contribution", name);
+                        }
+                    }
+                }
+
+                let expr = Expr::parse(source, |name| names.iter().position(|n| *n == Some(name)))
+                    .with_context(|| format!("Invalid stack expression for analysis {}", self.name))?;
+                StackFunction::Expression(expr)
+            },
+        };
+
+        // An entered contribution override wins over the feature's own
+        // distribution; calculated ones just follow the feature
+        let distributions = contributors.iter()
+            .map(|(contrib, feature)| contrib.distribution.clone()
+                .filter(|params| !params.calculated)
+                .unwrap_or_else(|| feature.effective_distribution()))
+            .collect();
+
+        Ok(ResolvedStack { contributors, distributions, function })
+    }
+}
+
+impl ResolvedStack<'_> {
+    pub fn nominal_values(&self) -> Vec<f64> {
+        self.contributors.iter().map(|(_, feature)| feature.dimension.value).collect()
+    }
+
+    /// Mean of each contributor's distribution
+    pub fn mean_values(&self) -> Vec<f64> {
+        self.distributions.iter().map(|d| d.moments().0).collect()
+    }
+
+    pub fn std_devs(&self) -> Vec<f64> {
+        self.distributions.iter().map(|d| d.moments().1).collect()
+    }
+
+    pub fn evaluate(&self, values: &[f64]) -> f64 {
+        match &self.function {
+            StackFunction::Linear(coefficients) => coefficients.iter()
+                .zip(values)
+                .map(|(c, v)| c * v)
+                .sum(),
+            StackFunction::Expression(expr) => expr.evaluate(values),
+        }
+    }
+
+    /// Stack value of each of `count` samples given one column of values per
+    /// contributor
+    pub fn evaluate_columns(&self, columns: &[Vec<f64>], count: usize) -> Vec<f64> {
+        match &self.function {
+            StackFunction::Linear(coefficients) => {
+                let mut results = vec![0.0; count];
+                for (c, column) in coefficients.iter().zip(columns) {
+                    for (result, value) in results.iter_mut().zip(column) {
+                        *result += c * value;
+                    }
+                }
+                results
+            },
+            StackFunction::Expression(expr) => {
+                let mut values = vec![0.0; columns.len()];
+                (0..count).map(|k| {
+                    for (value, column) in values.iter_mut().zip(columns) {
+                        *value = column[k];
+                    }
+                    expr.evaluate(&values)
+                }).collect()
+            },
+        }
+    }
+
+    /// ∂stack/∂value for each contributor at `at`. Exact for linear stacks,
+    /// central differences for expressions, which fail where the expression
+    /// is undefined next to `at`.
+    pub fn coefficients(&self, at: &[f64]) -> Result<Vec<f64>> {
+        match &self.function {
+            StackFunction::Linear(coefficients) => Ok(coefficients.clone()),
+            StackFunction::Expression(expr) => {
+                let mut point = at.to_vec();
+                (0..at.len()).map(|i| {
+                    let h = 1e-6 * at[i].abs().max(1.0);
+                    point[i] = at[i] + h;
+                    let above = expr.evaluate(&point);
+                    point[i] = at[i] - h;
+                    let below = expr.evaluate(&point);
+                    point[i] = at[i];
+                    let coefficient = (above - below) / (2.0 * h);
+                    if !coefficient.is_finite() {
+                        let (contrib, _) = self.contributors[i];
+                        bail!("Sensitivity to {}.{}: stack expression is not defined over the whole tolerance zone",
+                            contrib.component_id, contrib.feature_id);
+                    }
+                    Ok(coefficient)
+                }).collect()
+            },
+        }
+    }
+
+    /// Stack bounds with every feature anywhere within its tolerance zone
+    pub fn worst_case_range(&self) -> Result<(f64, f64)> {
+        let intervals: Vec<Interval> = self.contributors.iter()
+            .map(|(_, feature)| Interval::new(
+                feature.dimension.value - feature.dimension.minus_tolerance,
+                feature.dimension.value + feature.dimension.plus_tolerance,
+            ))
+            .collect();
+
+        let range = match &self.function {
+            StackFunction::Linear(coefficients) => coefficients.iter()
+                .zip(&intervals)
+                .fold(Interval::point(0.0), |acc, (c, x)| Interval::new(
+                    acc.lo + (c * x.lo).min(c * x.hi),
+                    acc.hi + (c * x.lo).max(c * x.hi),
+                )),
+            StackFunction::Expression(expr) => expr.evaluate_interval(&intervals)
+                .context("Worst case: stack expression is not defined over the whole tolerance zone")?,
+        };
+
+        Ok((range.lo, range.hi))
+    }
+
+    /// Each contributor's equal-bilateral tolerance scaled by its sensitivity
+    /// at the means, as used by the tolerance-based RSS variants
+    pub fn rss_tolerances(&self) -> Result<Vec<f64>> {
+        Ok(self.coefficients(&self.mean_values())?.iter()
+            .zip(&self.contributors)
+            .map(|(c, (_, feature))| (c * feature.dimension.bilateral_tolerance()).abs())
+            .collect())
+    }
+}
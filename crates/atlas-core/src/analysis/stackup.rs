@@ -0,0 +1,1600 @@
+// src/analysis/stackup.rs
+
+use serde::{Serialize, Deserialize};
+use rayon::prelude::*;
+use crate::config::Component;
+use crate::config::Feature;
+use uuid::Uuid;
+use chrono;
+use anyhow::Result;
+use super::six_sigma::{SixSigmaResult, SixSigmaSettings};
+use super::stack_function::ResolvedStack;
+use super::correlation::{correlation_factor, CorrelationGroup};
+use super::allocation::AllocationSettings;
+use super::study::StudySettings;
+use super::doe::DoeSettings;
+use super::cost::{estimate_cost, CostEstimate};
+use super::distribution::{number_or_option, MixtureComponent, TAIL_FRACTION};
+use super::monte_carlo::{mean_and_variance, Sampler, Samples, PROGRESS_STEP};
+use super::job::AnalysisProgress;
+use super::tail::{tail_sample_count, MIN_SAMPLED_FAILURES};
+use super::sampling::SamplingStrategy;
+use super::convergence::{band_std_error, percentile_band, std_dev_std_error, ConvergenceSettings, PpmEstimate};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AnalysisMethod {
+    WorstCase,
+    Rss,
+    ModifiedRss,
+    SixSigma,
+    MonteCarlo,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum DistributionType {
+    #[default]
+    Normal, // Most common distribution type as default
+    Uniform,
+    Triangular,
+    LogNormal,
+    Weibull,
+    Beta,           // PERT, from min, mode and max
+    Rayleigh,
+    FoldedNormal,
+    TruncatedNormal,
+    Mixture,
+}
+
+/// Distribution of one feature's actual value. Stored on the feature and,
+/// as an override, on a stackup contribution.
+///
+/// Older project files stored the feature-level parameters as `Some(..)`
+/// options; those still load, with a missing value read as NaN. Their
+/// contributions stored snapshots calculated from the tolerances, with no
+/// `calculated` flag, and load as calculated.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DistributionParams {
+    pub dist_type: DistributionType,
+    /// Derived from the feature's tolerances and kept in step with them,
+    /// rather than entered by the user
+    #[serde(default = "default_calculated")]
+    pub calculated: bool,
+    #[serde(deserialize_with = "number_or_option")]
+    pub mean: f64,
+    #[serde(deserialize_with = "number_or_option")]
+    pub std_dev: f64,           // Used for Normal, LogNormal
+    #[serde(deserialize_with = "number_or_option")]
+    pub min: f64,               // Used for Uniform, Triangular
+    #[serde(deserialize_with = "number_or_option")]
+    pub max: f64,               // Used for Uniform, Triangular
+    pub mode: Option<f64>,      // Used for Triangular, Beta
+    #[serde(default)]
+    pub shape: Option<f64>,     // Used for Weibull
+    #[serde(default)]
+    pub scale: Option<f64>,     // Used for Weibull, Rayleigh
+    #[serde(default)]
+    pub components: Vec<MixtureComponent>, // Used for Mixture
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessCapability {
+    pub upper_spec: Option<f64>,
+    pub lower_spec: Option<f64>,
+    pub cp: Option<f64>,
+    pub cpk: Option<f64>,
+    pub ppm_above: Option<f64>,
+    pub ppm_below: Option<f64>,
+    /// 95% bands on the ppm estimates
+    #[serde(default)]
+    pub ppm_above_band: Option<(f64, f64)>,
+    #[serde(default)]
+    pub ppm_below_band: Option<(f64, f64)>,
+    pub pph_above: Option<f64>,
+    pub pph_below: Option<f64>,
+}
+
+impl DistributionParams {
+    pub fn new_normal(mean: f64, std_dev: f64) -> Self {
+        Self {
+            dist_type: DistributionType::Normal,
+            calculated: false,
+            mean,
+            std_dev,
+            min: 0.0,
+            max: 0.0,
+            mode: None,
+            shape: None,
+            scale: None,
+            components: Vec::new(),
+        }
+    }
+
+    pub fn new_uniform(min: f64, max: f64) -> Self {
+        Self {
+            dist_type: DistributionType::Uniform,
+            calculated: false,
+            mean: 0.0,
+            std_dev: 0.0,
+            min,
+            max,
+            mode: None,
+            shape: None,
+            scale: None,
+            components: Vec::new(),
+        }
+    }
+
+    pub fn new_triangular(min: f64, max: f64, mode: f64) -> Self {
+        Self {
+            dist_type: DistributionType::Triangular,
+            calculated: false,
+            mean: 0.0,
+            std_dev: 0.0,
+            min,
+            max,
+            mode: Some(mode),
+            shape: None,
+            scale: None,
+            components: Vec::new(),
+        }
+    }
+
+    pub fn new_lognormal(mean: f64, std_dev: f64) -> Self {
+        Self {
+            dist_type: DistributionType::LogNormal,
+            calculated: false,
+            mean,
+            std_dev,
+            min: 0.0,
+            max: 0.0,
+            mode: None,
+            shape: None,
+            scale: None,
+            components: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackupContribution {
+    pub component_id: String,
+    pub feature_id: String,
+    pub direction: f64,         // 1.0 or -1.0
+    pub half_count: bool,       // For cases where only half the tolerance applies
+    pub distribution: Option<DistributionParams>,
+    #[serde(default = "default_coefficient")]
+    pub coefficient: f64,       // Sensitivity, e.g. a lever ratio or cos(angle)
+    #[serde(default)]
+    pub variable: Option<String>, // Name of the feature value in the stack expression
+}
+
+fn default_coefficient() -> f64 {
+    1.0
+}
+
+fn default_calculated() -> bool {
+    true
+}
+
+impl StackupContribution {
+    /// ∂stack/∂feature for a linear stack
+    pub fn sensitivity(&self) -> f64 {
+        self.direction * self.coefficient * if self.half_count { 0.5 } else { 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackupAnalysis {
+    pub id: String,
+    pub name: String,
+    pub contributions: Vec<StackupContribution>,
+    pub methods: Vec<AnalysisMethod>,
+    pub monte_carlo_settings: Option<MonteCarloSettings>,
+    pub upper_spec_limit: Option<f64>, 
+    pub lower_spec_limit: Option<f64>, 
+    #[serde(default)]
+    pub modified_rss_settings: Option<ModifiedRssSettings>,
+    #[serde(default)]
+    pub six_sigma_settings: Option<SixSigmaSettings>,
+    /// Stack function over the contributions' variables, e.g. `a + b*cos(theta) - d/2`.
+    /// The stack is the linear sum of the contributions when unset.
+    #[serde(default)]
+    pub expression: Option<String>,
+    /// Contributors sampled together in Monte Carlo
+    #[serde(default)]
+    pub correlation_groups: Vec<CorrelationGroup>,
+    /// Target, method and feature bounds last used to allocate tolerances
+    #[serde(default)]
+    pub allocation: Option<AllocationSettings>,
+    /// Parameters last swept in a what-if study
+    #[serde(default)]
+    pub study: Option<StudySettings>,
+    /// Factors and design of the last designed experiment
+    #[serde(default)]
+    pub doe: Option<DoeSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloSettings {
+    pub iterations: usize,
+    pub confidence: f64,
+    pub seed: Option<u64>,
+    /// Keep sampling in batches until the estimates converge; `iterations`
+    /// is then the first batch
+    #[serde(default)]
+    pub convergence: Option<ConvergenceSettings>,
+    #[serde(default)]
+    pub sampling: SamplingStrategy,
+}
+impl Default for MonteCarloSettings {
+    fn default() -> Self {
+        Self {
+            iterations: 10000,
+            confidence: 0.9995,
+            seed: None,
+            convergence: None,
+            sampling: SamplingStrategy::Random,
+        }
+    }
+}
+
+/// Correction applied to the RSS result to account for short or unevenly
+/// toleranced stacks, where plain RSS is optimistic
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum MrssCorrection {
+    /// Constant factor, e.g. Bender's 1.5
+    Fixed(f64),
+    /// Factor derived from the number of contributors and the ratio of the
+    /// worst case to the RSS tolerance (Drake); 1.5 for equal tolerances
+    ContributorCount,
+}
+
+impl MrssCorrection {
+    pub const BENDER: f64 = 1.5;
+
+    /// Correction factor for a stack with the given contributor tolerances
+    pub fn factor(&self, tolerances: &[f64]) -> f64 {
+        match self {
+            MrssCorrection::Fixed(factor) => *factor,
+            MrssCorrection::ContributorCount => {
+                let n = tolerances.len() as f64;
+                let t_wc: f64 = tolerances.iter().map(|t| t.abs()).sum();
+                let t_rss = tolerances.iter().map(|t| t * t).sum::<f64>().sqrt();
+                if n <= 1.0 || t_rss <= 0.0 {
+                    1.0
+                } else {
+                    0.5 * (t_wc - t_rss) / (t_rss * (n.sqrt() - 1.0)) + 1.0
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifiedRssSettings {
+    pub correction: MrssCorrection,
+}
+
+impl Default for ModifiedRssSettings {
+    fn default() -> Self {
+        Self {
+            correction: MrssCorrection::Fixed(MrssCorrection::BENDER),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisResults {
+    pub analysis_id: String,
+    pub timestamp: String,
+    pub nominal: f64,
+    pub worst_case: Option<WorstCaseResult>,
+    pub rss: Option<RssResult>,
+    #[serde(default)]
+    pub modified_rss: Option<ModifiedRssResult>,
+    #[serde(default)]
+    pub six_sigma: Option<SixSigmaResult>,
+    pub monte_carlo: Option<MonteCarloResult>,
+    pub process_capability: Option<ProcessCapability>,
+    /// Stopped early; Monte Carlo covers only the samples drawn before that
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Cost of the features at their tolerances, when any has a process
+    #[serde(default)]
+    pub cost: Option<CostEstimate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributorSensitivity {
+    pub component_id: String,
+    pub feature_id: String,
+    pub contribution_percent: f64,    // Percentage contribution to total variation
+    pub nominal_value: f64,           // Original nominal value
+    pub variation_range: (f64, f64),  // Min/max or statistical range
+    pub correlation: Option<f64>,     // Only used for Monte Carlo
+    pub samples: Option<Vec<(f64, f64)>>, // Optional (feature_value, stackup_result) pairs
+    /// Share of the stack variance from this contributor alone (Monte Carlo only)
+    #[serde(default)]
+    pub sobol_first_order: Option<f64>,
+    /// Share of the stack variance from this contributor including its
+    /// interactions with the others (Monte Carlo only)
+    #[serde(default)]
+    pub sobol_total: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorstCaseResult {
+    pub min: f64,
+    pub max: f64,
+    pub sensitivity: Vec<ContributorSensitivity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RssResult {
+    #[serde(default)]
+    pub mean: f64,  // Stack mean after centering each contributor's tolerance zone
+    pub min: f64,
+    pub max: f64,
+    pub std_dev: f64,
+    pub sensitivity: Vec<ContributorSensitivity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifiedRssResult {
+    pub correction_factor: f64,
+    pub min: f64,
+    pub max: f64,
+    pub std_dev: f64,
+    pub sensitivity: Vec<ContributorSensitivity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloResult {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Std dev the stack would have with the same contributors sampled
+    /// independently; only set when correlation groups apply
+    #[serde(default)]
+    pub independent_std_dev: Option<f64>,
+    pub confidence_intervals: Vec<ConfidenceInterval>,
+    pub histogram: Vec<(f64, usize)>,
+    pub sensitivity: Vec<ContributorSensitivity>,
+    /// Samples actually drawn
+    #[serde(default)]
+    pub iterations: usize,
+    /// Whether the adaptive run met its tolerance; None for a fixed count
+    #[serde(default)]
+    pub converged: Option<bool>,
+    #[serde(default)]
+    pub mean_std_error: f64,
+    #[serde(default)]
+    pub std_dev_std_error: f64,
+    /// Sample fractions beyond the spec limits
+    #[serde(default)]
+    pub ppm_below: Option<PpmEstimate>,
+    #[serde(default)]
+    pub ppm_above: Option<PpmEstimate>,
+    /// Importance sampled fractions beyond the spec limits, made when the
+    /// plain sample saw too few failures to estimate them
+    #[serde(default)]
+    pub tail_below: Option<PpmEstimate>,
+    #[serde(default)]
+    pub tail_above: Option<PpmEstimate>,
+    /// How the samples were drawn
+    #[serde(default)]
+    pub sampling: SamplingStrategy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub confidence_level: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+    /// 95% bands on the two percentile estimates
+    #[serde(default)]
+    pub lower_band: Option<(f64, f64)>,
+    #[serde(default)]
+    pub upper_band: Option<(f64, f64)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpecLimit {
+    Upper,
+    Lower,
+}
+
+/// A predicted stack bound that falls outside one of the analysis spec limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecViolation {
+    pub method: AnalysisMethod,
+    pub limit: SpecLimit,
+    pub limit_value: f64,
+    pub predicted: f64,
+}
+
+impl StackupAnalysis {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            contributions: Vec::new(),
+            methods: vec![AnalysisMethod::WorstCase],
+            monte_carlo_settings: None,
+            upper_spec_limit: None,  
+            lower_spec_limit: None,  
+            modified_rss_settings: None,
+            six_sigma_settings: None,
+            expression: None,
+            correlation_groups: Vec::new(),
+            allocation: None,
+            study: None,
+            doe: None,
+        }
+    }
+
+    pub fn add_contribution(
+        &mut self,
+        component_id: String,
+        feature_id: String,
+        direction: f64,
+        half_count: bool,
+        distribution: Option<DistributionParams>
+    ) {
+        self.contributions.push(StackupContribution {
+            component_id,
+            feature_id,
+            direction,
+            half_count,
+            distribution,
+            coefficient: 1.0,
+            variable: None,
+        });
+    }
+
+    pub fn calculate_nominal(&self, components: &[Component]) -> Result<f64> {
+        let stack = self.resolve(components)?;
+        Ok(stack.evaluate(&stack.nominal_values()))
+    }
+
+    /// Stack value with every contributor at the middle of its tolerance zone.
+    /// Differs from the nominal when any contributor has asymmetric tolerances.
+    pub fn calculate_mean(&self, components: &[Component]) -> Result<f64> {
+        let stack = self.resolve(components)?;
+        Ok(stack.evaluate(&stack.mean_values()))
+    }
+
+    pub fn get_feature<'a>(&self, components: &'a [Component], contrib: &StackupContribution) -> Option<&'a Feature> {
+        components.iter()
+            .find(|c| c.name == contrib.component_id)?
+            .features.iter()
+            .find(|f| f.name == contrib.feature_id)
+    }
+
+    /// Default parameters for the feature's distribution type, fitted to its
+    /// tolerance zone. The result is marked `calculated`.
+    pub fn calculate_distribution_params(feature: &Feature) -> DistributionParams {
+        // Get feature's selected distribution type or default to Normal
+        let dist_type = feature.distribution.unwrap_or(DistributionType::Normal);
+
+        // Center on the tolerance zone rather than the nominal, so an asymmetric
+        // tolerance such as +0.2/-0.0 samples around value + 0.1
+        let mean = feature.dimension.mean();
+        let half_tolerance = feature.dimension.bilateral_tolerance();
+        let std_dev = half_tolerance / 3.0; // Using 6-sigma for 99.73% coverage
+
+        let mut params = match dist_type {
+            DistributionType::Normal => DistributionParams::new_normal(
+                mean,
+                std_dev
+            ),
+            DistributionType::Uniform => DistributionParams::new_uniform(
+                mean - half_tolerance,
+                mean + half_tolerance
+            ),
+            DistributionType::Triangular => DistributionParams::new_triangular(
+                mean - half_tolerance,
+                mean + half_tolerance,
+                mean // mode is the middle of the tolerance zone
+            ),
+            DistributionType::LogNormal => DistributionParams::new_lognormal(
+                mean,
+                std_dev
+            ),
+            // The one-sided distributions start at the lower limit and put
+            // 99.73% of parts below the upper limit
+            DistributionType::Weibull => {
+                let shape = 2.0;
+                DistributionParams::new_weibull(
+                    mean - half_tolerance,
+                    shape,
+                    2.0 * half_tolerance / (-TAIL_FRACTION.ln()).powf(1.0 / shape)
+                )
+            },
+            DistributionType::Beta => DistributionParams::new_pert(
+                mean - half_tolerance,
+                mean + half_tolerance,
+                mean
+            ),
+            DistributionType::Rayleigh => DistributionParams::new_rayleigh(
+                mean - half_tolerance,
+                2.0 * half_tolerance / (-2.0 * TAIL_FRACTION.ln()).sqrt()
+            ),
+            DistributionType::FoldedNormal => DistributionParams::new_folded_normal(
+                mean - half_tolerance,
+                mean - half_tolerance,
+                2.0 * half_tolerance / 3.0
+            ),
+            DistributionType::TruncatedNormal => DistributionParams::new_truncated_normal(
+                mean,
+                std_dev,
+                mean - half_tolerance,
+                mean + half_tolerance
+            ),
+            // Two equal populations, one at each half of the tolerance zone
+            DistributionType::Mixture => DistributionParams::new_mixture(vec![
+                MixtureComponent {
+                    weight: 0.5,
+                    distribution: DistributionParams::new_normal(mean - half_tolerance / 2.0, half_tolerance / 6.0),
+                },
+                MixtureComponent {
+                    weight: 0.5,
+                    distribution: DistributionParams::new_normal(mean + half_tolerance / 2.0, half_tolerance / 6.0),
+                },
+            ]),
+        };
+
+        params.calculated = true;
+        params
+    }
+
+    pub fn run_analysis(&self, components: &[Component]) -> Result<AnalysisResults> {
+        self.run_analysis_with_progress(components, &AnalysisProgress::default())
+    }
+
+    /// `run_analysis`, reporting Monte Carlo iterations to `progress` and
+    /// stopping early with partial results once it is cancelled
+    pub fn run_analysis_with_progress(&self, components: &[Component], progress: &AnalysisProgress) -> Result<AnalysisResults> {
+        let stack = self.resolve(components)?;
+
+        let mut results = AnalysisResults {
+            analysis_id: self.id.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            nominal: stack.evaluate(&stack.nominal_values()),
+            worst_case: None,
+            rss: None,
+            modified_rss: None,
+            six_sigma: None,
+            monte_carlo: None,
+            process_capability: None,
+            cancelled: false,
+            cost: estimate_cost(&stack),
+        };
+
+        for method in &self.methods {
+            match method {
+                AnalysisMethod::WorstCase => {
+                    results.worst_case = Some(Self::calculate_worst_case(&stack)?);
+                },
+                AnalysisMethod::Rss => {
+                    results.rss = Some(Self::calculate_rss(&stack)?);
+                },
+                AnalysisMethod::ModifiedRss => {
+                    let settings = self.modified_rss_settings.clone().unwrap_or_default();
+                    results.modified_rss = Some(Self::calculate_modified_rss(&stack, &settings)?);
+                },
+                AnalysisMethod::SixSigma => {
+                    let settings = self.six_sigma_settings.clone().unwrap_or_default();
+                    results.six_sigma = Some(self.calculate_six_sigma(&stack, &settings)?);
+                },
+                AnalysisMethod::MonteCarlo => {
+                    if let Some(settings) = &self.monte_carlo_settings {
+                        let correlation = correlation_factor(&stack, &self.correlation_groups)?;
+                        results.monte_carlo = self.run_monte_carlo(&stack, settings, correlation.as_deref(), progress)?;
+                        results.cancelled = progress.is_cancelled();
+                    }
+                }
+            }
+        }
+        if let Some(mc) = &results.monte_carlo {
+            let process_capability = if let (Some(usl), Some(lsl)) = 
+                (self.upper_spec_limit, self.lower_spec_limit) {
+                let std_dev = mc.std_dev;
+                let mean = mc.mean;
+                
+                // Calculate Cp
+                let cp = if std_dev > 0.0 {
+                    Some((usl - lsl) / (6.0 * std_dev))
+                } else {
+                    None
+                };
+
+                let cpk = cpk(mean, std_dev, Some(usl), Some(lsl));
+
+                // PPM from the simulated distribution, using the tail estimate
+                // where the plain sample saw too few failures
+                let below = mc.tail_below.or(mc.ppm_below);
+                let above = mc.tail_above.or(mc.ppm_above);
+                let ppm_below = below.map(|estimate| estimate.ppm);
+                let ppm_above = above.map(|estimate| estimate.ppm);
+
+                Some(ProcessCapability {
+                    upper_spec: Some(usl),
+                    lower_spec: Some(lsl),
+                    cp,
+                    cpk,
+                    ppm_above,
+                    ppm_below,
+                    ppm_above_band: above.map(|estimate| (estimate.lower, estimate.upper)),
+                    ppm_below_band: below.map(|estimate| (estimate.lower, estimate.upper)),
+                    // Parts per hour, assuming 3600 parts per hour
+                    pph_above: ppm_above.map(|ppm| ppm * 3.6),
+                    pph_below: ppm_below.map(|ppm| ppm * 3.6),
+                })
+            } else {
+                None
+            };
+
+            results.process_capability = process_capability;
+        }
+
+        Ok(results)
+    }
+
+    /// Monte Carlo range held against the spec limits: the interval at the
+    /// configured confidence level, or the sampled range without one
+    pub fn monte_carlo_range(&self, mc: &MonteCarloResult) -> (f64, f64) {
+        let confidence = self.monte_carlo_settings.as_ref()
+            .map(|s| s.confidence.clamp(0.0, 0.9999))
+            .unwrap_or(1.0);
+        mc.confidence_intervals.iter()
+            .find(|ci| (ci.confidence_level - confidence).abs() < 1e-9)
+            .map_or((mc.min, mc.max), |ci| (ci.lower_bound, ci.upper_bound))
+    }
+
+    /// Compare the predicted range of every method in `results` against the spec limits.
+    /// Monte Carlo is judged on the interval at the configured confidence level rather
+    /// than on the raw sample extremes.
+    pub fn check_spec_limits(&self, results: &AnalysisResults) -> Vec<SpecViolation> {
+        let mut ranges = Vec::new();
+
+        if let Some(wc) = &results.worst_case {
+            ranges.push((AnalysisMethod::WorstCase, wc.min, wc.max));
+        }
+        if let Some(rss) = &results.rss {
+            ranges.push((AnalysisMethod::Rss, rss.min, rss.max));
+        }
+        if let Some(mrss) = &results.modified_rss {
+            ranges.push((AnalysisMethod::ModifiedRss, mrss.min, mrss.max));
+        }
+        if let Some(six_sigma) = &results.six_sigma {
+            ranges.push((AnalysisMethod::SixSigma, six_sigma.min, six_sigma.max));
+        }
+        if let Some(mc) = &results.monte_carlo {
+            let (min, max) = self.monte_carlo_range(mc);
+            ranges.push((AnalysisMethod::MonteCarlo, min, max));
+        }
+
+        // Allow for round-off so a stack that lands exactly on a limit is not flagged
+        let epsilon = |limit: f64| 1e-9 * limit.abs().max(1.0);
+
+        let mut violations = Vec::new();
+        for (method, min, max) in ranges {
+            if let Some(usl) = self.upper_spec_limit {
+                if max > usl + epsilon(usl) {
+                    violations.push(SpecViolation {
+                        method,
+                        limit: SpecLimit::Upper,
+                        limit_value: usl,
+                        predicted: max,
+                    });
+                }
+            }
+            if let Some(lsl) = self.lower_spec_limit {
+                if min < lsl - epsilon(lsl) {
+                    violations.push(SpecViolation {
+                        method,
+                        limit: SpecLimit::Lower,
+                        limit_value: lsl,
+                        predicted: min,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    fn calculate_worst_case(stack: &ResolvedStack) -> Result<WorstCaseResult> {
+        let (min, max) = stack.worst_case_range()?;
+        let coefficients = stack.coefficients(&stack.nominal_values())?;
+        let mut sensitivities = Vec::new();
+
+        // Calculate total variation first
+        let total_variation: f64 = stack.contributors.iter()
+            .zip(&coefficients)
+            .map(|((_, feature), c)| (feature.dimension.plus_tolerance + feature.dimension.minus_tolerance) * c.abs())
+            .sum();
+
+        // Calculate individual contributions
+        for ((contrib, feature), coefficient) in stack.contributors.iter().zip(&coefficients) {
+            // Calculate contribution to overall stack
+            let nominal = feature.dimension.value * coefficient;
+            let total_tol = (feature.dimension.plus_tolerance + feature.dimension.minus_tolerance)
+                * coefficient.abs();
+
+            // Calculate individual min/max contributions
+            let (contrib_min, contrib_max) = if *coefficient > 0.0 {
+                (nominal - feature.dimension.minus_tolerance * coefficient.abs(),
+                 nominal + feature.dimension.plus_tolerance * coefficient.abs())
+            } else {
+                (nominal - feature.dimension.plus_tolerance * coefficient.abs(),
+                 nominal + feature.dimension.minus_tolerance * coefficient.abs())
+            };
+
+            // Calculate sensitivity
+            let contribution_percent = if total_variation > 0.0 {
+                total_tol / total_variation * 100.0
+            } else {
+                0.0
+            };
+
+            sensitivities.push(ContributorSensitivity {
+                component_id: contrib.component_id.clone(),
+                feature_id: contrib.feature_id.clone(),
+                contribution_percent,
+                nominal_value: feature.dimension.value,
+                variation_range: (contrib_min, contrib_max),
+                correlation: None,
+                samples: None,
+                sobol_first_order: None,
+                sobol_total: None,
+            });
+        }
+
+        // Sort sensitivities by contribution percentage
+        sensitivities.sort_by(|a, b| b.contribution_percent.partial_cmp(&a.contribution_percent).unwrap());
+
+        Ok(WorstCaseResult { min, max, sensitivity: sensitivities })
+    }
+
+    fn calculate_rss(stack: &ResolvedStack) -> Result<RssResult> {
+        // Each contributor enters with the mean and std dev of its distribution;
+        // for the default normal that is the middle of the tolerance zone and a
+        // third of the equal-bilateral tolerance. Nonlinear stacks are linearized
+        // at the means.
+        let means = stack.mean_values();
+        let mean = stack.evaluate(&means);
+        let tolerances: Vec<f64> = stack.coefficients(&means)?.iter()
+            .zip(stack.std_devs())
+            .map(|(c, std_dev)| (3.0 * c * std_dev).abs())
+            .collect();
+        let sum_squares: f64 = tolerances.iter().map(|t| t.powi(2)).sum();
+        let std_dev = sum_squares.sqrt() / 3.0;
+        let mut sensitivities = Vec::new();
+    
+        // Calculate sensitivities
+        for ((contrib, feature), tolerance) in stack.contributors.iter().zip(&tolerances) {
+            let variance = tolerance.powi(2);
+            let contribution_percent = if sum_squares > 0.0 {
+                variance / sum_squares * 100.0
+            } else {
+                0.0
+            };
+    
+            sensitivities.push(ContributorSensitivity {
+                component_id: contrib.component_id.clone(),
+                feature_id: contrib.feature_id.clone(),
+                contribution_percent,
+                nominal_value: feature.dimension.value,
+                variation_range: (
+                    mean - 3.0 * (variance).sqrt(),
+                    mean + 3.0 * (variance).sqrt()
+                ),
+                correlation: None,
+                samples: None,
+                sobol_first_order: None,
+                sobol_total: None,
+            });
+        }
+    
+        // Sort sensitivities by contribution percentage
+        sensitivities.sort_by(|a, b| b.contribution_percent.partial_cmp(&a.contribution_percent).unwrap());
+    
+        Ok(RssResult {
+            mean,
+            min: mean - 3.0 * std_dev,
+            max: mean + 3.0 * std_dev,
+            std_dev,
+            sensitivity: sensitivities,
+        })
+    }
+
+    fn calculate_modified_rss(stack: &ResolvedStack, settings: &ModifiedRssSettings) -> Result<ModifiedRssResult> {
+        let rss = Self::calculate_rss(stack)?;
+        let correction_factor = settings.correction.factor(&stack.rss_tolerances()?);
+        let std_dev = rss.std_dev * correction_factor;
+
+        Ok(ModifiedRssResult {
+            correction_factor,
+            min: rss.mean - 3.0 * std_dev,
+            max: rss.mean + 3.0 * std_dev,
+            std_dev,
+            // Scaling the stack leaves each contributor's share of the variation unchanged
+            sensitivity: rss.sensitivity,
+        })
+    }
+
+    /// `correlation` is the Cholesky factor of the contributors' correlation
+    /// matrix; correlated samples go through a Gaussian copula. None if the
+    /// run was cancelled before drawing anything.
+    fn run_monte_carlo(
+        &self,
+        stack: &ResolvedStack,
+        settings: &MonteCarloSettings,
+        correlation: Option<&[Vec<f64>]>,
+        progress: &AnalysisProgress,
+    ) -> Result<Option<MonteCarloResult>> {
+        let mut sampler = Sampler::new(stack, correlation, settings);
+        let mut samples = Samples::default();
+        progress.start(settings.convergence.as_ref()
+            .map_or(settings.iterations, |c| c.max_iterations.max(settings.iterations)));
+
+        // Run simulation, in batches until converged when adaptive
+        let mut batch = settings.iterations;
+        let mut converged = None;
+        loop {
+            // Draw in steps so progress and cancellation are seen
+            let mut remaining = batch;
+            while remaining > 0 && !progress.is_cancelled() {
+                let step = remaining.min(PROGRESS_STEP);
+                sampler.draw(step, &mut samples);
+                progress.advance(step);
+                remaining -= step;
+            }
+
+            if progress.is_cancelled() {
+                break;
+            }
+            let Some(convergence) = &settings.convergence else { break };
+            let stable = Self::monte_carlo_converged(&samples.results, settings.confidence, convergence.tolerance);
+            if stable || samples.results.len() >= convergence.max_iterations {
+                converged = Some(stable);
+                break;
+            }
+            batch = convergence.batch_size.max(1)
+                .min(convergence.max_iterations - samples.results.len());
+        }
+
+        if samples.results.is_empty() {
+            return Ok(None);
+        }
+        let Samples { columns, results: mut stackup_results } = samples;
+
+        // Calculate overall statistics
+        let (mean, variance) = mean_and_variance(&stackup_results);
+        let std_dev = variance.sqrt();
+
+        // Linearized sensitivity of every contributor for attributing the stack
+        // variation: (variance, correlation) of value × coefficient per contributor
+        let coefficients = stack.coefficients(&stack.mean_values())?;
+        let contrib_stats: Vec<(f64, f64)> = columns.par_iter()
+            .zip(&coefficients)
+            .map(|(column, coefficient)| {
+                let (value_mean, value_variance) = mean_and_variance(column);
+                let contrib_variance = coefficient * coefficient * value_variance;
+
+                let correlation = if variance > 0.0 && value_variance > 0.0 && column.len() > 1 {
+                    let covariance = column.iter()
+                        .zip(&stackup_results)
+                        .map(|(x, y)| (x - value_mean) * (y - mean))
+                        .sum::<f64>() / (column.len() - 1) as f64;
+                    coefficient.signum() * covariance / (value_variance.sqrt() * std_dev)
+                } else {
+                    0.0
+                };
+
+                (contrib_variance, correlation)
+            })
+            .collect();
+
+        // Calculate total of all variance contributions
+        let total_contrib = contrib_stats.iter()
+            .map(|(variance, correlation)| variance * correlation.abs())
+            .sum::<f64>();
+
+        // Variance-based indices, which hold for nonlinear stacks and show interactions
+        let sobol = sampler.sobol_indices(&columns, &stackup_results);
+
+        // Now create sensitivities with properly normalized percentages
+        let mut sensitivities = Vec::new();
+        for (i, ((contrib, _), column)) in stack.contributors.iter().zip(&columns).enumerate() {
+            let contrib_mean = mean_and_variance(column).0;
+            let min_val = column.iter().copied().fold(f64::INFINITY, f64::min);
+            let max_val = column.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+            let (variance, correlation) = contrib_stats[i];
+
+            // Calculate normalized contribution percentage
+            let contribution_percent = if total_contrib > 0.0 {
+                (variance * correlation.abs() / total_contrib) * 100.0
+            } else if i == 0 {
+                100.0 // If no variance, assign all contribution to first component
+            } else {
+                0.0
+            };
+
+            // Create visualization samples
+            let sample_count = column.len().min(1000);
+            let step = column.len().checked_div(sample_count).unwrap_or(1);
+            let visualization_samples = column.iter()
+                .step_by(step)
+                .take(sample_count)
+                .map(|val| (*val, mean))
+                .collect();
+
+            sensitivities.push(ContributorSensitivity {
+                component_id: contrib.component_id.clone(),
+                feature_id: contrib.feature_id.clone(),
+                contribution_percent,
+                nominal_value: contrib_mean,
+                variation_range: (min_val, max_val),
+                correlation: Some(correlation),
+                samples: Some(visualization_samples),
+                sobol_first_order: sobol.as_ref().map(|indices| indices[i].0),
+                sobol_total: sobol.as_ref().map(|indices| indices[i].1),
+            });
+        }
+
+        // Sort by contribution percentage
+        sensitivities.sort_by(|a, b| b.contribution_percent
+            .partial_cmp(&a.contribution_percent)
+            .unwrap_or(std::cmp::Ordering::Equal));
+
+        // Without correlation the stack variance is the sum of the contribution
+        // variances (exact for linear stacks, first order otherwise)
+        let independent_std_dev = correlation.map(|_| contrib_stats.iter()
+            .map(|(variance, _)| variance)
+            .sum::<f64>()
+            .sqrt());
+
+        let n = stackup_results.len();
+        let mean_std_error = std_dev / (n.max(1) as f64).sqrt();
+        let std_dev_std_error = std_dev_std_error(&stackup_results, mean, std_dev);
+        let ppm_below = self.lower_spec_limit
+            .map(|lsl| PpmEstimate::new(stackup_results.iter().filter(|x| **x < lsl).count(), n));
+        let ppm_above = self.upper_spec_limit
+            .map(|usl| PpmEstimate::new(stackup_results.iter().filter(|x| **x > usl).count(), n));
+
+        // Too few sampled failures to say much about the tail: importance sample it
+        let tail_samples = tail_sample_count(settings.iterations);
+        let mut estimate_tail = |limit: Option<f64>, sampled: Option<PpmEstimate>, above: bool| {
+            let limit = limit.filter(|_| sampled.is_some_and(|p| p.count < MIN_SAMPLED_FAILURES))?;
+            if progress.is_cancelled() {
+                return None;
+            }
+            progress.extend(tail_samples);
+            sampler.estimate_tail(limit, above, tail_samples, progress)
+        };
+        let tail_below = estimate_tail(self.lower_spec_limit, ppm_below, false);
+        let tail_above = estimate_tail(self.upper_spec_limit, ppm_above, true);
+
+        Ok(Some(MonteCarloResult {
+            min: stackup_results.iter().copied().fold(f64::INFINITY, f64::min),
+            max: stackup_results.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std_dev,
+            independent_std_dev,
+            confidence_intervals: Self::calculate_confidence_intervals(&mut stackup_results, settings.confidence),
+            histogram: Self::calculate_histogram(&stackup_results, 20),
+            sensitivity: sensitivities,
+            iterations: n,
+            converged,
+            mean_std_error,
+            std_dev_std_error,
+            ppm_below,
+            ppm_above,
+            tail_below,
+            tail_above,
+            sampling: settings.sampling,
+        }))
+    }
+
+    /// Whether the standard errors of the mean, the std dev and both
+    /// percentile bounds at `confidence` are all within `tolerance` × std dev
+    fn monte_carlo_converged(results: &[f64], confidence: f64, tolerance: f64) -> bool {
+        let n = results.len() as f64;
+        if n < 2.0 {
+            return false;
+        }
+
+        let (mean, variance) = mean_and_variance(results);
+        let std_dev = variance.sqrt();
+        if std_dev <= 0.0 {
+            return true;
+        }
+
+        let mut sorted = results.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let alpha = 1.0 - confidence.clamp(0.0, 0.9999);
+        let limit = tolerance * std_dev;
+
+        std_dev / n.sqrt() <= limit
+            && std_dev_std_error(results, mean, std_dev) <= limit
+            && band_std_error(percentile_band(&sorted, alpha / 2.0)) <= limit
+            && band_std_error(percentile_band(&sorted, 1.0 - alpha / 2.0)) <= limit
+    }
+
+/// Calculate confidence intervals directly from Monte Carlo results
+/// Uses actual simulation data which naturally accounts for the combined effects
+/// of different distributions in the stack.
+/// Calculate confidence intervals directly from Monte Carlo results
+fn calculate_confidence_intervals(results: &mut [f64], user_confidence: f64) -> Vec<ConfidenceInterval> {
+    // Sort results for percentile calculations
+    results.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let n = results.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // Start with 100% interval using actual min/max values
+    let mut intervals = vec![ConfidenceInterval {
+        confidence_level: 1.0,
+        lower_bound: results[0], // Min value
+        upper_bound: results[n - 1], // Max value
+        lower_band: None,
+        upper_band: None,
+    }];
+
+    // Add standard confidence levels
+    let standard_levels = vec![
+        0.90f64,
+        0.95f64,
+        0.99f64,
+        user_confidence.clamp(0.0, 0.9999),
+    ];
+
+    intervals.extend(standard_levels.into_iter().map(|confidence| {
+        let alpha = 1.0 - confidence;
+        let lower_index = ((alpha / 2.0) * (n as f64)).round() as usize;
+        let upper_index = ((1.0 - alpha / 2.0) * (n as f64)).round() as usize;
+
+        // Clamp indices to valid range
+        let lower_index = lower_index.min(n - 1);
+        let upper_index = upper_index.min(n - 1);
+
+        ConfidenceInterval {
+            confidence_level: confidence,
+            lower_bound: results[lower_index],
+            upper_bound: results[upper_index],
+            lower_band: Some(percentile_band(results, alpha / 2.0)),
+            upper_band: Some(percentile_band(results, 1.0 - alpha / 2.0)),
+        }
+    }));
+
+    intervals
+}
+
+    fn calculate_histogram(results: &[f64], num_bins: usize) -> Vec<(f64, usize)> {
+        if results.is_empty() {
+            return Vec::new();
+        }
+
+        let min = results.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = results.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let bin_width = (max - min) / num_bins as f64;
+        
+        (0..num_bins)
+            .map(|i| {
+                let bin_start = min + i as f64 * bin_width;
+                (
+                    bin_start,
+                    results.iter()
+                        .filter(|&x| *x >= bin_start && *x < bin_start + bin_width)
+                        .count()
+                )
+            })
+            .collect()
+    }
+}
+
+/// Cpk of a normal process with `mean` and `std_dev` against whichever
+/// limits are set; None without a limit or without spread
+pub fn cpk(mean: f64, std_dev: f64, upper: Option<f64>, lower: Option<f64>) -> Option<f64> {
+    if std_dev <= 0.0 {
+        return None;
+    }
+    let cpu = upper.map(|usl| (usl - mean) / (3.0 * std_dev));
+    let cpl = lower.map(|lsl| (mean - lsl) / (3.0 * std_dev));
+    match (cpu, cpl) {
+        (Some(u), Some(l)) => Some(u.min(l)),
+        (u, l) => u.or(l),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FeatureType;
+    use rand::prelude::*;
+    use statrs::distribution::{Normal as StatsNormal, ContinuousCDF};
+    use crate::analysis::convergence::PpmMethod;
+    use crate::analysis::test_support::stack_of;
+
+    #[test]
+    fn test_modified_rss_analysis() {
+        let (components, mut analysis) = stack_of(&[0.1, 0.1, 0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::Rss, AnalysisMethod::ModifiedRss];
+
+        // Equal tolerances give Bender's factor from the contributor-count formula too
+        for correction in [MrssCorrection::Fixed(MrssCorrection::BENDER), MrssCorrection::ContributorCount] {
+            analysis.modified_rss_settings = Some(ModifiedRssSettings { correction });
+            let results = analysis.run_analysis(&components).unwrap();
+            let rss = results.rss.unwrap();
+            let mrss = results.modified_rss.unwrap();
+
+            assert!((mrss.correction_factor - 1.5).abs() < 1e-12);
+            assert!((mrss.std_dev - 1.5 * rss.std_dev).abs() < 1e-12);
+            assert!((mrss.max - 40.0 - 1.5 * 0.2).abs() < 1e-9);
+            assert_eq!(mrss.sensitivity.len(), 4);
+        }
+
+        // A single contributor needs no correction
+        assert_eq!(MrssCorrection::ContributorCount.factor(&[0.1]), 1.0);
+    }
+
+    #[test]
+    fn test_six_sigma_analysis() {
+        use crate::analysis::six_sigma::{MeanShiftMode, SixSigmaSettings};
+        use crate::config::CapabilityTarget;
+
+        let (mut components, mut analysis) = stack_of(&[0.3, 0.3]);
+        analysis.methods = vec![AnalysisMethod::SixSigma];
+        analysis.upper_spec_limit = Some(20.6);
+        analysis.lower_spec_limit = Some(19.4);
+        for feature in &mut components[0].features {
+            feature.capability = Some(CapabilityTarget::CpCpk { cp: 1.0, cpk: 0.5 });
+        }
+
+        // Dynamic: each contributor has σ = 0.3 / 1.5 = 0.2 and the mean stays put
+        analysis.six_sigma_settings = Some(SixSigmaSettings {
+            mode: MeanShiftMode::Dynamic,
+            ..Default::default()
+        });
+        let dynamic = analysis.run_analysis(&components).unwrap().six_sigma.unwrap();
+        assert!((dynamic.long_term_std_dev - 0.2 * 2f64.sqrt()).abs() < 1e-12);
+        assert!((dynamic.short_term_std_dev - 0.1 * 2f64.sqrt()).abs() < 1e-12);
+        assert!((dynamic.max - 20.0 - 3.0 * dynamic.long_term_std_dev).abs() < 1e-9);
+
+        // Static: both means drift by 1.5σ_st = 0.15 in the same direction
+        analysis.six_sigma_settings = Some(SixSigmaSettings {
+            mode: MeanShiftMode::Static,
+            ..Default::default()
+        });
+        let stat = analysis.run_analysis(&components).unwrap().six_sigma.unwrap();
+        assert!((stat.mean_drift - 0.3).abs() < 1e-12);
+        let expected_cpk = (0.6 - 0.3) / (3.0 * stat.short_term_std_dev);
+        assert!((stat.cpk.unwrap() - expected_cpk).abs() < 1e-12);
+        assert!(stat.ppm_above.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_rss_asymmetric_tolerance() {
+        let (mut components, mut analysis) = stack_of(&[0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::WorstCase, AnalysisMethod::Rss];
+        // +0.2/-0.0 is 10.1 ± 0.1 once centered
+        components[0].features[0].dimension.plus_tolerance = 0.2;
+        components[0].features[0].dimension.minus_tolerance = 0.0;
+
+        let results = analysis.run_analysis(&components).unwrap();
+        let rss = results.rss.unwrap();
+        assert!((results.nominal - 20.0).abs() < 1e-12);
+        assert!((rss.mean - 20.1).abs() < 1e-12);
+        assert!((rss.std_dev - 0.02f64.sqrt() / 3.0).abs() < 1e-12);
+
+        // The RSS range sits inside the worst-case range, centered with it
+        let wc = results.worst_case.unwrap();
+        assert!(((wc.min + wc.max) / 2.0 - rss.mean).abs() < 1e-12);
+
+        let params = StackupAnalysis::calculate_distribution_params(&components[0].features[0]);
+        assert_eq!(params.mean, 10.1);
+        assert_eq!(components[0].features[0].effective_distribution().mean, 10.1);
+    }
+
+    #[test]
+    fn test_feature_distribution_params() {
+        let (mut components, mut analysis) = stack_of(&[0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::Rss, AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { seed: Some(3), ..Default::default() });
+
+        // A std dev entered on the feature replaces the tolerance-derived one
+        let mut params = DistributionParams::new_normal(10.0, 0.05);
+        components[0].features[0].distribution_params = Some(params.clone());
+        let results = analysis.run_analysis(&components).unwrap();
+        let expected = (0.05f64.powi(2) + (0.1f64 / 3.0).powi(2)).sqrt();
+        assert!((results.rss.unwrap().std_dev - expected).abs() < 1e-12);
+        assert!((results.monte_carlo.unwrap().std_dev - expected).abs() < 0.05 * expected);
+
+        // Calculated parameters follow the tolerances instead
+        params.calculated = true;
+        components[0].features[0].distribution_params = Some(params);
+        let results = analysis.run_analysis(&components).unwrap();
+        assert!((results.rss.unwrap().std_dev - 0.02f64.sqrt() / 3.0).abs() < 1e-12);
+
+        // Both the old feature shape and the old contribution shape still load
+        let old_feature: DistributionParams = ron::from_str(
+            "(dist_type: Normal, calculated: true, mean: Some(1.0), std_dev: Some(0.02), \
+              min: None, max: None, mode: None, shape: None, scale: None)"
+        ).unwrap();
+        assert!(old_feature.calculated && old_feature.std_dev == 0.02 && old_feature.min.is_nan());
+        assert!(old_feature.is_complete());
+        let old_contribution: DistributionParams = ron::from_str(
+            "(dist_type: Uniform, mean: 0.0, std_dev: 0.0, min: 1, max: 2.0, mode: None)"
+        ).unwrap();
+        assert!(old_contribution.calculated && old_contribution.min == 1.0);
+
+        // Old contributions carried a snapshot of the feature's distribution;
+        // it follows the feature's tolerances and its entered std dev
+        analysis.contributions[0] = ron::from_str(
+            "(component_id: \"Part\", feature_id: \"F0\", direction: 1.0, half_count: false, \
+              distribution: Some((dist_type: Normal, mean: 10.0, std_dev: 0.0333, min: 9.9, max: 10.1, mode: None)))"
+        ).unwrap();
+        components[0].features[0].dimension.plus_tolerance = 0.3;
+        components[0].features[0].dimension.minus_tolerance = 0.3;
+        let results = analysis.run_analysis(&components).unwrap();
+        assert!((results.rss.unwrap().std_dev - (0.01f64 + 0.01 / 9.0).sqrt()).abs() < 1e-12);
+        components[0].features[0].distribution_params = Some(DistributionParams::new_normal(10.0, 0.05));
+        let results = analysis.run_analysis(&components).unwrap();
+        assert!((results.rss.unwrap().std_dev - expected).abs() < 1e-12);
+
+        // An entered copy of the calculated parameters is settled as calculated
+        let feature = &mut components[0].features[1];
+        let mut snapshot = StackupAnalysis::calculate_distribution_params(feature);
+        snapshot.calculated = false;
+        feature.distribution_params = Some(snapshot);
+        feature.settle_distribution();
+        assert!(feature.distribution_params.as_ref().unwrap().calculated);
+        components[0].features[0].settle_distribution();
+        assert!(!components[0].features[0].distribution_params.as_ref().unwrap().calculated);
+    }
+
+    #[test]
+    fn test_sensitivity_coefficients() {
+        let (components, mut analysis) = stack_of(&[0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::WorstCase, AnalysisMethod::Rss];
+        analysis.contributions[1].coefficient = 2.0;
+        analysis.contributions[1].direction = -1.0;
+
+        let results = analysis.run_analysis(&components).unwrap();
+        let wc = results.worst_case.unwrap();
+        assert!((results.nominal + 10.0).abs() < 1e-12);
+        assert!((wc.min + 10.3).abs() < 1e-12);
+        assert!((wc.max + 9.7).abs() < 1e-12);
+        assert!((results.rss.unwrap().std_dev - 0.05f64.sqrt() / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_expression_stack() {
+        let (mut components, mut analysis) = stack_of(&[0.1, 0.2, 0.01]);
+        let theta = std::f64::consts::PI / 6.0;
+        components[0].features[1].dimension.value = 20.0;
+        components[0].features[2].dimension.value = theta;
+        for (contrib, name) in analysis.contributions.iter_mut().zip(["a", "b", "theta"]) {
+            contrib.variable = Some(name.to_string());
+        }
+        analysis.expression = Some("a + b*cos(theta)".to_string());
+        analysis.methods = vec![AnalysisMethod::WorstCase, AnalysisMethod::Rss, AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { seed: Some(7), ..Default::default() });
+
+        let results = analysis.run_analysis(&components).unwrap();
+        assert!((results.nominal - (10.0 + 20.0 * theta.cos())).abs() < 1e-12);
+
+        // b·cos(theta) is monotone over the zone, so interval evaluation is exact
+        let wc = results.worst_case.unwrap();
+        assert!((wc.min - (9.9 + 19.8 * (theta + 0.01).cos())).abs() < 1e-9);
+        assert!((wc.max - (10.1 + 20.2 * (theta - 0.01).cos())).abs() < 1e-9);
+
+        // RSS combines the numeric partials 1, cos(theta) and -b·sin(theta)
+        let expected = (0.1f64.powi(2) + (0.2 * theta.cos()).powi(2) + (20.0 * theta.sin() * 0.01).powi(2)).sqrt() / 3.0;
+        let rss = results.rss.unwrap();
+        assert!((rss.std_dev - expected).abs() < 1e-6);
+
+        let mc = results.monte_carlo.unwrap();
+        assert!((mc.mean - results.nominal).abs() < 0.01);
+        assert!((mc.std_dev / rss.std_dev - 1.0).abs() < 0.05);
+
+        analysis.expression = Some("a + c".to_string());
+        assert!(analysis.run_analysis(&components).is_err());
+
+        // sqrt(a - 10) has no slope at a = 10, so the linearized methods
+        // fail instead of reporting NaN
+        analysis.expression = Some("sqrt(a - 10) + b".to_string());
+        for method in [AnalysisMethod::Rss, AnalysisMethod::ModifiedRss, AnalysisMethod::SixSigma] {
+            analysis.methods = vec![method];
+            let error = analysis.run_analysis(&components).unwrap_err();
+            assert!(error.to_string().contains("not defined over the whole tolerance zone"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_correlated_monte_carlo() {
+        let (mut components, mut analysis) = stack_of(&[0.3, 0.3, 0.3]);
+        components[0].features[2].distribution = Some(DistributionType::Uniform);
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 20000, seed: Some(11), ..Default::default() });
+
+        let independent = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+        assert!(independent.independent_std_dev.is_none());
+
+        // F0 and F1 fully correlated: their spreads add instead of combining in quadrature
+        let mut group = CorrelationGroup::new("Fixture".to_string(), 1.0);
+        group.members = vec![
+            ("Part".to_string(), "F0".to_string()),
+            ("Part".to_string(), "F1".to_string()),
+        ];
+        analysis.correlation_groups = vec![group];
+
+        let correlated = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+        let uniform_std_dev = 0.3 / 3f64.sqrt();
+        let expected = (0.2f64.powi(2) + uniform_std_dev.powi(2)).sqrt();
+        assert!((correlated.std_dev / expected - 1.0).abs() < 0.03);
+        let expected_independent = (2.0 * 0.1f64.powi(2) + uniform_std_dev.powi(2)).sqrt();
+        assert!((correlated.independent_std_dev.unwrap() / expected_independent - 1.0).abs() < 0.03);
+        assert!((independent.std_dev / expected_independent - 1.0).abs() < 0.03);
+
+        // Correlations that cannot coexist are rejected
+        let mut group = CorrelationGroup::new("Impossible".to_string(), -0.9);
+        group.members = components[0].features.iter()
+            .map(|f| ("Part".to_string(), f.name.clone()))
+            .collect();
+        analysis.correlation_groups = vec![group];
+        assert!(analysis.run_analysis(&components).is_err());
+    }
+
+    #[test]
+    fn test_distribution_moments() {
+        let mut feature = Feature::new("F".to_string(), FeatureType::External, 10.0, 0.1, 0.1);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for dist_type in [
+            DistributionType::Normal,
+            DistributionType::Uniform,
+            DistributionType::Triangular,
+            DistributionType::LogNormal,
+            DistributionType::Weibull,
+            DistributionType::Beta,
+            DistributionType::Rayleigh,
+            DistributionType::FoldedNormal,
+            DistributionType::TruncatedNormal,
+            DistributionType::Mixture,
+        ] {
+            feature.distribution = Some(dist_type);
+            let params = StackupAnalysis::calculate_distribution_params(&feature);
+            let (mean, std_dev) = params.moments();
+
+            let samples: Vec<f64> = (0..50_000).map(|_| params.sample(&mut rng)).collect();
+            let sample_mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            let sample_std_dev = (samples.iter().map(|x| (x - sample_mean).powi(2)).sum::<f64>()
+                / (samples.len() - 1) as f64).sqrt();
+            assert!((sample_mean - mean).abs() < 0.05 * std_dev, "{:?} mean", dist_type);
+            assert!((sample_std_dev / std_dev - 1.0).abs() < 0.03, "{:?} std dev", dist_type);
+
+            // The defaults keep the bulk of the parts within the tolerance zone
+            assert!(params.cdf(9.9) < 0.01 && params.cdf(10.1) > 0.99, "{:?} coverage", dist_type);
+            for u in [0.01, 0.3, 0.5, 0.9] {
+                assert!((params.cdf(params.inverse_cdf(u)) - u).abs() < 1e-6, "{:?} inverse cdf", dist_type);
+            }
+        }
+    }
+
+    #[test]
+    fn test_monte_carlo_convergence() {
+        let (components, mut analysis) = stack_of(&[0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        analysis.upper_spec_limit = Some(20.1);
+        analysis.monte_carlo_settings = Some(MonteCarloSettings {
+            iterations: 5000,
+            confidence: 0.99,
+            seed: Some(5),
+            convergence: Some(ConvergenceSettings { batch_size: 5000, max_iterations: 200_000, tolerance: 0.05 }),
+            ..Default::default()
+        });
+
+        let mc = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+        assert_eq!(mc.converged, Some(true));
+        assert!(mc.iterations > 5000 && mc.iterations < 200_000 && mc.iterations % 5000 == 0);
+        assert!((mc.mean_std_error - mc.std_dev / (mc.iterations as f64).sqrt()).abs() < 1e-12);
+        assert!(mc.std_dev_std_error > 0.0 && mc.std_dev_std_error <= 0.05 * mc.std_dev);
+
+        // The bands straddle the exact normal answers
+        let sigma = 0.02f64.sqrt() / 3.0;
+        let exact = StatsNormal::new(20.0, sigma).unwrap();
+        let ppm = mc.ppm_above.unwrap();
+        let exact_ppm = (1.0 - exact.cdf(20.1)) * 1e6;
+        assert!(ppm.lower < exact_ppm && exact_ppm < ppm.upper);
+        assert!(mc.ppm_below.is_none());
+        let interval = mc.confidence_intervals.iter().find(|i| i.confidence_level == 0.99).unwrap();
+        let (lo, hi) = interval.upper_band.unwrap();
+        assert!(lo < exact.inverse_cdf(0.995) && exact.inverse_cdf(0.995) < hi);
+
+        // With no failures the band still bounds the ppm from above
+        let none = PpmEstimate::new(0, 10_000);
+        assert_eq!(none.ppm, 0.0);
+        assert!(none.upper > 300.0 && none.upper < 400.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_reproducible() {
+        let (components, mut analysis) = stack_of(&[0.1, 0.2, 0.3]);
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 20_000, seed: Some(9), ..Default::default() });
+
+        // The same seed gives the same samples whatever the thread count
+        let run = |threads: usize| rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+            .install(|| analysis.run_analysis(&components).unwrap().monte_carlo.unwrap());
+        let (single, parallel) = (run(1), run(4));
+        assert_eq!(single.iterations, 20_000);
+        assert_eq!(single.mean.to_bits(), parallel.mean.to_bits());
+        assert_eq!(single.std_dev.to_bits(), parallel.std_dev.to_bits());
+        assert_eq!(single.histogram, parallel.histogram);
+    }
+
+    #[test]
+    fn test_background_analysis() {
+        use crate::analysis::job::{AnalysisProgress, AnalysisQueue};
+
+        let (components, mut analysis) = stack_of(&[0.1, 0.2]);
+        analysis.methods = vec![AnalysisMethod::Rss, AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 300_000, seed: Some(1), ..Default::default() });
+
+        // Cancelled before sampling: the analytic results are kept
+        let progress = AnalysisProgress::default();
+        progress.cancel();
+        let partial = analysis.run_analysis_with_progress(&components, &progress).unwrap();
+        assert!(partial.cancelled && partial.rss.is_some() && partial.monte_carlo.is_none());
+
+        // Queued jobs run one after the other and report their progress
+        let mut queue = AnalysisQueue::default();
+        let mut second = analysis.clone();
+        second.id = "second".to_string();
+        queue.enqueue(analysis.clone(), components.clone());
+        queue.enqueue(second.clone(), components.clone());
+        queue.enqueue(second, components.clone());
+        assert_eq!(queue.pending_count(), 1);
+
+        let mut finished = Vec::new();
+        while queue.is_busy() {
+            match queue.poll() {
+                Some((done, results)) => finished.push((done.id, results.unwrap())),
+                None => std::thread::sleep(std::time::Duration::from_millis(5)),
+            }
+        }
+        assert_eq!(finished.len(), 2);
+        assert_eq!(finished[1].0, "second");
+        let mc = finished[0].1.monte_carlo.as_ref().unwrap();
+        assert!(!finished[0].1.cancelled && mc.iterations == 300_000);
+    }
+
+    #[test]
+    fn test_sampling_strategies() {
+        let (components, mut analysis) = stack_of(&[0.1, 0.2, 0.3, 0.1, 0.2]);
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        let sigma = (0.01f64 + 0.04 + 0.09 + 0.01 + 0.04).sqrt() / 3.0;
+        let exact = StatsNormal::new(50.0, sigma).unwrap();
+
+        for sampling in [SamplingStrategy::LatinHypercube, SamplingStrategy::Sobol, SamplingStrategy::Halton] {
+            analysis.monte_carlo_settings = Some(MonteCarloSettings {
+                iterations: 4096,
+                confidence: 0.99,
+                seed: Some(2),
+                sampling,
+                ..Default::default()
+            });
+            let mc = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+            let interval = mc.confidence_intervals.iter().find(|i| i.confidence_level == 0.99).unwrap();
+            assert_eq!(mc.sampling, sampling);
+
+            // Plain random sampling has a standard error of σ/64 on the mean here
+            assert!((mc.mean - 50.0).abs() < 0.005 * sigma);
+            assert!((mc.std_dev / sigma - 1.0).abs() < 0.012);
+            assert!((interval.lower_bound - exact.inverse_cdf(0.005)).abs() < 0.1 * sigma);
+            assert!((interval.upper_bound - exact.inverse_cdf(0.995)).abs() < 0.1 * sigma);
+        }
+    }
+
+    #[test]
+    fn test_tail_estimation() {
+        // Five normal contributors with the USL 5.5σ out: far rarer than the
+        // plain sample can see
+        let (components, mut analysis) = stack_of(&[0.1, 0.2, 0.3, 0.1, 0.2]);
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        let sigma = (0.01f64 + 0.04 + 0.09 + 0.01 + 0.04).sqrt() / 3.0;
+        analysis.upper_spec_limit = Some(50.0 + 5.5 * sigma);
+        analysis.lower_spec_limit = Some(50.0 - 2.0 * sigma);
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 10_000, seed: Some(4), ..Default::default() });
+
+        let results = analysis.run_analysis(&components).unwrap();
+        let mc = results.monte_carlo.as_ref().unwrap();
+        let exact_ppm = (1.0 - StatsNormal::new(0.0, 1.0).unwrap().cdf(5.5)) * 1e6;
+        let tail = mc.tail_above.unwrap();
+        assert_eq!(mc.ppm_above.unwrap().count, 0);
+        assert!(matches!(tail.method, PpmMethod::ImportanceSampled { samples: 10_000, shift } if (shift - 5.5).abs() < 1e-3));
+        assert!(tail.lower < exact_ppm && exact_ppm < tail.upper);
+        assert!(tail.upper < 1.2 * exact_ppm);
+        // Plenty of failures below the LSL: the plain sample is used
+        assert!(mc.tail_below.is_none());
+        let capability = results.process_capability.unwrap();
+        assert_eq!(capability.ppm_above, Some(tail.ppm));
+        assert_eq!(capability.ppm_below, Some(mc.ppm_below.unwrap().ppm));
+
+        // Two uniform contributors add up to a triangular stack that ends at
+        // ±0.2, well short of where a normal fit puts its tail
+        let (components, mut analysis) = stack_of(&[0.1, 0.1]);
+        for contribution in &mut analysis.contributions {
+            contribution.distribution = Some(DistributionParams::new_uniform(9.9, 10.1));
+        }
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        analysis.upper_spec_limit = Some(20.198);
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 10_000, seed: Some(4), ..Default::default() });
+
+        let mc = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+        let tail = mc.tail_above.unwrap();
+        let exact_ppm = 0.002f64.powi(2) / (2.0 * 0.04) * 1e6;
+        assert!(tail.lower < exact_ppm && exact_ppm < tail.upper);
+        assert!(tail.upper < 1.5 * exact_ppm);
+    }
+
+    #[test]
+    fn test_sobol_indices() {
+        // Linear: both indices are the variance shares, 1/6, 4/6 and 1/6 here
+        let (components, mut analysis) = stack_of(&[0.1, 0.2, 0.1]);
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 40_000, seed: Some(3), ..Default::default() });
+        let mc = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+        for sens in &mc.sensitivity {
+            let share = if sens.feature_id == "F1" { 4.0 / 6.0 } else { 1.0 / 6.0 };
+            assert!((sens.sobol_first_order.unwrap() - share).abs() < 0.03);
+            assert!((sens.sobol_total.unwrap() - share).abs() < 0.03);
+        }
+
+        // a·b about zero is all interaction: neither contributor matters alone
+        let (mut components, mut analysis) = stack_of(&[0.1, 0.1]);
+        for feature in &mut components[0].features {
+            feature.dimension.value = 0.0;
+        }
+        for (contrib, name) in analysis.contributions.iter_mut().zip(["a", "b"]) {
+            contrib.variable = Some(name.to_string());
+        }
+        analysis.expression = Some("a*b".to_string());
+        analysis.methods = vec![AnalysisMethod::MonteCarlo];
+        analysis.monte_carlo_settings = Some(MonteCarloSettings { iterations: 40_000, seed: Some(3), ..Default::default() });
+        let mc = analysis.run_analysis(&components).unwrap().monte_carlo.unwrap();
+        for sens in &mc.sensitivity {
+            assert!(sens.sobol_first_order.unwrap().abs() < 0.05);
+            assert!((sens.sobol_total.unwrap() - 1.0).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_worst_case_analysis() {
+        // Create test components and run worst case analysis
+        // TODO: Implement test cases
+    }
+
+    #[test]
+    fn test_rss_analysis() {
+        // Create test components and run RSS analysis
+        // TODO: Implement test cases
+    }
+
+    #[test]
+    fn test_monte_carlo_analysis() {
+        // Create test components and run Monte Carlo analysis
+        // TODO: Implement test cases
+    }
+}
\ No newline at end of file
@@ -0,0 +1,431 @@
+// src/analysis/study.rs
+
+use std::io;
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Result};
+use csv::Writer;
+use statrs::distribution::{ContinuousCDF, Normal};
+use crate::config::{Component, Feature, Units};
+use super::stackup::{cpk, AnalysisMethod, AnalysisResults, DistributionParams, DistributionType, StackupAnalysis};
+use super::job::AnalysisProgress;
+
+/// Most grid points a study runs; each one is a full analysis
+pub const MAX_STUDY_POINTS: usize = 2_500;
+
+/// Which property of a feature a study varies
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StudyParameter {
+    /// The nominal value. A custom distribution moves with it.
+    Nominal,
+    PlusTolerance,
+    MinusTolerance,
+    /// Standard deviation of a normal, lognormal or truncated normal feature;
+    /// the distribution stops following the tolerances
+    StdDev,
+}
+
+impl StudyParameter {
+    pub const ALL: [StudyParameter; 4] = [
+        StudyParameter::Nominal,
+        StudyParameter::PlusTolerance,
+        StudyParameter::MinusTolerance,
+        StudyParameter::StdDev,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StudyParameter::Nominal => "Nominal",
+            StudyParameter::PlusTolerance => "Plus tolerance",
+            StudyParameter::MinusTolerance => "Minus tolerance",
+            StudyParameter::StdDev => "Std dev",
+        }
+    }
+
+    /// The parameter's current value on `feature`
+    pub fn value(&self, feature: &Feature) -> f64 {
+        match self {
+            StudyParameter::Nominal => feature.dimension.value,
+            StudyParameter::PlusTolerance => feature.dimension.plus_tolerance,
+            StudyParameter::MinusTolerance => feature.dimension.minus_tolerance,
+            StudyParameter::StdDev => feature.effective_distribution().std_dev,
+        }
+    }
+}
+
+/// One feature parameter stepped evenly from `start` to `end`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudyAxis {
+    pub component_id: String,
+    pub feature_id: String,
+    pub parameter: StudyParameter,
+    pub start: f64,
+    pub end: f64,
+    pub steps: usize,
+}
+
+impl StudyAxis {
+    pub fn label(&self) -> String {
+        format!("{}.{} {}", self.component_id, self.feature_id, self.parameter.label())
+    }
+
+    /// The values the axis takes, `start` and `end` included
+    pub fn values(&self) -> Vec<f64> {
+        match self.steps {
+            0 => Vec::new(),
+            1 => vec![self.start],
+            steps => (0..steps)
+                .map(|i| self.start + (self.end - self.start) * i as f64 / (steps - 1) as f64)
+                .collect(),
+        }
+    }
+
+    /// Set the parameter to `value` in a copy of the components
+    fn apply(&self, components: &mut [Component], value: f64, units: Units) -> Result<()> {
+        set_parameter(components, &self.component_id, &self.feature_id, self.parameter, value, units)
+    }
+}
+
+/// Set `parameter` of a feature to `value`. A nominal carries the
+/// tolerances of the feature's tolerance class, in `units`, along with it.
+pub fn set_parameter(
+    components: &mut [Component],
+    component_id: &str,
+    feature_id: &str,
+    parameter: StudyParameter,
+    value: f64,
+    units: Units,
+) -> Result<()> {
+    let Some(feature) = components.iter_mut()
+        .filter(|c| c.name == component_id)
+        .flat_map(|c| c.features.iter_mut())
+        .find(|f| f.name == feature_id)
+    else {
+        bail!("Feature {}.{} not found", component_id, feature_id);
+    };
+
+    let custom = feature.distribution_params.as_ref().is_some_and(|p| !p.calculated);
+    match parameter {
+        StudyParameter::Nominal => {
+            let delta = value - feature.dimension.value;
+            feature.dimension.value = value;
+            if custom {
+                if let Some(params) = &mut feature.distribution_params {
+                    shift(params, delta);
+                }
+            }
+            feature.apply_tolerance_class(units)?;
+        },
+        StudyParameter::PlusTolerance => {
+            feature.dimension.plus_tolerance = value;
+            feature.tolerance_class = None;
+        },
+        StudyParameter::MinusTolerance => {
+            feature.dimension.minus_tolerance = value;
+            feature.tolerance_class = None;
+        },
+        StudyParameter::StdDev => {
+            let mut params = feature.effective_distribution();
+            if !matches!(params.dist_type,
+                DistributionType::Normal | DistributionType::LogNormal | DistributionType::TruncatedNormal)
+            {
+                bail!("{}.{} has a {:?} distribution, which has no std dev to vary",
+                    component_id, feature_id, params.dist_type);
+            }
+            if value <= 0.0 {
+                bail!("{}.{}: the std dev must be positive", component_id, feature_id);
+            }
+            params.std_dev = value;
+            params.calculated = false;
+            feature.distribution_params = Some(params);
+        },
+    }
+    // Calculated parameters are refitted to the new tolerances on use
+    Ok(())
+}
+
+/// Move a distribution along by `delta` without changing its shape
+fn shift(params: &mut DistributionParams, delta: f64) {
+    params.mean += delta;
+    params.min += delta;
+    params.max += delta;
+    params.mode = params.mode.map(|mode| mode + delta);
+    for component in &mut params.components {
+        shift(&mut component.distribution, delta);
+    }
+}
+
+/// A what-if study over one or two feature parameters
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StudySettings {
+    /// One axis gives a line, two a grid
+    pub axes: Vec<StudyAxis>,
+}
+
+/// What a study plots
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StudyMetric {
+    Min(AnalysisMethod),
+    Max(AnalysisMethod),
+    Cpk,
+    Ppm,
+}
+
+impl StudyMetric {
+    pub fn label(&self) -> String {
+        let method = |method: &AnalysisMethod| match method {
+            AnalysisMethod::WorstCase => "Worst case",
+            AnalysisMethod::Rss => "RSS",
+            AnalysisMethod::ModifiedRss => "Modified RSS",
+            AnalysisMethod::SixSigma => "Six Sigma",
+            AnalysisMethod::MonteCarlo => "Monte Carlo",
+        };
+        match self {
+            StudyMetric::Min(m) => format!("{} min", method(m)),
+            StudyMetric::Max(m) => format!("{} max", method(m)),
+            StudyMetric::Cpk => "Cpk".to_string(),
+            StudyMetric::Ppm => "PPM".to_string(),
+        }
+    }
+}
+
+/// The stack at one grid point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudyPoint {
+    /// Parameter value on each axis
+    pub values: Vec<f64>,
+    pub worst_case: Option<(f64, f64)>,
+    pub rss: Option<(f64, f64)>,
+    /// Interval at the configured confidence level
+    pub monte_carlo: Option<(f64, f64)>,
+    pub cpk: Option<f64>,
+    /// Total out of spec, both limits
+    pub ppm: Option<f64>,
+}
+
+impl StudyPoint {
+    pub fn metric(&self, metric: StudyMetric) -> Option<f64> {
+        let range = |method: AnalysisMethod| match method {
+            AnalysisMethod::WorstCase => self.worst_case,
+            AnalysisMethod::Rss => self.rss,
+            AnalysisMethod::MonteCarlo => self.monte_carlo,
+            AnalysisMethod::ModifiedRss | AnalysisMethod::SixSigma => None,
+        };
+        match metric {
+            StudyMetric::Min(method) => range(method).map(|(min, _)| min),
+            StudyMetric::Max(method) => range(method).map(|(_, max)| max),
+            StudyMetric::Cpk => self.cpk,
+            StudyMetric::Ppm => self.ppm,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudyResult {
+    pub axes: Vec<StudyAxis>,
+    /// Row-major: the last axis varies fastest
+    pub points: Vec<StudyPoint>,
+    /// Stopped before every point was run
+    pub cancelled: bool,
+}
+
+impl StudyResult {
+    /// The metrics the points have values for
+    pub fn metrics(&self) -> Vec<StudyMetric> {
+        let methods = [AnalysisMethod::WorstCase, AnalysisMethod::Rss, AnalysisMethod::MonteCarlo];
+        methods.iter()
+            .flat_map(|m| [StudyMetric::Min(*m), StudyMetric::Max(*m)])
+            .chain([StudyMetric::Cpk, StudyMetric::Ppm])
+            .filter(|metric| self.points.iter().any(|p| p.metric(*metric).is_some()))
+            .collect()
+    }
+
+    /// One row per point: the axis values, then every metric
+    pub fn write_csv<W: io::Write>(&self, writer: W) -> Result<()> {
+        let mut writer = Writer::from_writer(writer);
+        let metrics = self.metrics();
+
+        let headers: Vec<String> = self.axes.iter()
+            .map(|axis| axis.label())
+            .chain(metrics.iter().map(|metric| metric.label()))
+            .collect();
+        writer.write_record(&headers)?;
+
+        for point in &self.points {
+            let record: Vec<String> = point.values.iter()
+                .map(|value| value.to_string())
+                .chain(metrics.iter().map(|metric| point.metric(*metric)
+                    .map(|value| value.to_string())
+                    .unwrap_or_default()))
+                .collect();
+            writer.write_record(&record)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl StackupAnalysis {
+    /// Run the analysis's worst case, RSS and Monte Carlo methods at every
+    /// point of the study grid, with lengths in `units`. Reports grid points
+    /// to `progress` and stops between points once it is cancelled.
+    pub fn run_study(&self, components: &[Component], study: &StudySettings, units: Units, progress: &AnalysisProgress) -> Result<StudyResult> {
+        if study.axes.is_empty() || study.axes.len() > 2 {
+            bail!("A study varies one or two parameters");
+        }
+        let grid: Vec<Vec<f64>> = study.axes.iter().map(|axis| axis.values()).collect();
+        let count: usize = grid.iter().map(|values| values.len()).product();
+        if count == 0 {
+            bail!("Every study parameter needs at least one step");
+        }
+        if count > MAX_STUDY_POINTS {
+            bail!("The study has {} points; at most {} can be run", count, MAX_STUDY_POINTS);
+        }
+
+        let mut analysis = self.clone();
+        analysis.methods.retain(|m| matches!(m,
+            AnalysisMethod::WorstCase | AnalysisMethod::Rss | AnalysisMethod::MonteCarlo));
+        if analysis.methods.is_empty() {
+            bail!("Analysis {} needs worst case, RSS or Monte Carlo for a study", self.name);
+        }
+
+        progress.start(count);
+        let mut points = Vec::with_capacity(count);
+        for index in 0..count {
+            if progress.is_cancelled() {
+                break;
+            }
+
+            // Row-major: the last axis varies fastest
+            let mut remainder = index;
+            let mut values = vec![0.0; grid.len()];
+            for (axis, axis_values) in grid.iter().enumerate().rev() {
+                values[axis] = axis_values[remainder % axis_values.len()];
+                remainder /= axis_values.len();
+            }
+
+            let mut varied = components.to_vec();
+            for (axis, value) in study.axes.iter().zip(&values) {
+                axis.apply(&mut varied, *value, units)?;
+            }
+            let results = analysis.run_analysis(&varied)?;
+            points.push(analysis.study_point(values, &results));
+            progress.advance(1);
+        }
+
+        Ok(StudyResult {
+            axes: study.axes.clone(),
+            cancelled: points.len() < count,
+            points,
+        })
+    }
+
+    /// Summary of one grid point's results. Cpk and ppm come from Monte Carlo
+    /// when it ran and from the RSS normal approximation otherwise.
+    fn study_point(&self, values: Vec<f64>, results: &AnalysisResults) -> StudyPoint {
+        let monte_carlo = results.monte_carlo.as_ref().map(|mc| self.monte_carlo_range(mc));
+
+        let moments = results.monte_carlo.as_ref().map(|mc| (mc.mean, mc.std_dev))
+            .or(results.rss.as_ref().map(|rss| (rss.mean, rss.std_dev)));
+        let limits = [self.upper_spec_limit, self.lower_spec_limit];
+
+        let cpk = moments.and_then(|(mean, std_dev)| cpk(mean, std_dev, self.upper_spec_limit, self.lower_spec_limit));
+
+        let ppm = if limits.iter().all(Option::is_none) {
+            None
+        } else if let Some(mc) = &results.monte_carlo {
+            let below = mc.tail_below.or(mc.ppm_below).map(|e| e.ppm);
+            let above = mc.tail_above.or(mc.ppm_above).map(|e| e.ppm);
+            Some(below.unwrap_or(0.0) + above.unwrap_or(0.0))
+        } else {
+            results.rss.as_ref()
+                .and_then(|rss| Normal::new(rss.mean, rss.std_dev).ok())
+                .map(|normal| {
+                    let below = self.lower_spec_limit.map_or(0.0, |lsl| normal.cdf(lsl));
+                    let above = self.upper_spec_limit.map_or(0.0, |usl| normal.sf(usl));
+                    (below + above) * 1e6
+                })
+        };
+
+        StudyPoint {
+            values,
+            worst_case: results.worst_case.as_ref().map(|wc| (wc.min, wc.max)),
+            rss: results.rss.as_ref().map(|rss| (rss.min, rss.max)),
+            monte_carlo,
+            cpk,
+            ppm,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_support::stack_of;
+    use crate::config::ToleranceClass;
+
+    #[test]
+    fn test_parameter_study() {
+        let (components, mut analysis) = stack_of(&[0.1, 0.1]);
+        analysis.methods = vec![AnalysisMethod::WorstCase, AnalysisMethod::Rss];
+        analysis.upper_spec_limit = Some(20.3);
+        analysis.lower_spec_limit = Some(19.7);
+
+        let axis = |feature: &str, parameter, start, end, steps| StudyAxis {
+            component_id: "Part".to_string(),
+            feature_id: feature.to_string(),
+            parameter,
+            start,
+            end,
+            steps,
+        };
+
+        // Moving one nominal moves the worst-case range with it
+        let study = StudySettings { axes: vec![axis("F0", StudyParameter::Nominal, 9.9, 10.1, 3)] };
+        let result = analysis.run_study(&components, &study, Units::Metric, &AnalysisProgress::default()).unwrap();
+        assert_eq!(result.points.len(), 3);
+        for (point, shift) in result.points.iter().zip([-0.1, 0.0, 0.1]) {
+            let (min, max) = point.worst_case.unwrap();
+            assert!((min - (19.8 + shift)).abs() < 1e-9);
+            assert!((max - (20.2 + shift)).abs() < 1e-9);
+        }
+        // Centered, the RSS stack has σ = 0.1·√2/3 against a ±0.3 spec
+        let cpk = result.points[1].cpk.unwrap();
+        assert!((cpk - 0.3 / (0.1 * 2f64.sqrt())).abs() < 1e-9);
+        assert!(result.points[0].ppm.unwrap() > result.points[1].ppm.unwrap());
+
+        // Two axes run every combination, the last axis fastest
+        let study = StudySettings { axes: vec![
+            axis("F0", StudyParameter::PlusTolerance, 0.1, 0.2, 2),
+            axis("F1", StudyParameter::StdDev, 0.02, 0.04, 3),
+        ] };
+        let result = analysis.run_study(&components, &study, Units::Metric, &AnalysisProgress::default()).unwrap();
+        let values: Vec<_> = result.points.iter().map(|p| p.values.clone()).collect();
+        assert_eq!(values, [[0.1, 0.02], [0.1, 0.03], [0.1, 0.04], [0.2, 0.02], [0.2, 0.03], [0.2, 0.04]]);
+        assert!((result.points[3].worst_case.unwrap().1 - 20.3).abs() < 1e-9);
+
+        let mut csv = Vec::new();
+        result.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 7);
+        assert!(csv.starts_with("Part.F0 Plus tolerance,Part.F1 Std dev,Worst case min"));
+
+        // A uniform feature has no std dev to vary
+        let mut components = components;
+        components[0].features[1].update_distribution(DistributionType::Uniform);
+        assert!(analysis.run_study(&components, &study, Units::Metric, &AnalysisProgress::default()).is_err());
+
+        // A nominal toleranced by class takes the class's limits at each
+        // size: H7 is +15 µm over 6–10 mm and +18 µm over 10–18 mm
+        let (mut components, analysis) = stack_of(&[0.1, 0.1]);
+        let class: ToleranceClass = "H7".parse().unwrap();
+        components[0].features[0].tolerance_class = Some(class);
+        components[0].features[0].apply_tolerance_class(Units::Metric).unwrap();
+        let study = StudySettings { axes: vec![axis("F0", StudyParameter::Nominal, 10.0, 12.0, 2)] };
+        let result = analysis.run_study(&components, &study, Units::Metric, &AnalysisProgress::default()).unwrap();
+        let (min, max) = result.points[0].worst_case.unwrap();
+        assert!((min - 19.9).abs() < 1e-9 && (max - 20.115).abs() < 1e-9);
+        let (min, max) = result.points[1].worst_case.unwrap();
+        assert!((min - 21.9).abs() < 1e-9 && (max - 22.118).abs() < 1e-9);
+    }
+}
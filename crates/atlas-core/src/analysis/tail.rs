@@ -0,0 +1,61 @@
+// src/analysis/tail.rs
+
+/// Failures the plain Monte Carlo sample must contain before its own ppm
+/// estimate is used; with fewer the tail beyond the limit is importance sampled
+pub(crate) const MIN_SAMPLED_FAILURES: usize = 10;
+
+/// Largest distance from the mean, in standard normal scores, searched for
+/// the failure point; the inverse CDFs saturate not far beyond
+const MAX_SCORE: f64 = 8.0;
+
+/// Importance samples drawn beyond each spec limit
+pub(crate) fn tail_sample_count(iterations: usize) -> usize {
+    iterations.clamp(10_000, 100_000)
+}
+
+/// Most probable failure point: the independent standard normal scores
+/// closest to the origin at which the stack reaches `limit`, found with the
+/// Hasofer-Lind-Rackwitz-Fiessler iteration on numerical gradients.
+/// `value_at` maps scores to the stack value. None when the stack does not
+/// move with its contributors.
+pub(crate) fn design_point(value_at: impl Fn(&[f64]) -> f64, dimensions: usize, limit: f64, above: bool) -> Option<Vec<f64>> {
+    // Safety margin: negative in the failure region
+    let margin = |z: &[f64]| if above { limit - value_at(z) } else { value_at(z) - limit };
+    let h = 1e-3;
+
+    let mut z = vec![0.0; dimensions];
+    for _ in 0..50 {
+        let g = margin(&z);
+        let mut point = z.clone();
+        let gradient: Vec<f64> = (0..dimensions)
+            .map(|i| {
+                point[i] = z[i] + h;
+                let forward = margin(&point);
+                point[i] = z[i] - h;
+                let backward = margin(&point);
+                point[i] = z[i];
+                (forward - backward) / (2.0 * h)
+            })
+            .collect();
+
+        let norm_squared: f64 = gradient.iter().map(|d| d * d).sum();
+        if norm_squared <= 0.0 || !norm_squared.is_finite() || !g.is_finite() {
+            // Flat where the search has got to; keep the last point if any
+            return z.iter().any(|x| *x != 0.0).then_some(z);
+        }
+
+        let along: f64 = gradient.iter().zip(&z).map(|(d, x)| d * x).sum::<f64>() - g;
+        let mut next: Vec<f64> = gradient.iter().map(|d| d * along / norm_squared).collect();
+        let length = next.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if length > MAX_SCORE {
+            next.iter_mut().for_each(|x| *x *= MAX_SCORE / length);
+        }
+
+        let step = next.iter().zip(&z).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt();
+        z = next;
+        if step < 1e-6 {
+            break;
+        }
+    }
+    Some(z)
+}
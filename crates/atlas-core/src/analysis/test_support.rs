@@ -0,0 +1,22 @@
+// src/analysis/test_support.rs
+use crate::config::{Component, Feature, FeatureType};
+use super::stackup::StackupAnalysis;
+
+/// A component "Part" with features F0, F1, … at 10 ± each of `tolerances`,
+/// and an analysis "Stack" adding them all
+pub(crate) fn stack_of(tolerances: &[f64]) -> (Vec<Component>, StackupAnalysis) {
+    let component = Component {
+        name: "Part".to_string(),
+        description: None,
+        features: tolerances.iter().enumerate()
+            .map(|(i, tol)| Feature::new(format!("F{}", i), FeatureType::External, 10.0, *tol, *tol))
+            .collect(),
+    };
+
+    let mut analysis = StackupAnalysis::new("Stack".to_string());
+    for feature in &component.features {
+        analysis.add_contribution(component.name.clone(), feature.name.clone(), 1.0, false, None);
+    }
+
+    (vec![component], analysis)
+}
@@ -0,0 +1,157 @@
+// src/config/feature.rs
+use serde::{Serialize, Deserialize};
+use crate::analysis::stackup::{DistributionType, StackupAnalysis};
+use anyhow::Result;
+use super::process::FeatureProcess;
+use super::fits::ToleranceClass;
+use super::Units;
+
+// Features and stackup contributions share one distribution model
+pub use crate::analysis::stackup::DistributionParams;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Copy, Default)]
+pub enum FeatureType {
+    #[default]
+    External,
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feature {
+    pub name: String,
+    pub feature_type: FeatureType,
+    pub dimension: Dimension,
+    pub distribution: Option<DistributionType>,
+    pub distribution_params: Option<DistributionParams>,
+    #[serde(default)]
+    pub capability: Option<CapabilityTarget>,
+    /// Manufacturing process and cost-tolerance curve
+    #[serde(default)]
+    pub process: Option<FeatureProcess>,
+    /// Inspection measurements of the feature, e.g. imported from a CMM export
+    #[serde(default)]
+    pub measurements: Vec<f64>,
+    /// ISO 286 tolerance class, e.g. H7; the tolerances follow it when the
+    /// nominal changes
+    #[serde(default)]
+    pub tolerance_class: Option<ToleranceClass>,
+}
+
+/// Process capability quoted for a feature, used by the Six Sigma stack
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum CapabilityTarget {
+    /// Short-term Cp and long-term Cpk; the difference is the mean shift
+    CpCpk { cp: f64, cpk: f64 },
+    /// Cp with the process mean drifting by k·σ (1.5 in the Motorola convention)
+    MeanShift { cp: f64, k: f64 },
+}
+
+impl CapabilityTarget {
+    pub fn cp(&self) -> f64 {
+        match self {
+            CapabilityTarget::CpCpk { cp, .. } | CapabilityTarget::MeanShift { cp, .. } => *cp,
+        }
+    }
+
+    pub fn cpk(&self) -> f64 {
+        match self {
+            CapabilityTarget::CpCpk { cpk, .. } => *cpk,
+            CapabilityTarget::MeanShift { cp, k } => cp - k / 3.0,
+        }
+    }
+
+    /// Mean shift in multiples of the short-term standard deviation
+    pub fn shift_sigmas(&self) -> f64 {
+        match self {
+            CapabilityTarget::CpCpk { cp, cpk } => 3.0 * (cp - cpk),
+            CapabilityTarget::MeanShift { k, .. } => *k,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dimension {
+    pub value: f64,
+    pub plus_tolerance: f64,
+    pub minus_tolerance: f64,
+}
+
+impl Dimension {
+    /// Middle of the tolerance zone; equals `value` for equal-bilateral tolerances
+    pub fn mean(&self) -> f64 {
+        self.value + (self.plus_tolerance - self.minus_tolerance) / 2.0
+    }
+
+    /// Half-width of the tolerance zone, i.e. the equal-bilateral tolerance around `mean()`
+    pub fn bilateral_tolerance(&self) -> f64 {
+        (self.plus_tolerance + self.minus_tolerance) / 2.0
+    }
+}
+
+impl Feature {
+    pub fn new(name: String, feature_type: FeatureType, value: f64, plus_tol: f64, minus_tol: f64) -> Self {
+        let mut new_feature = Self {
+            name,
+            feature_type,
+            dimension: Dimension {
+                value,
+                plus_tolerance: plus_tol,
+                minus_tolerance: minus_tol,
+            },
+            distribution: Some(DistributionType::Normal),
+            distribution_params: None,
+            capability: None,
+            process: None,
+            measurements: Vec::new(),
+            tolerance_class: None,
+        };
+
+        // Calculate initial distribution parameters
+        new_feature.distribution_params = Some(StackupAnalysis::calculate_distribution_params(&new_feature));
+        new_feature
+    }
+
+    pub fn update_distribution(&mut self, dist_type: DistributionType) {
+        self.distribution = Some(dist_type);
+        let custom = self.distribution_params.as_ref()
+            .is_some_and(|p| !p.calculated && p.dist_type == dist_type);
+        if !custom {
+            self.distribution_params = Some(StackupAnalysis::calculate_distribution_params(self));
+        }
+    }
+
+    /// Set the tolerances from the tolerance class at the current nominal,
+    /// if the feature has one, refitting a calculated distribution
+    pub fn apply_tolerance_class(&mut self, units: Units) -> Result<()> {
+        if let Some(class) = self.tolerance_class {
+            let (plus, minus) = class.tolerances(self.dimension.value, units)?;
+            self.dimension.plus_tolerance = plus;
+            self.dimension.minus_tolerance = minus;
+            if self.distribution_params.as_ref().is_none_or(|p| p.calculated) {
+                self.distribution_params = Some(StackupAnalysis::calculate_distribution_params(self));
+            }
+        }
+        Ok(())
+    }
+
+    /// Treat entered parameters that are just the ones calculated from the
+    /// tolerances as calculated, so they follow later tolerance edits. Older
+    /// projects saved such snapshots as if they had been entered.
+    pub fn settle_distribution(&mut self) {
+        let mut derived = StackupAnalysis::calculate_distribution_params(self);
+        derived.calculated = false;
+        if let Some(params) = &mut self.distribution_params {
+            if *params == derived {
+                params.calculated = true;
+            }
+        }
+    }
+
+    /// The distribution the analyses use: the parameters entered for the
+    /// feature, or defaults fitted to its current tolerances
+    pub fn effective_distribution(&self) -> DistributionParams {
+        self.distribution_params.clone()
+            .filter(|p| !p.calculated && p.is_complete())
+            .unwrap_or_else(|| StackupAnalysis::calculate_distribution_params(self))
+    }
+}
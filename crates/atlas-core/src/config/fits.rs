@@ -0,0 +1,435 @@
+// src/config/fits.rs
+use std::fmt;
+use std::str::FromStr;
+use serde::{Serialize, Deserialize};
+use anyhow::{anyhow, bail, Result};
+use super::{Component, FeatureType, Units};
+use super::mate::{FitType, Mate};
+
+/// Upper limits of the ISO 286 nominal size ranges for IT grades, in mm
+const GRADE_RANGES: [f64; 13] = [3.0, 6.0, 10.0, 18.0, 30.0, 50.0, 80.0, 120.0, 180.0, 250.0, 315.0, 400.0, 500.0];
+
+/// Standard tolerances IT1 to IT18 in µm, one row per grade range
+const IT_GRADES: [[f64; 18]; 13] = [
+    [0.8, 1.2, 2.0, 3.0, 4.0, 6.0, 10.0, 14.0, 25.0, 40.0, 60.0, 100.0, 140.0, 250.0, 400.0, 600.0, 1000.0, 1400.0],
+    [1.0, 1.5, 2.5, 4.0, 5.0, 8.0, 12.0, 18.0, 30.0, 48.0, 75.0, 120.0, 180.0, 300.0, 480.0, 750.0, 1200.0, 1800.0],
+    [1.0, 1.5, 2.5, 4.0, 6.0, 9.0, 15.0, 22.0, 36.0, 58.0, 90.0, 150.0, 220.0, 360.0, 580.0, 900.0, 1500.0, 2200.0],
+    [1.2, 2.0, 3.0, 5.0, 8.0, 11.0, 18.0, 27.0, 43.0, 70.0, 110.0, 180.0, 270.0, 430.0, 700.0, 1100.0, 1800.0, 2700.0],
+    [1.5, 2.5, 4.0, 6.0, 9.0, 13.0, 21.0, 33.0, 52.0, 84.0, 130.0, 210.0, 330.0, 520.0, 840.0, 1300.0, 2100.0, 3300.0],
+    [1.5, 2.5, 4.0, 7.0, 11.0, 16.0, 25.0, 39.0, 62.0, 100.0, 160.0, 250.0, 390.0, 620.0, 1000.0, 1600.0, 2500.0, 3900.0],
+    [2.0, 3.0, 5.0, 8.0, 13.0, 19.0, 30.0, 46.0, 74.0, 120.0, 190.0, 300.0, 460.0, 740.0, 1200.0, 1900.0, 3000.0, 4600.0],
+    [2.5, 4.0, 6.0, 10.0, 15.0, 22.0, 35.0, 54.0, 87.0, 140.0, 220.0, 350.0, 540.0, 870.0, 1400.0, 2200.0, 3500.0, 5400.0],
+    [3.5, 5.0, 8.0, 12.0, 18.0, 25.0, 40.0, 63.0, 100.0, 160.0, 250.0, 400.0, 630.0, 1000.0, 1600.0, 2500.0, 4000.0, 6300.0],
+    [4.5, 7.0, 10.0, 14.0, 20.0, 29.0, 46.0, 72.0, 115.0, 185.0, 290.0, 460.0, 720.0, 1150.0, 1850.0, 2900.0, 4600.0, 7200.0],
+    [6.0, 8.0, 12.0, 16.0, 23.0, 32.0, 52.0, 81.0, 130.0, 210.0, 320.0, 520.0, 810.0, 1300.0, 2100.0, 3200.0, 5200.0, 8100.0],
+    [7.0, 9.0, 13.0, 18.0, 25.0, 36.0, 57.0, 89.0, 140.0, 230.0, 360.0, 570.0, 890.0, 1400.0, 2300.0, 3600.0, 5700.0, 8900.0],
+    [8.0, 10.0, 15.0, 20.0, 27.0, 40.0, 63.0, 97.0, 155.0, 250.0, 400.0, 630.0, 970.0, 1550.0, 2500.0, 4000.0, 6300.0, 9700.0],
+];
+
+/// Upper limits of the finer size ranges the fundamental deviations use, in mm
+const DEVIATION_RANGES: [f64; 25] = [
+    3.0, 6.0, 10.0, 14.0, 18.0, 24.0, 30.0, 40.0, 50.0, 65.0, 80.0, 100.0, 120.0,
+    140.0, 160.0, 180.0, 200.0, 225.0, 250.0, 280.0, 315.0, 355.0, 400.0, 450.0, 500.0,
+];
+
+/// Shaft fundamental deviations in µm, one entry per deviation range: the
+/// magnitude of the upper deviation es (negative) for c to g, and the lower
+/// deviation ei (positive) for m to u. k is handled on its own.
+const SHAFT_DEVIATIONS: [(Deviation, [f64; 25]); 11] = [
+    (Deviation::C, [60.0, 70.0, 80.0, 95.0, 95.0, 110.0, 110.0, 120.0, 130.0, 140.0, 150.0, 170.0, 180.0,
+        200.0, 210.0, 230.0, 240.0, 260.0, 280.0, 300.0, 330.0, 360.0, 400.0, 440.0, 480.0]),
+    (Deviation::D, [20.0, 30.0, 40.0, 50.0, 50.0, 65.0, 65.0, 80.0, 80.0, 100.0, 100.0, 120.0, 120.0,
+        145.0, 145.0, 145.0, 170.0, 170.0, 170.0, 190.0, 190.0, 210.0, 210.0, 230.0, 230.0]),
+    (Deviation::E, [14.0, 20.0, 25.0, 32.0, 32.0, 40.0, 40.0, 50.0, 50.0, 60.0, 60.0, 72.0, 72.0,
+        85.0, 85.0, 85.0, 100.0, 100.0, 100.0, 110.0, 110.0, 125.0, 125.0, 135.0, 135.0]),
+    (Deviation::F, [6.0, 10.0, 13.0, 16.0, 16.0, 20.0, 20.0, 25.0, 25.0, 30.0, 30.0, 36.0, 36.0,
+        43.0, 43.0, 43.0, 50.0, 50.0, 50.0, 56.0, 56.0, 62.0, 62.0, 68.0, 68.0]),
+    (Deviation::G, [2.0, 4.0, 5.0, 6.0, 6.0, 7.0, 7.0, 9.0, 9.0, 10.0, 10.0, 12.0, 12.0,
+        14.0, 14.0, 14.0, 15.0, 15.0, 15.0, 17.0, 17.0, 18.0, 18.0, 20.0, 20.0]),
+    (Deviation::M, [2.0, 4.0, 6.0, 7.0, 7.0, 8.0, 8.0, 9.0, 9.0, 11.0, 11.0, 13.0, 13.0,
+        15.0, 15.0, 15.0, 17.0, 17.0, 17.0, 20.0, 20.0, 21.0, 21.0, 23.0, 23.0]),
+    (Deviation::N, [4.0, 8.0, 10.0, 12.0, 12.0, 15.0, 15.0, 17.0, 17.0, 20.0, 20.0, 23.0, 23.0,
+        27.0, 27.0, 27.0, 31.0, 31.0, 31.0, 34.0, 34.0, 37.0, 37.0, 40.0, 40.0]),
+    (Deviation::P, [6.0, 12.0, 15.0, 18.0, 18.0, 22.0, 22.0, 26.0, 26.0, 32.0, 32.0, 37.0, 37.0,
+        43.0, 43.0, 43.0, 50.0, 50.0, 50.0, 56.0, 56.0, 62.0, 62.0, 68.0, 68.0]),
+    (Deviation::R, [10.0, 15.0, 19.0, 23.0, 23.0, 28.0, 28.0, 34.0, 34.0, 41.0, 43.0, 51.0, 54.0,
+        63.0, 65.0, 68.0, 77.0, 80.0, 84.0, 94.0, 98.0, 108.0, 114.0, 126.0, 132.0]),
+    (Deviation::S, [14.0, 19.0, 23.0, 28.0, 28.0, 35.0, 35.0, 43.0, 43.0, 53.0, 59.0, 71.0, 79.0,
+        92.0, 100.0, 108.0, 122.0, 130.0, 140.0, 158.0, 170.0, 190.0, 208.0, 232.0, 252.0]),
+    (Deviation::U, [18.0, 23.0, 28.0, 33.0, 33.0, 41.0, 48.0, 60.0, 70.0, 87.0, 102.0, 124.0, 144.0,
+        170.0, 190.0, 210.0, 236.0, 258.0, 284.0, 315.0, 350.0, 390.0, 435.0, 490.0, 540.0]),
+];
+
+/// Lower deviation of shaft k in µm for grades IT4 to IT7, per grade range;
+/// other grades have 0
+const K_DEVIATIONS: [f64; 13] = [0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 4.0, 5.0];
+
+/// Position of a tolerance zone relative to the nominal size. The letters
+/// covered are those of the ISO 286 and ANSI B4.2 preferred fits along with
+/// their common neighbours.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Deviation {
+    C, D, E, F, G, H, Js, K, M, N, P, R, S, U,
+}
+
+impl Deviation {
+    pub const ALL: [Deviation; 14] = [
+        Deviation::C, Deviation::D, Deviation::E, Deviation::F, Deviation::G, Deviation::H, Deviation::Js,
+        Deviation::K, Deviation::M, Deviation::N, Deviation::P, Deviation::R, Deviation::S, Deviation::U,
+    ];
+
+    /// Upper-case letters as used for holes
+    fn letters(&self) -> &'static str {
+        match self {
+            Deviation::C => "C",
+            Deviation::D => "D",
+            Deviation::E => "E",
+            Deviation::F => "F",
+            Deviation::G => "G",
+            Deviation::H => "H",
+            Deviation::Js => "JS",
+            Deviation::K => "K",
+            Deviation::M => "M",
+            Deviation::N => "N",
+            Deviation::P => "P",
+            Deviation::R => "R",
+            Deviation::S => "S",
+            Deviation::U => "U",
+        }
+    }
+
+    /// Shaft fundamental deviation in µm: es for c to h, ei for k to u.
+    /// `k_grade` says whether k takes its IT4 to IT7 value.
+    fn shaft(&self, range: usize, grade_range: usize, k_grade: bool) -> f64 {
+        let table = || SHAFT_DEVIATIONS.iter()
+            .find(|(d, _)| d == self)
+            .map_or(0.0, |(_, values)| values[range]);
+        match self {
+            Deviation::C | Deviation::D | Deviation::E | Deviation::F | Deviation::G => -table(),
+            Deviation::H | Deviation::Js => 0.0,
+            Deviation::K if k_grade => K_DEVIATIONS[grade_range],
+            Deviation::K => 0.0,
+            _ => table(),
+        }
+    }
+}
+
+/// An ISO 286 tolerance class: a fundamental deviation and an IT grade,
+/// written upper case for holes (H7) and lower case for shafts (g6)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct ToleranceClass {
+    pub deviation: Deviation,
+    /// Internal feature; upper-case letters
+    pub hole: bool,
+    /// IT grade, 1 to 18
+    pub grade: u8,
+}
+
+impl ToleranceClass {
+    /// Upper and lower deviations from a nominal size in mm, in µm
+    pub fn deviations(&self, size: f64) -> Result<(f64, f64)> {
+        if !(size > 0.0 && size <= 500.0) {
+            bail!("{} covers sizes up to 500 mm; {} mm is outside the table", self, size);
+        }
+        if !(1..=18).contains(&self.grade) {
+            bail!("{}: IT grades run from 1 to 18", self);
+        }
+        let grade_range = GRADE_RANGES.iter().position(|limit| size <= *limit).unwrap_or(GRADE_RANGES.len() - 1);
+        let range = DEVIATION_RANGES.iter().position(|limit| size <= *limit).unwrap_or(DEVIATION_RANGES.len() - 1);
+        let it = |grade: u8| IT_GRADES[grade_range][grade as usize - 1];
+        let tolerance = it(self.grade);
+
+        if self.deviation == Deviation::Js {
+            return Ok((tolerance / 2.0, -tolerance / 2.0));
+        }
+
+        // Hole K takes shaft k's IT4 to IT7 value for every grade up to IT8
+        let k_grade = if self.hole { self.grade <= 8 } else { (4..=7).contains(&self.grade) };
+        let shaft = self.deviation.shaft(range, grade_range, k_grade);
+        let upper_zone = matches!(self.deviation,
+            Deviation::C | Deviation::D | Deviation::E | Deviation::F | Deviation::G | Deviation::H);
+
+        if !self.hole {
+            return Ok(if upper_zone { (shaft, shaft - tolerance) } else { (shaft + tolerance, shaft) });
+        }
+
+        // Holes mirror the shafts, except that K to U in the fine grades
+        // move by Δ so a hole and shaft one grade finer give the same fit
+        if upper_zone {
+            return Ok((-shaft + tolerance, -shaft));
+        }
+        let delta = if size <= 3.0 || self.grade < 3 { 0.0 } else { tolerance - it(self.grade - 1) };
+        let upper = match self.deviation {
+            Deviation::K if self.grade <= 8 => -shaft + delta,
+            Deviation::K => 0.0,
+            Deviation::M if self.grade <= 8 => -shaft + delta,
+            Deviation::M => -shaft,
+            Deviation::N if self.grade <= 8 || size <= 3.0 => -shaft + delta,
+            Deviation::N => 0.0,
+            _ if self.grade <= 7 => -shaft + delta,
+            _ => -shaft,
+        };
+        Ok((upper, upper - tolerance))
+    }
+
+    /// Plus and minus tolerances at `nominal` in the project's units, in the
+    /// form `Dimension` stores them
+    pub fn tolerances(&self, nominal: f64, units: Units) -> Result<(f64, f64)> {
+        let millimetres_per_unit = units.millimetres();
+        let (upper, lower) = self.deviations(nominal * millimetres_per_unit)?;
+        let to_units = |microns: f64| microns / 1000.0 / millimetres_per_unit;
+        Ok((to_units(upper), -to_units(lower)))
+    }
+}
+
+impl fmt::Display for ToleranceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letters = self.deviation.letters();
+        if self.hole {
+            write!(f, "{}{}", letters, self.grade)
+        } else {
+            write!(f, "{}{}", letters.to_lowercase(), self.grade)
+        }
+    }
+}
+
+impl FromStr for ToleranceClass {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let split = s.find(|c: char| c.is_ascii_digit())
+            .ok_or_else(|| anyhow!("'{}' has no IT grade, e.g. H7 or g6", s))?;
+        let (letters, grade) = s.split_at(split);
+        let hole = letters.chars().next().is_some_and(|c| c.is_ascii_uppercase());
+        if letters.chars().any(|c| c.is_ascii_uppercase() != hole) {
+            bail!("'{}' mixes upper case (holes) and lower case (shafts)", s);
+        }
+        let deviation = Deviation::ALL.iter()
+            .find(|d| d.letters().eq_ignore_ascii_case(letters))
+            .copied()
+            .ok_or_else(|| anyhow!("'{}' is not a supported fundamental deviation", letters))?;
+        let grade = grade.parse::<u8>()
+            .ok()
+            .filter(|g| (1..=18).contains(g))
+            .ok_or_else(|| anyhow!("'{}' is not an IT grade from 1 to 18", grade))?;
+        Ok(Self { deviation, hole, grade })
+    }
+}
+
+impl TryFrom<String> for ToleranceClass {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<ToleranceClass> for String {
+    fn from(class: ToleranceClass) -> Self {
+        class.to_string()
+    }
+}
+
+/// A hole and shaft class that make a fit, e.g. H7/g6
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct FitDesignation {
+    pub hole: ToleranceClass,
+    pub shaft: ToleranceClass,
+}
+
+/// The ANSI B4.2 preferred fits with their names; ISO 286 recommends the
+/// same classes. Hole basis first, then shaft basis; H7/h6 is both.
+pub const PREFERRED_FITS: [(&str, &str); 19] = [
+    ("H11/c11", "Loose running"),
+    ("H9/d9", "Free running"),
+    ("H8/f7", "Close running"),
+    ("H7/g6", "Sliding"),
+    ("H7/h6", "Locational clearance"),
+    ("H7/k6", "Locational transition"),
+    ("H7/n6", "Locational transition"),
+    ("H7/p6", "Locational interference"),
+    ("H7/s6", "Medium drive"),
+    ("H7/u6", "Force"),
+    ("C11/h11", "Loose running"),
+    ("D9/h9", "Free running"),
+    ("F8/h7", "Close running"),
+    ("G7/h6", "Sliding"),
+    ("K7/h6", "Locational transition"),
+    ("N7/h6", "Locational transition"),
+    ("P7/h6", "Locational interference"),
+    ("S7/h6", "Medium drive"),
+    ("U7/h6", "Force"),
+];
+
+impl FitDesignation {
+    /// Smallest and largest clearance at `nominal` in the project's units;
+    /// negative clearance is interference
+    pub fn clearances(&self, nominal: f64, units: Units) -> Result<(f64, f64)> {
+        let (hole_plus, hole_minus) = self.hole.tolerances(nominal, units)?;
+        let (shaft_plus, shaft_minus) = self.shaft.tolerances(nominal, units)?;
+        Ok((-hole_minus - shaft_plus, hole_plus + shaft_minus))
+    }
+
+    /// Clearance, transition or interference, from the clearances at `nominal`
+    pub fn fit_type(&self, nominal: f64, units: Units) -> Result<FitType> {
+        let (min, max) = self.clearances(nominal, units)?;
+        Ok(if min >= 0.0 {
+            FitType::Clearance
+        } else if max <= 0.0 {
+            FitType::Interference
+        } else {
+            FitType::Transition
+        })
+    }
+
+    /// Give the mate's internal feature the hole class and its external
+    /// feature the shaft class, and set their tolerances. Both features must
+    /// share a nominal size. Returns the resulting fit type.
+    pub fn apply(&self, mate: &Mate, components: &mut [Component], units: Units) -> Result<FitType> {
+        let find = |components: &[Component], component: &str, feature: &str| components.iter()
+            .find(|c| c.name == component)
+            .and_then(|c| c.features.iter().find(|f| f.name == feature))
+            .map(|f| (f.feature_type, f.dimension.value))
+            .ok_or_else(|| anyhow!("Feature {}.{} not found", component, feature));
+        let (type_a, nominal_a) = find(components, &mate.component_a, &mate.feature_a)?;
+        let (type_b, nominal_b) = find(components, &mate.component_b, &mate.feature_b)?;
+
+        let (hole, shaft) = match (type_a, type_b) {
+            (FeatureType::Internal, FeatureType::External) => ((&mate.component_a, &mate.feature_a), (&mate.component_b, &mate.feature_b)),
+            (FeatureType::External, FeatureType::Internal) => ((&mate.component_b, &mate.feature_b), (&mate.component_a, &mate.feature_a)),
+            _ => bail!("{} needs one internal and one external feature", self),
+        };
+        if (nominal_a - nominal_b).abs() > 1e-9 * nominal_a.abs().max(1.0) {
+            bail!("{} needs both features at the same nominal size; they are {} and {}", self, nominal_a, nominal_b);
+        }
+        let fit_type = self.fit_type(nominal_a, units)?;
+
+        for ((component, feature), class) in [(hole, self.hole), (shaft, self.shaft)] {
+            if let Some(feature) = components.iter_mut()
+                .filter(|c| c.name == *component)
+                .flat_map(|c| c.features.iter_mut())
+                .find(|f| f.name == *feature)
+            {
+                feature.tolerance_class = Some(class);
+                feature.apply_tolerance_class(units)?;
+            }
+        }
+        Ok(fit_type)
+    }
+}
+
+impl fmt::Display for FitDesignation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.hole, self.shaft)
+    }
+}
+
+impl FromStr for FitDesignation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (hole, shaft) = s.split_once('/')
+            .ok_or_else(|| anyhow!("'{}' is not a fit such as H7/g6", s.trim()))?;
+        let (hole, shaft): (ToleranceClass, ToleranceClass) = (hole.parse()?, shaft.parse()?);
+        if !hole.hole || shaft.hole {
+            bail!("A fit is written hole/shaft, e.g. H7/g6");
+        }
+        Ok(Self { hole, shaft })
+    }
+}
+
+impl TryFrom<String> for FitDesignation {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<FitDesignation> for String {
+    fn from(fit: FitDesignation) -> Self {
+        fit.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Feature;
+
+    #[test]
+    fn test_iso_fits() {
+        // Limits at Ø20 and Ø45 from the ISO 286-2 tables, in µm
+        for (class, size, upper, lower) in [
+            ("H7", 20.0, 21.0, 0.0), ("g6", 20.0, -7.0, -20.0), ("F8", 20.0, 53.0, 20.0),
+            ("K7", 20.0, 6.0, -15.0), ("N7", 20.0, -7.0, -28.0), ("P7", 20.0, -14.0, -35.0),
+            ("U7", 20.0, -33.0, -54.0), ("k6", 20.0, 15.0, 2.0), ("js6", 20.0, 6.5, -6.5),
+            ("s6", 45.0, 59.0, 43.0), ("c11", 45.0, -130.0, -290.0),
+        ] {
+            let class: ToleranceClass = class.parse().unwrap();
+            assert_eq!(class.deviations(size).unwrap(), (upper, lower), "{} at {}", class, size);
+        }
+        assert!("H7".parse::<ToleranceClass>().unwrap().deviations(600.0).is_err());
+        assert!("Hg7".parse::<ToleranceClass>().is_err());
+        assert!("g6/H7".parse::<FitDesignation>().is_err());
+
+        let fit_type = |fit: &str| fit.parse::<FitDesignation>().unwrap().fit_type(20.0, Units::Metric).unwrap();
+        assert_eq!(fit_type("H7/g6"), FitType::Clearance);
+        assert_eq!(fit_type("H7/h6"), FitType::Clearance);
+        assert_eq!(fit_type("H7/k6"), FitType::Transition);
+        assert_eq!(fit_type("H7/s6"), FitType::Interference);
+        assert_eq!(fit_type("S7/h6"), FitType::Interference);
+
+        // Imperial nominals are converted, and deviations come back in inches
+        let class: ToleranceClass = "H7".parse().unwrap();
+        let (plus, minus) = class.tolerances(1.0, Units::Imperial).unwrap();
+        assert!((plus - 0.021 / 25.4).abs() < 1e-12 && minus == 0.0);
+
+        // A fit designation sets both features and their tolerances follow the nominal
+        let mut components = vec![
+            Component { name: "Housing".to_string(), description: None,
+                features: vec![Feature::new("Bore".to_string(), FeatureType::Internal, 20.0, 0.1, 0.1)] },
+            Component { name: "Shaft".to_string(), description: None,
+                features: vec![Feature::new("Journal".to_string(), FeatureType::External, 20.0, 0.1, 0.1)] },
+        ];
+        let mut mate = Mate::new("m".to_string(), "Shaft".to_string(), "Journal".to_string(),
+            "Housing".to_string(), "Bore".to_string(), FitType::Interference);
+        let fit: FitDesignation = "H7/g6".parse().unwrap();
+        mate.fit_type = fit.apply(&mate, &mut components, Units::Metric).unwrap();
+        mate.fit = Some(fit);
+        assert!(mate.validate(&components[1].features[0], &components[0].features[0]).is_valid);
+        let bore = &components[0].features[0];
+        assert_eq!(bore.tolerance_class, Some(fit.hole));
+        assert!((bore.dimension.plus_tolerance - 0.021).abs() < 1e-12);
+        let journal = &components[1].features[0];
+        assert!((journal.dimension.plus_tolerance + 0.007).abs() < 1e-12);
+        assert!((journal.dimension.minus_tolerance - 0.020).abs() < 1e-12);
+
+        components[0].features[0].dimension.value = 40.0;
+        components[0].features[0].apply_tolerance_class(Units::Metric).unwrap();
+        assert!((components[0].features[0].dimension.plus_tolerance - 0.025).abs() < 1e-12);
+
+        // Zero clearance passes only for a clearance fit set from a designation
+        let fit: FitDesignation = "H7/h6".parse().unwrap();
+        components[0].features[0].dimension.value = 20.0;
+        mate.fit = None;
+        mate.fit_type = fit.apply(&mate, &mut components, Units::Metric).unwrap();
+        let (journal, bore) = (&components[1].features[0], &components[0].features[0]);
+        assert_eq!(mate.validate(journal, bore).min_fit, 0.0);
+        assert!(!mate.validate(journal, bore).is_valid);
+        mate.fit = Some(fit);
+        assert!(mate.validate(journal, bore).is_valid);
+        let mut loose = journal.clone();
+        loose.dimension.plus_tolerance += 0.001;
+        assert!(!mate.validate(&loose, bore).is_valid);
+
+        // Classes are stored as their designations
+        let saved = ron::to_string(&mate).unwrap();
+        assert!(saved.contains("\"H7/h6\""));
+        let loaded: Mate = ron::from_str(&saved).unwrap();
+        assert_eq!(loaded.fit, Some(fit));
+    }
+}
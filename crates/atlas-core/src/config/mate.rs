@@ -3,20 +3,16 @@ use serde::{Serialize, Deserialize};
 use std::fmt;
 use super::Feature;
 use super::feature::FeatureType;
+use super::fits::FitDesignation;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum FitType {
-    Clearance,
+    #[default]
+    Clearance, // Makes sense as a default since it's the most common
     Transition,
     Interference,
 }
 
-impl Default for FitType {
-    fn default() -> Self {
-        FitType::Clearance // Makes sense as a default since it's the most common
-    }
-}
-
 impl fmt::Display for FitType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -44,6 +40,9 @@ pub struct Mate {
     pub component_b: String,
     pub feature_b: String,
     pub fit_type: FitType,
+    /// ISO 286 fit the features' tolerance classes came from, e.g. H7/g6
+    #[serde(default)]
+    pub fit: Option<FitDesignation>,
 }
 
 impl Mate {
@@ -62,6 +61,7 @@ impl Mate {
             component_b,
             feature_b,
             fit_type,
+            fit: None,
         }
     }
 
@@ -105,8 +105,14 @@ impl Mate {
         }
     }
 
+    /// Check the features against the fit type. Clearance fits from an ISO
+    /// 286 designation may close to zero clearance, as H7/h6 does.
     pub fn validate(&self, feature_a: &Feature, feature_b: &Feature) -> FitValidation {
-        self.fit_type.validate_fit(feature_a, feature_b)
+        let validation = self.fit_type.validate_fit(feature_a, feature_b);
+        if self.fit.is_some() && self.fit_type == FitType::Clearance && validation.min_fit >= 0.0 {
+            return FitValidation { is_valid: true, error_message: None, ..validation };
+        }
+        validation
     }
 }
 
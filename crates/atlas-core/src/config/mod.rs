@@ -0,0 +1,15 @@
+// src/config/mod.rs
+pub mod project;
+pub mod component;
+pub mod feature;
+pub mod mate;
+pub mod process;
+pub mod fits;
+
+// Re-export commonly used types
+pub use project::{ProjectFile, Units};
+pub use component::{Component, ComponentReference};
+pub use feature::{Feature, FeatureType, Dimension, CapabilityTarget, DistributionParams};
+pub use mate::Mate;
+pub use process::{CostCurve, FeatureProcess, ManufacturingProcess};
+pub use fits::{FitDesignation, ToleranceClass};
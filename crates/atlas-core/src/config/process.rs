@@ -0,0 +1,113 @@
+// src/config/process.rs
+use serde::{Serialize, Deserialize};
+use super::Units;
+
+/// How a feature is made, which sets what its tolerance costs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ManufacturingProcess {
+    Turning,
+    Milling,
+    Grinding,
+    InjectionMolding,
+    SheetMetal,
+    /// A curve entered by the user
+    Custom,
+}
+
+impl ManufacturingProcess {
+    pub const BUILT_IN: [ManufacturingProcess; 5] = [
+        ManufacturingProcess::Turning,
+        ManufacturingProcess::Milling,
+        ManufacturingProcess::Grinding,
+        ManufacturingProcess::InjectionMolding,
+        ManufacturingProcess::SheetMetal,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ManufacturingProcess::Turning => "Turning",
+            ManufacturingProcess::Milling => "Milling",
+            ManufacturingProcess::Grinding => "Grinding",
+            ManufacturingProcess::InjectionMolding => "Injection molding",
+            ManufacturingProcess::SheetMetal => "Sheet metal",
+            ManufacturingProcess::Custom => "Custom",
+        }
+    }
+
+    /// Representative cost curve for the process in relative cost units, from
+    /// published cost-tolerance studies; calibrate it against real quotes.
+    /// None for `Custom`.
+    pub fn table_curve(&self, units: Units) -> Option<CostCurve> {
+        // (fixed, scale, min, max) with tolerances in mm and a reciprocal curve
+        let (fixed, scale, min_tolerance, max_tolerance) = match self {
+            ManufacturingProcess::Turning => (2.0, 0.05, 0.0125, 0.25),
+            ManufacturingProcess::Milling => (3.0, 0.08, 0.025, 0.25),
+            ManufacturingProcess::Grinding => (6.0, 0.01, 0.0025, 0.025),
+            ManufacturingProcess::InjectionMolding => (0.5, 0.05, 0.05, 0.5),
+            ManufacturingProcess::SheetMetal => (1.0, 0.2, 0.1, 1.0),
+            ManufacturingProcess::Custom => return None,
+        };
+
+        let curve = CostCurve { fixed, scale, exponent: 1.0, min_tolerance, max_tolerance };
+        Some(match units {
+            Units::Metric => curve,
+            Units::Imperial => curve.scaled(1.0 / 25.4),
+        })
+    }
+}
+
+/// Cost of making a feature to a bilateral tolerance ±t:
+/// `fixed + scale / t^exponent` for t between the tightest and loosest
+/// tolerances the process is used for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CostCurve {
+    pub fixed: f64,
+    pub scale: f64,
+    pub exponent: f64,
+    /// Tightest tolerance the process holds
+    pub min_tolerance: f64,
+    /// Beyond this a looser tolerance saves nothing more
+    pub max_tolerance: f64,
+}
+
+impl CostCurve {
+    /// Cost at ±`tolerance`, or None if the process cannot hold it
+    pub fn cost(&self, tolerance: f64) -> Option<f64> {
+        (tolerance >= self.min_tolerance && tolerance > 0.0)
+            .then(|| self.value(tolerance.min(self.max_tolerance)))
+    }
+
+    /// The curve itself, without the process limits
+    pub fn value(&self, tolerance: f64) -> f64 {
+        self.fixed + self.scale / tolerance.powf(self.exponent)
+    }
+
+    /// d(cost)/d(tolerance), without the process limits
+    pub fn slope(&self, tolerance: f64) -> f64 {
+        -self.exponent * self.scale / tolerance.powf(self.exponent + 1.0)
+    }
+
+    /// The same curve with tolerances in units `factor` times as large
+    fn scaled(&self, factor: f64) -> Self {
+        Self {
+            scale: self.scale * factor.powf(self.exponent),
+            min_tolerance: self.min_tolerance * factor,
+            max_tolerance: self.max_tolerance * factor,
+            ..*self
+        }
+    }
+}
+
+/// A feature's process and the cost curve used for it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FeatureProcess {
+    pub process: ManufacturingProcess,
+    pub curve: CostCurve,
+}
+
+impl FeatureProcess {
+    /// The built-in curve of `process`, in the project's units
+    pub fn from_table(process: ManufacturingProcess, units: Units) -> Option<Self> {
+        process.table_curve(units).map(|curve| Self { process, curve })
+    }
+}
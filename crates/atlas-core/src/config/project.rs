@@ -1,4 +1,5 @@
 // src/config/project.rs
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
 use super::ComponentReference;
 
@@ -10,6 +11,9 @@ pub struct ProjectFile {
     pub units: Units,
     pub component_references: Vec<ComponentReference>,
     pub analyses: Vec<AnalysisReference>,  
+    /// Positions of the components in the assembly graph, by component name
+    #[serde(default)]
+    pub graph_layout: BTreeMap<String, [f64; 2]>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -18,6 +22,16 @@ pub enum Units {
     Imperial,
 }
 
+impl Units {
+    /// Length of one project unit in millimetres
+    pub fn millimetres(&self) -> f64 {
+        match self {
+            Units::Metric => 1.0,
+            Units::Imperial => 25.4,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisReference {
     pub path: String,
@@ -41,6 +55,7 @@ impl Default for ProjectFile {
             units: Units::Metric,
             component_references: Vec::new(),
             analyses: Vec::new(),
+            graph_layout: BTreeMap::new(),
         }
     }
 }
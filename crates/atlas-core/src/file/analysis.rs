@@ -154,10 +154,10 @@ impl AnalysisFileManager {
             .and_then(|s| s.samples.as_ref()) 
         {
             // Write data row by row
-            for i in 0..first_samples.len() {
+            for (i, first_sample) in first_samples.iter().enumerate() {
                 let mut record = Vec::new();
                 // Get stackup result for this row
-                record.push(first_samples[i].1.to_string());
+                record.push(first_sample.1.to_string());
                 
                 // Get individual feature values from each sensitivity
                 for sensitivity in &results.sensitivity {
@@ -5,7 +5,7 @@ use std::path::Path;
 use std::fs;
 use anyhow::{Result, Context};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ComponentFileHandler;
 
 impl ComponentFileHandler {
@@ -17,7 +17,11 @@ impl ComponentFileHandler {
 impl FileHandler<Component> for ComponentFileHandler {
     fn load(&self, path: &Path) -> Result<Component> {
         let content = fs::read_to_string(path)?;
-        ron::from_str(&content).context("Failed to parse component file")
+        let mut component: Component = ron::from_str(&content).context("Failed to parse component file")?;
+        for feature in &mut component.features {
+            feature.settle_distribution();
+        }
+        Ok(component)
     }
 
     fn save(&self, data: &Component, path: &Path) -> Result<()> {
@@ -12,6 +12,12 @@ pub struct MatesFile {
     pub mates: Vec<Mate>,
 }
 
+impl Default for MatesFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MatesFile {
     pub fn new() -> Self {
         Self {
@@ -21,7 +27,7 @@ impl MatesFile {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MatesFileHandler;
 
 impl MatesFileHandler {
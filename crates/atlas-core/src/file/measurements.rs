@@ -0,0 +1,122 @@
+// src/file/measurements.rs
+
+use std::io;
+use std::path::Path;
+use anyhow::{anyhow, Context, Result};
+use csv::{ReaderBuilder, Trim};
+use crate::config::Component;
+
+/// Numeric columns of an inspection CSV, e.g. a CMM export with one row per
+/// part and one column per measured feature
+#[derive(Debug, Clone)]
+pub struct MeasurementTable {
+    pub headers: Vec<String>,
+    /// Values in each column; blank and non-numeric cells are skipped
+    pub columns: Vec<Vec<f64>>,
+}
+
+/// Feature a column's measurements belong to, as (component, feature)
+pub type ColumnMapping = Option<(String, String)>;
+
+impl MeasurementTable {
+    pub fn read<R: io::Read>(reader: R) -> Result<Self> {
+        let mut reader = ReaderBuilder::new()
+            .flexible(true)
+            .trim(Trim::All)
+            .from_reader(reader);
+
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+        let mut columns = vec![Vec::new(); headers.len()];
+        for record in reader.records() {
+            let record = record?;
+            for (column, cell) in columns.iter_mut().zip(record.iter()) {
+                if let Ok(value) = cell.parse::<f64>() {
+                    if value.is_finite() {
+                        column.push(value);
+                    }
+                }
+            }
+        }
+
+        Ok(Self { headers, columns })
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open measurement file: {}", path.display()))?;
+        Self::read(file)
+            .with_context(|| format!("Failed to read measurement file: {}", path.display()))
+    }
+
+    /// The feature each column header names: `Component.Feature` (or with
+    /// `/`, `:` or `_` between), or a feature name found on one component
+    /// only. Case and surrounding spaces are ignored.
+    pub fn auto_map(&self, components: &[Component]) -> Vec<ColumnMapping> {
+        let normalize = |s: &str| s.trim().to_lowercase();
+
+        self.headers.iter()
+            .map(|header| {
+                let header = normalize(header);
+                let qualified = components.iter()
+                    .flat_map(|c| c.features.iter().map(move |f| (c, f)))
+                    .find(|(c, f)| ['.', '/', ':', '_'].iter().any(|separator| {
+                        header == format!("{}{}{}", normalize(&c.name), separator, normalize(&f.name))
+                    }));
+                if let Some((c, f)) = qualified {
+                    return Some((c.name.clone(), f.name.clone()));
+                }
+
+                let mut by_name = components.iter()
+                    .flat_map(|c| c.features.iter().map(move |f| (c, f)))
+                    .filter(|(_, f)| normalize(&f.name) == header);
+                match (by_name.next(), by_name.next()) {
+                    (Some((c, f)), None) => Some((c.name.clone(), f.name.clone())),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Store the mapped columns as their features' measurements, replacing
+    /// any already there. Returns how many features were updated.
+    pub fn apply(&self, mappings: &[ColumnMapping], components: &mut [Component]) -> Result<usize> {
+        let mut updated = 0;
+        for (column, mapping) in self.columns.iter().zip(mappings) {
+            let Some((component_id, feature_id)) = mapping else { continue };
+            let feature = components.iter_mut()
+                .filter(|c| c.name == *component_id)
+                .flat_map(|c| c.features.iter_mut())
+                .find(|f| f.name == *feature_id)
+                .ok_or_else(|| anyhow!("Feature {}.{} not found", component_id, feature_id))?;
+            feature.measurements = column.clone();
+            updated += 1;
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Feature, FeatureType};
+
+    #[test]
+    fn test_measurement_import() {
+        let feature = |name: &str| Feature::new(name.to_string(), FeatureType::External, 10.0, 0.05, 0.05);
+        let mut components = vec![Component {
+            name: "Part".to_string(),
+            description: None,
+            features: vec![feature("F0"), feature("F1")],
+        }];
+
+        // Columns map to features by name and carry the numbers only
+        let csv = "Serial, Part.F0, f1, Notes\n1, 10.01, 9.98, ok\n2, 10.02, , \n3, 9.99, 10.03, rework\n";
+        let table = MeasurementTable::read(csv.as_bytes()).unwrap();
+        let mappings = table.auto_map(&components);
+        assert_eq!(mappings[0], None);
+        assert_eq!(mappings[1], Some(("Part".to_string(), "F0".to_string())));
+        assert_eq!(mappings[2], Some(("Part".to_string(), "F1".to_string())));
+        assert_eq!(table.apply(&mappings, &mut components).unwrap(), 2);
+        assert_eq!(components[0].features[1].measurements, [9.98, 10.03]);
+    }
+}
@@ -7,12 +7,12 @@ use crate::file::mates::MatesFile;
 use crate::config::project::AnalysisReference;
 use crate::analysis::stackup::{StackupAnalysis, AnalysisResults};
 use crate::config::ComponentReference;
-use std::collections::HashMap;
 
 pub mod project;
 pub mod component;
 pub mod mates;
 pub mod analysis;
+pub mod measurements;
 
 // Core trait for file operations
 pub trait FileHandler<T> {
@@ -21,6 +21,9 @@ pub trait FileHandler<T> {
 }
 
 
+/// Everything read from a project file: the project, its components, mates and analyses
+pub type LoadedProject = (ProjectFile, Vec<Component>, MatesFile, Vec<(StackupAnalysis, Option<AnalysisResults>)>);
+
 #[derive(Debug)]
 pub struct FileManager {
     project_dir: Option<PathBuf>,
@@ -30,6 +33,12 @@ pub struct FileManager {
     pub analysis_handler: analysis::AnalysisFileManager,
 }
 
+impl Default for FileManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FileManager {
     pub fn new() -> Self {
         Self {
@@ -74,7 +83,7 @@ impl FileManager {
         }
     }
 
-    pub fn load_project(&self, path: &Path) -> Result<(ProjectFile, Vec<Component>, MatesFile, Vec<(StackupAnalysis, Option<AnalysisResults>)>)> {
+    pub fn load_project(&self, path: &Path) -> Result<LoadedProject> {
         // First verify the project file exists
         if !path.exists() {
             return Err(anyhow!("Project file not found: {}", path.display()));
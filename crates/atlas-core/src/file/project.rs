@@ -5,7 +5,7 @@ use std::path::Path;
 use std::fs;
 use anyhow::{Result, Context};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ProjectFileHandler;
 
 impl ProjectFileHandler {
@@ -0,0 +1,9 @@
+// src/lib.rs
+// Project model, stackup engine and file handling shared by the GUI and the CLI.
+pub mod analysis;
+pub mod config;
+pub mod file;
+pub mod project;
+pub mod utils;
+
+pub use project::Project;
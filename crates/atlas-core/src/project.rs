@@ -0,0 +1,162 @@
+// src/project.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Context, Result};
+
+use crate::analysis::{AnalysisResults, StackupAnalysis};
+use crate::config::{Component, Feature, ProjectFile};
+use crate::config::mate::Mate;
+use crate::file::FileManager;
+use crate::file::mates::MatesFile;
+use crate::utils::find_feature;
+
+/// An open Atlas project: the project file with its components, mates and
+/// analyses, the latest results of each analysis, and the file manager that
+/// reads and writes them.
+#[derive(Debug)]
+pub struct Project {
+    pub file: ProjectFile,
+    pub dir: Option<PathBuf>,
+    pub components: Vec<Component>,
+    pub mates: Vec<Mate>,
+    pub analyses: Vec<StackupAnalysis>,
+    pub latest_results: HashMap<String, AnalysisResults>, // analysis id -> results
+    pub file_manager: FileManager,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Project {
+    pub fn new() -> Self {
+        Self {
+            file: ProjectFile::default(),
+            dir: None,
+            components: Vec::new(),
+            mates: Vec::new(),
+            analyses: Vec::new(),
+            latest_results: HashMap::new(),
+            file_manager: FileManager::new(),
+        }
+    }
+
+    /// Open the project file at `path` along with everything it references.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut project = Self::new();
+        project.set_dir(project_dir_of(path)?)
+            .context("Error setting project directory")?;
+
+        let (project_file, components, mates_file, analyses) = project.file_manager.load_project(path)?;
+        project.file = project_file;
+        project.components = components;
+        project.mates = mates_file.mates;
+
+        for (analysis, results) in analyses {
+            // Keep any existing results
+            if let Some(results) = results {
+                project.latest_results.insert(analysis.id.clone(), results);
+            }
+            project.analyses.push(analysis);
+        }
+
+        Ok(project)
+    }
+
+    pub fn set_dir(&mut self, dir: PathBuf) -> Result<()> {
+        self.file_manager.set_project_dir(dir.clone())?;
+        self.dir = Some(dir);
+        Ok(())
+    }
+
+    /// Save the project file, components and mates into the project directory.
+    pub fn save(&mut self) -> Result<()> {
+        if self.dir.is_none() {
+            return Err(anyhow!("No project directory selected"));
+        }
+
+        self.file_manager.save_project(&self.file, &self.components, &self.analyses)?;
+
+        let mut mates_file = MatesFile::new();
+        mates_file.mates = self.mates.clone();
+        self.file_manager.save_mates(&mates_file)
+    }
+
+    /// Move the project to `dir` and save it there.
+    pub fn save_as(&mut self, dir: PathBuf) -> Result<()> {
+        self.set_dir(dir)?;
+        self.save()
+    }
+
+    /// Recompute the tolerances of every feature with a tolerance class, e.g.
+    /// after nominals change
+    pub fn apply_tolerance_classes(&mut self) -> Result<()> {
+        let units = self.file.units;
+        for component in &mut self.components {
+            for feature in &mut component.features {
+                feature.apply_tolerance_class(units)
+                    .with_context(|| format!("{}.{}", component.name, feature.name))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn find_feature(&self, component_name: &str, feature_name: &str) -> Option<&Feature> {
+        find_feature(&self.components, component_name, feature_name)
+    }
+
+    /// Look up an analysis by name or id.
+    pub fn analysis_index(&self, name_or_id: &str) -> Option<usize> {
+        self.analyses.iter().position(|a| a.id == name_or_id || a.name == name_or_id)
+    }
+
+    /// Run the analysis at `index`, keep its results as the latest and, when the
+    /// project has a directory, record them in the analysis history.
+    pub fn run_analysis(&mut self, index: usize) -> Result<AnalysisResults> {
+        let analysis = self.analyses.get(index)
+            .ok_or_else(|| anyhow!("No analysis at index {}", index))?;
+        let results = analysis.run_analysis(&self.components)?;
+        let analysis = analysis.clone();
+        self.store_results(&analysis, results.clone())?;
+
+        Ok(results)
+    }
+
+    /// Make `results` the latest results of `analysis` and save them to its
+    /// history, e.g. when a background analysis job finishes
+    pub fn store_results(&mut self, analysis: &StackupAnalysis, results: AnalysisResults) -> Result<()> {
+        self.latest_results.insert(analysis.id.clone(), results.clone());
+
+        if self.dir.is_some() {
+            self.file_manager.analysis_handler.save_analysis(analysis, &results)
+                .context("Error saving analysis results")?;
+        }
+
+        Ok(())
+    }
+
+    /// Make a results file from the analysis history the latest results of `analysis_id`.
+    pub fn load_results(&mut self, analysis_id: &str, relative_path: &str) -> Result<()> {
+        let results_path = self.file_manager.analysis_handler.get_results_file_path(relative_path);
+        let content = fs::read_to_string(&results_path)
+            .with_context(|| format!("Failed to read results file: {}", results_path.display()))?;
+        let results: AnalysisResults = ron::from_str(&content)
+            .with_context(|| format!("Failed to parse results file: {}", results_path.display()))?;
+
+        self.latest_results.insert(analysis_id.to_string(), results);
+        Ok(())
+    }
+}
+
+fn project_dir_of(path: &Path) -> Result<PathBuf> {
+    let parent = path.parent()
+        .ok_or_else(|| anyhow!("Invalid project path: {}", path.display()))?;
+    if parent.as_os_str().is_empty() {
+        Ok(PathBuf::from("."))
+    } else {
+        Ok(parent.to_path_buf())
+    }
+}
@@ -2,6 +2,7 @@
 use eframe::egui;
 use rfd::FileDialog;
 use std::path::PathBuf;
+use atlas_core::Project;
 
 use crate::state::{AppState, Screen, DialogState};
 use crate::ui::{dialog, DialogManager}; // Add DialogManager import
@@ -76,39 +77,15 @@ impl AtlasApp {
     }
 
     fn load_project(&mut self, path: PathBuf) {
-        let project_dir = path.parent().unwrap().to_path_buf();
-        match self.state.file_manager.set_project_dir(project_dir.clone()) {
-            Ok(_) => {
-                self.state.project_dir = Some(project_dir);
-                match self.state.file_manager.load_project(&path) {
-                    Ok((project_file, components, mates_file, analyses)) => {
-                        self.state.project_file = project_file;
-                        self.state.components = components;
-                        self.state.mates = mates_file.mates;
-                        
-                        // Load analyses and their latest results
-                        self.state.analyses.clear();
-                        self.state.latest_results.clear();
-                        
-                        for (analysis, results) in analyses {
-                            // Store any existing results
-                            if let Some(results) = results {
-                                self.state.latest_results.insert(analysis.id.clone(), results);
-                            }
-                            
-                            self.state.analyses.push(analysis);
-                        }
-                        
-                        self.state.update_mate_graph();
-                        self.state.error_message = None;
-                    }
-                    Err(e) => {
-                        self.state.error_message = Some(format!("Error loading project: {}", e));
-                    }
-                }
+        match Project::open(&path) {
+            Ok(project) => {
+                self.state.project = project;
+                self.state.assembly_graph = crate::state::GraphState::default();
+                self.state.update_mate_graph();
+                self.state.error_message = None;
             }
             Err(e) => {
-                self.state.error_message = Some(format!("Error setting project directory: {}", e));
+                self.state.error_message = Some(format!("Error loading project: {:#}", e));
             }
         }
     }
@@ -120,11 +97,8 @@ impl AtlasApp {
 
         if let Some(path) = file_dialog.save_file() {
             let project_dir = path.parent().unwrap().to_path_buf();
-            if let Ok(_) = self.state.file_manager.set_project_dir(project_dir.clone()) {
-                self.state.project_dir = Some(project_dir);
-                if let Err(e) = self.state.save_project() {
-                    self.state.error_message = Some(e.to_string());
-                }
+            if let Err(e) = self.state.project.save_as(project_dir) {
+                self.state.error_message = Some(e.to_string());
             }
         }
     }
@@ -132,6 +106,11 @@ impl AtlasApp {
 
 impl eframe::App for AtlasApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Keep redrawing while analyses run so progress and results show up
+        if self.state.poll_analysis_jobs() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             self.show_menu(ui);
         });
@@ -148,7 +127,7 @@ impl eframe::App for AtlasApp {
                     crate::ui::mates::show_mates_view(ui, &mut self.state);
                 },
                 Screen::DependencyMatrix => {
-                    ui.label("Dependencies View - Coming Soon");
+                    crate::ui::dependencies::show_dependencies_view(ui, &mut self.state);
                 },
                 Screen::Analysis => {
                     crate::ui::analysis::show_analysis_view(ui, &mut self.state);
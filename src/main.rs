@@ -2,13 +2,9 @@
 use eframe::egui;
 use anyhow::Result;
 
-mod analysis;
 mod app;
-mod config;
-mod file;
 mod state;
 mod ui;
-mod utils;
 
 use app::AtlasApp;
 
@@ -1,8 +1,9 @@
 // src/state/mate_state.rs
 use petgraph::graph::{Graph, NodeIndex};
 use std::collections::HashMap;
-use crate::config::mate::Mate;
-use crate::config::Component;
+use atlas_core::config::mate::{FitType, Mate};
+use atlas_core::config::{Component, DistributionParams, FeatureType};
+use atlas_core::analysis::mate_fit::MateFitResult;
 
 #[derive(Debug)]
 pub struct MateState {
@@ -10,6 +11,17 @@ pub struct MateState {
     pub dependency_graph: Graph<String, String>,
     pub feature_nodes: HashMap<(String, String), NodeIndex>, // (component_id, feature_id) -> node_index
     pub filter: Option<MateFilter>,
+    pub fit: Option<MateFitCache>,
+}
+
+/// Statistical fit of the selected mate, kept until the mate or its
+/// features change
+#[derive(Debug)]
+pub struct MateFitCache {
+    pub mate_id: String,
+    pub fit_type: FitType,
+    pub features: [(FeatureType, DistributionParams); 2],
+    pub result: Result<MateFitResult, String>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +37,7 @@ impl Default for MateState {
             dependency_graph: Graph::new(),
             feature_nodes: HashMap::new(),
             filter: None,
+            fit: None,
         }
     }
 }
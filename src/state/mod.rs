@@ -3,11 +3,13 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use anyhow::Result;
 
-use crate::config::{ProjectFile, Component};
-use crate::config::mate::Mate;
-use crate::analysis::{StackupAnalysis, AnalysisResults};
-use crate::file::FileManager;
-use crate::analysis::stackup::{AnalysisMethod, MonteCarloSettings};
+use atlas_core::Project;
+use atlas_core::analysis::stackup::{AnalysisMethod, MonteCarloSettings};
+use atlas_core::analysis::job::{AnalysisQueue, BackgroundJob, DoeJob, StudyJob};
+use atlas_core::analysis::allocation::{AllocationResult, AllocationSettings};
+use atlas_core::analysis::study::{StudyMetric, StudyResult, StudySettings};
+use atlas_core::analysis::doe::{DoeResult, DoeSettings};
+use atlas_core::analysis::fitting::DistributionFit;
 use crate::state::mate_state::MateState;
 
 pub mod mate_state;
@@ -81,6 +83,10 @@ pub enum DialogState {
         direction: f64,
         half_count: bool,
     },
+    ImportMeasurements {
+        path: PathBuf,
+    },
+    StackFromMates,
 }
 
 // Screen/tab tracking
@@ -99,32 +105,152 @@ pub enum AnalysisTab {
     Details,
     Results,
     Visualization,
+    Allocation,
+    Study,
+    Doe,
+}
+
+/// Tolerance allocation being set up for an analysis: settings being edited
+/// and the last proposal, not yet applied
+#[derive(Debug, Clone)]
+pub struct AllocationState {
+    pub analysis_id: String,
+    pub settings: AllocationSettings,
+    pub preview: Option<AllocationResult>,
+}
+
+/// What-if study of an analysis: the axes being edited, the run in progress
+/// and the last result
+#[derive(Debug)]
+pub struct StudyState {
+    pub analysis_id: String,
+    pub settings: StudySettings,
+    pub job: Option<StudyJob>,
+    pub result: Option<StudyResult>,
+    /// Plotted metric
+    pub metric: StudyMetric,
+}
+
+/// Designed experiment on an analysis: the factors and design being edited,
+/// the run in progress and the last result
+#[derive(Debug)]
+pub struct DoeState {
+    pub analysis_id: String,
+    pub settings: DoeSettings,
+    pub job: Option<DoeJob>,
+    pub result: Option<DoeResult>,
+}
+
+/// Distributions fitted to a feature's measurements, kept until the
+/// selected feature or its measurements change
+#[derive(Debug, Clone)]
+pub struct FitState {
+    pub component_id: String,
+    pub feature_id: String,
+    pub measurements: Vec<f64>,
+    pub fits: Result<Vec<DistributionFit>, String>,
+    /// Index of the fit in the probability plot
+    pub plotted: usize,
+}
+
+/// Rows and columns of the dependency matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DsmLevel {
+    Component,
+    Feature,
+}
+
+/// What the cells of the dependency matrix are shaded by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DsmColor {
+    MateCount,
+    FitType,
+    SharedStackups,
+}
+
+/// Options of the dependency matrix and the item drilled into
+#[derive(Debug, Clone)]
+pub struct DsmState {
+    pub level: DsmLevel,
+    pub color: DsmColor,
+    /// Reorder rows so coupled items form blocks
+    pub clustered: bool,
+    /// Weakest average coupling (mates plus shared stackups) within a block
+    pub min_coupling: f64,
+    /// (component, feature) of the selected row; no feature at the
+    /// component level
+    pub selected: Option<(String, Option<String>)>,
+}
+
+impl Default for DsmState {
+    fn default() -> Self {
+        Self {
+            level: DsmLevel::Component,
+            color: DsmColor::MateCount,
+            clustered: true,
+            min_coupling: 0.5,
+            selected: None,
+        }
+    }
+}
+
+/// Views of the Dependencies screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DependencyView {
+    Matrix,
+    Graph,
+}
+
+/// What the edges of the assembly graph are colored by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphColor {
+    FitType,
+    Validity,
+}
+
+/// Node positions and view of the assembly graph. Components in the
+/// project's saved layout stay where they were put; the rest are placed by
+/// the force layout.
+#[derive(Debug, Clone)]
+pub struct GraphState {
+    pub color: GraphColor,
+    /// Current position of each component, by name
+    pub positions: HashMap<String, [f64; 2]>,
+    /// Force layout steps left before the layout is considered settled
+    pub steps_left: usize,
+    /// Component being dragged
+    pub dragging: Option<String>,
+    pub pan: [f32; 2],
+    pub zoom: f32,
+}
+
+impl Default for GraphState {
+    fn default() -> Self {
+        Self {
+            color: GraphColor::FitType,
+            positions: HashMap::new(),
+            steps_left: 0,
+            dragging: None,
+            pan: [0.0, 0.0],
+            zoom: 1.0,
+        }
+    }
 }
 
 // Core application state
 #[derive(Debug)]
 pub struct AppState {
-    // Project data
-    pub project_file: ProjectFile,
-    pub project_dir: Option<PathBuf>,
-    pub components: Vec<Component>,
+    // Project data, analyses and file management
+    pub project: Project,
     
     // Dependency & mate tracking 
-    pub mates: Vec<Mate>,
     pub mate_graph: petgraph::Graph<String, String>,
     
-    // Analysis data
-    pub analyses: Vec<StackupAnalysis>,
-    pub latest_results: HashMap<String, AnalysisResults>,
-    
     // Minimal UI state
     pub current_screen: Screen,
     pub current_dialog: DialogState,
     pub analysis_tab: AnalysisTab,
     pub error_message: Option<String>,
-    
-    // File management
-    pub file_manager: FileManager,
 
     pub selected_component: Option<usize>,
     pub selected_feature: Option<usize>, 
@@ -132,43 +258,94 @@ pub struct AppState {
     pub selected_analysis: Option<usize>,
 
     pub mate_state: mate_state::MateState,
+
+    // Analyses running or waiting on the worker
+    pub analysis_queue: AnalysisQueue,
+
+    pub allocation: Option<AllocationState>,
+    pub study: Option<StudyState>,
+    pub doe: Option<DoeState>,
+    pub measurement_fits: Option<FitState>,
+    pub dsm: DsmState,
+    pub dependency_view: DependencyView,
+    pub assembly_graph: GraphState,
+}
+
+/// Move a finished background job's result into `result`, or its error into
+/// `error`. Returns whether the job is still running.
+fn poll_job<T: Send + 'static>(
+    job: &mut Option<BackgroundJob<T>>,
+    result: &mut Option<T>,
+    error: &mut Option<String>,
+) -> bool {
+    match job.as_ref().map(|job| job.try_finish()) {
+        Some(Some(Ok(finished))) => {
+            *result = Some(finished);
+            *job = None;
+            false
+        },
+        Some(Some(Err(e))) => {
+            *error = Some(format!("{:#}", e));
+            *job = None;
+            false
+        },
+        Some(None) => true,
+        None => false,
+    }
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
-            project_file: ProjectFile::default(),
-            project_dir: None,
-            components: Vec::new(),
-            mates: Vec::new(),
+            project: Project::new(),
             mate_graph: petgraph::Graph::new(),
             mate_state: mate_state::MateState::default(),
-            analyses: Vec::new(),
-            latest_results: HashMap::new(),
             current_screen: Screen::Project,
             current_dialog: DialogState::None,
             analysis_tab: AnalysisTab::Details,
             error_message: None,
-            file_manager: FileManager::new(),
             selected_component: None,
             selected_feature: None,
             selected_mate: None, 
             selected_analysis: None,
+            analysis_queue: AnalysisQueue::default(),
+            allocation: None,
+            study: None,
+            doe: None,
+            measurement_fits: None,
+            dsm: DsmState::default(),
+            dependency_view: DependencyView::Matrix,
+            assembly_graph: GraphState::default(),
         }
     }
 
     pub fn save_project(&mut self) -> Result<()> {
-        if self.project_dir.is_none() {
-            return Err(anyhow::anyhow!("No project directory selected"));
+        self.project.save()
+    }
+
+    /// Queue the analysis at `index` to run in the background
+    pub fn run_analysis(&mut self, index: usize) {
+        if let Some(analysis) = self.project.analyses.get(index) {
+            self.analysis_queue.enqueue(analysis.clone(), self.project.components.clone());
+        }
+    }
+
+    /// Store the results of finished analysis and study jobs, if any.
+    /// Returns whether jobs are still running.
+    pub fn poll_analysis_jobs(&mut self) -> bool {
+        if let Some((analysis, results)) = self.analysis_queue.poll() {
+            let stored = results.and_then(|results| self.project.store_results(&analysis, results));
+            if let Err(e) = stored {
+                self.error_message = Some(format!("{:#}", e));
+            }
         }
 
-        self.file_manager.save_project(
-            &self.project_file,
-            &self.components,
-            &self.analyses
-        )?;
+        let study_running = self.study.as_mut()
+            .is_some_and(|study| poll_job(&mut study.job, &mut study.result, &mut self.error_message));
+        let doe_running = self.doe.as_mut()
+            .is_some_and(|doe| poll_job(&mut doe.job, &mut doe.result, &mut self.error_message));
 
-        Ok(())
+        self.analysis_queue.is_busy() || study_running || doe_running
     }
 
     pub fn update_mate_graph(&mut self) {
@@ -176,7 +353,7 @@ impl AppState {
         let mut nodes = HashMap::new();
 
         // Create nodes for all features
-        for component in &self.components {
+        for component in &self.project.components {
             for feature in &component.features {
                 let node_id = self.mate_graph.add_node(feature.name.clone());
                 nodes.insert(
@@ -187,7 +364,7 @@ impl AppState {
         }
 
         // Add edges for mates
-        for mate in &self.mates {
+        for mate in &self.project.mates {
             if let (Some(&node_a), Some(&node_b)) = (
                 nodes.get(&(mate.component_a.clone(), mate.feature_a.clone())),
                 nodes.get(&(mate.component_b.clone(), mate.feature_b.clone()))
@@ -201,8 +378,8 @@ impl AppState {
         }
     }
     pub fn update_mate_state(&mut self) {
-        self.mate_state.mates = self.mates.clone();
-        self.mate_state.update_dependency_graph(&self.components);
+        self.mate_state.mates = self.project.mates.clone();
+        self.mate_state.update_dependency_graph(&self.project.components);
     }
 }
 
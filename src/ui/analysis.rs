@@ -1,10 +1,16 @@
 // src/ui/analysis.rs
 use eframe::egui;
 use egui_plot::{self, Plot, BarChart, Bar, Line};
-use crate::state::{AppState, DialogState, AnalysisTab};
-use crate::analysis::stackup::{AnalysisMethod, MonteCarloSettings, StackupAnalysis, AnalysisResults};
-use crate::config::{Component, Feature};
-use crate::utils::find_feature;
+use crate::state::{AllocationState, AppState, DialogState, AnalysisTab, DoeState, StudyState};
+use atlas_core::analysis::stackup::{AnalysisMethod, MonteCarloSettings, MrssCorrection, StackupAnalysis, AnalysisResults};
+use atlas_core::analysis::six_sigma::MeanShiftMode;
+use atlas_core::analysis::convergence::{PpmEstimate, PpmMethod};
+use atlas_core::analysis::allocation::{AllocationMethod, AllocationTarget, CostModel};
+use atlas_core::analysis::job::{DoeJob, StudyJob};
+use atlas_core::analysis::study::{set_parameter, StudyAxis, StudyMetric, StudyParameter, StudyResult, StudySettings};
+use atlas_core::analysis::doe::{CcdAlpha, DesignType, DoeFactor, DoeResult, DoeSettings};
+use atlas_core::config::{CapabilityTarget, Component, Feature};
+use atlas_core::utils::find_feature;
 
 pub fn show_analysis_view(ui: &mut egui::Ui, state: &mut AppState) {
     let available_size = ui.available_size();
@@ -34,6 +40,9 @@ pub fn show_analysis_view(ui: &mut egui::Ui, state: &mut AppState) {
                     (AnalysisTab::Details, "Details"),
                     (AnalysisTab::Results, "Results"),
                     (AnalysisTab::Visualization, "Visualization"),
+                    (AnalysisTab::Allocation, "Allocation"),
+                    (AnalysisTab::Study, "Study"),
+                    (AnalysisTab::Doe, "DOE"),
                 ];
 
                 for (tab, label) in tabs {
@@ -47,8 +56,8 @@ pub fn show_analysis_view(ui: &mut egui::Ui, state: &mut AppState) {
 
             // Tab content
             if let Some(selected_idx) = state.selected_analysis {
-                if let Some(analysis) = state.analyses.get(selected_idx).cloned() {
-                    let results = state.latest_results.get(&analysis.id).cloned();
+                if let Some(analysis) = state.project.analyses.get(selected_idx).cloned() {
+                    let results = state.project.latest_results.get(&analysis.id).cloned();
                     
                     match state.analysis_tab {
                         AnalysisTab::Details => {
@@ -56,8 +65,9 @@ pub fn show_analysis_view(ui: &mut egui::Ui, state: &mut AppState) {
                         },
                         AnalysisTab::Results => {
                             if let Some(results) = results {
-                                show_analysis_results(ui, state, &analysis);
+                                show_analysis_results(ui, state, &analysis, selected_idx);
                             } else {
+                                show_analysis_job(ui, state, &analysis.id);
                                 ui.centered_and_justified(|ui| {
                                     ui.label("No results available - run analysis to see results");
                                 });
@@ -72,6 +82,15 @@ pub fn show_analysis_view(ui: &mut egui::Ui, state: &mut AppState) {
                                 });
                             }
                         },
+                        AnalysisTab::Allocation => {
+                            show_tolerance_allocation(ui, state, &analysis, selected_idx);
+                        },
+                        AnalysisTab::Study => {
+                            show_parameter_study(ui, state, &analysis, selected_idx);
+                        },
+                        AnalysisTab::Doe => {
+                            show_designed_experiment(ui, state, &analysis, selected_idx);
+                        },
                     }
                 }
             } else {
@@ -90,13 +109,19 @@ fn show_analysis_list(ui: &mut egui::Ui, state: &mut AppState) {
         ui.heading("Analyses");
         ui.add_space(4.0);
 
-        if ui.button("➕ Add Analysis").clicked() {
-            state.current_dialog = DialogState::NewAnalysis {
-                name: String::new(),
-                methods: vec![AnalysisMethod::WorstCase],
-                monte_carlo_settings: MonteCarloSettings::default(),
-            };
-        }
+        ui.horizontal(|ui| {
+            if ui.button("➕ Add Analysis").clicked() {
+                state.current_dialog = DialogState::NewAnalysis {
+                    name: String::new(),
+                    methods: vec![AnalysisMethod::WorstCase],
+                    monte_carlo_settings: MonteCarloSettings::default(),
+                };
+            }
+
+            if ui.button("🔗 From Mates...").on_hover_text("Build a stack from the chain of mates across a gap").clicked() {
+                state.current_dialog = DialogState::StackFromMates;
+            }
+        });
 
         ui.add_space(8.0);
         ui.separator();
@@ -104,7 +129,7 @@ fn show_analysis_list(ui: &mut egui::Ui, state: &mut AppState) {
 
         egui::ScrollArea::vertical()
             .show(ui, |ui| {
-                let analyses = state.analyses.clone();
+                let analyses = state.project.analyses.clone();
                 for (index, analysis) in analyses.iter().enumerate() {
                     let is_selected = state.selected_analysis == Some(index);
                     
@@ -112,12 +137,18 @@ fn show_analysis_list(ui: &mut egui::Ui, state: &mut AppState) {
                         ui.set_width(ui.available_width());
                         
                         // Format the timestamp if available
-                        let timestamp = state.latest_results.get(&analysis.id)
+                        let timestamp = state.project.latest_results.get(&analysis.id)
                             .and_then(|r| DateTime::parse_from_rfc3339(&r.timestamp).ok())
                             .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
                             .unwrap_or_default();
                         
                         // Create compact display string
+                        let job_status = match state.analysis_queue.running() {
+                            Some(job) if job.analysis.id == analysis.id =>
+                                format!("\nRunning {:.0}%", job.progress.fraction() * 100.0),
+                            _ if state.analysis_queue.is_pending(&analysis.id) => "\nQueued".to_string(),
+                            _ => String::new(),
+                        };
                         let display_text = if timestamp.is_empty() {
                             format!(
                                 "{}\n{} methods, {} contributions",
@@ -133,7 +164,7 @@ fn show_analysis_list(ui: &mut egui::Ui, state: &mut AppState) {
                                 analysis.methods.len(),
                                 analysis.contributions.len()
                             )
-                        };
+                        } + &job_status;
                         
                         let response = ui.selectable_label(is_selected, display_text);
 
@@ -154,26 +185,18 @@ fn show_analysis_list(ui: &mut egui::Ui, state: &mut AppState) {
                             }
 
                             if ui.button("▶ Run Analysis").clicked() {
-                                let results = analysis.run_analysis(&state.components);
-                                state.latest_results.insert(analysis.id.clone(), results.clone());
-                                
-                                if let Err(e) = state.file_manager.analysis_handler.save_analysis(
-                                    analysis,
-                                    &results
-                                ) {
-                                    state.error_message = Some(format!("Error saving analysis results: {}", e));
-                                }
+                                state.run_analysis(index);
                                 ui.close_menu();
                             }
 
                             ui.separator();
 
                             if ui.button(egui::RichText::new("🗑 Delete").color(egui::Color32::RED)).clicked() {
-                                state.analyses.remove(index);
-                                if state.analyses.is_empty() {
+                                state.project.analyses.remove(index);
+                                if state.project.analyses.is_empty() {
                                     state.selected_analysis = None;
-                                } else if index >= state.analyses.len() {
-                                    state.selected_analysis = Some(state.analyses.len() - 1);
+                                } else if index >= state.project.analyses.len() {
+                                    state.selected_analysis = Some(state.project.analyses.len() - 1);
                                 }
                                 
                                 if let Err(e) = state.save_project() {
@@ -190,6 +213,35 @@ fn show_analysis_list(ui: &mut egui::Ui, state: &mut AppState) {
 }
 
 
+/// Progress bar and Cancel button while the analysis runs or waits in the queue
+fn show_analysis_job(ui: &mut egui::Ui, state: &mut AppState, analysis_id: &str) {
+    let mut cancel = false;
+
+    if let Some(job) = state.analysis_queue.running().filter(|job| job.analysis.id == analysis_id) {
+        let progress = &job.progress;
+        ui.horizontal(|ui| {
+            let text = if progress.is_cancelled() {
+                "Cancelling...".to_string()
+            } else {
+                format!("{} / {} iterations", progress.completed(), progress.total())
+            };
+            ui.add(egui::ProgressBar::new(progress.fraction())
+                .desired_width(240.0)
+                .text(text));
+            cancel = ui.add_enabled(!progress.is_cancelled(), egui::Button::new("Cancel")).clicked();
+        });
+    } else if state.analysis_queue.is_pending(analysis_id) {
+        ui.horizontal(|ui| {
+            ui.label("Queued");
+            cancel = ui.button("Remove from queue").clicked();
+        });
+    }
+
+    if cancel {
+        state.analysis_queue.cancel(analysis_id);
+    }
+}
+
 fn show_analysis_details(
     ui: &mut egui::Ui, 
     state: &mut AppState, 
@@ -222,6 +274,57 @@ fn show_analysis_details(
 
         ui.add_space(8.0);
 
+        // Stack expression if the stack is not a linear sum
+        if let Some(expression) = &analysis.expression {
+            ui.group(|ui| {
+                ui.heading("Stack Expression");
+                ui.monospace(expression);
+            });
+
+            ui.add_space(8.0);
+        }
+
+        // Modified RSS settings if enabled
+        if analysis.methods.contains(&AnalysisMethod::ModifiedRss) {
+            ui.group(|ui| {
+                ui.heading("Modified RSS Settings");
+                let settings = analysis.modified_rss_settings.clone().unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.label("Correction:");
+                    match settings.correction {
+                        MrssCorrection::Fixed(factor) => ui.label(format!("Fixed factor {:.3}", factor)),
+                        MrssCorrection::ContributorCount => ui.label("From contributor count"),
+                    };
+                });
+            });
+
+            ui.add_space(8.0);
+        }
+
+        // Six Sigma settings if enabled
+        if analysis.methods.contains(&AnalysisMethod::SixSigma) {
+            ui.group(|ui| {
+                ui.heading("Six Sigma Settings");
+                let settings = analysis.six_sigma_settings.clone().unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.label("Mean Shift:");
+                    match settings.mode {
+                        MeanShiftMode::Static => ui.label("Static"),
+                        MeanShiftMode::Dynamic => ui.label("Dynamic"),
+                    };
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Default Capability:");
+                    match settings.default_capability {
+                        CapabilityTarget::MeanShift { cp, k } => ui.label(format!("Cp {:.2}, shift {:.2}σ", cp, k)),
+                        CapabilityTarget::CpCpk { cp, cpk } => ui.label(format!("Cp {:.2}, Cpk {:.2}", cp, cpk)),
+                    };
+                });
+            });
+
+            ui.add_space(8.0);
+        }
+
         // Monte Carlo settings if enabled
         if analysis.methods.contains(&AnalysisMethod::MonteCarlo) {
             ui.group(|ui| {
@@ -241,6 +344,22 @@ fn show_analysis_details(
                             ui.label(seed.to_string());
                         });
                     }
+                    ui.horizontal(|ui| {
+                        ui.label("Sampling:");
+                        ui.label(settings.sampling.label());
+                    });
+                    if let Some(convergence) = &settings.convergence {
+                        ui.label(format!(
+                            "Adaptive: batches of {} up to {}, tolerance {:.1}% of σ",
+                            convergence.batch_size, convergence.max_iterations, convergence.tolerance * 100.0
+                        ));
+                    }
+                }
+                for group in &analysis.correlation_groups {
+                    let members: Vec<String> = group.members.iter()
+                        .map(|(component, feature)| format!("{}.{}", component, feature))
+                        .collect();
+                    ui.label(format!("Correlated ({}, ρ = {:.2}): {}", group.name, group.coefficient, members.join(", ")));
                 }
             });
 
@@ -273,14 +392,20 @@ fn show_analysis_details(
                                     ui.set_min_width(ui.available_width() - 50.0);
                                     
                                     // Find the actual feature to display its values
-                                    if let Some(feature) = find_feature(&state.components, &contrib.component_id, &contrib.feature_id) {
-                                        let label = format!(
+                                    if let Some(feature) = find_feature(&state.project.components, &contrib.component_id, &contrib.feature_id) {
+                                        let mut label = format!(
                                             "{}.{} {} {}",
                                             contrib.component_id,
                                             contrib.feature_id,
                                             if contrib.direction > 0.0 { "+" } else { "-" },
                                             if contrib.half_count { "(½)" } else { "" }
                                         );
+                                        if contrib.coefficient != 1.0 {
+                                            label.push_str(&format!(" ×{}", contrib.coefficient));
+                                        }
+                                        if let Some(variable) = &contrib.variable {
+                                            label.push_str(&format!(" [{}]", variable));
+                                        }
                                         ui.strong(label);
 
                                         ui.label(format!(
@@ -290,8 +415,18 @@ fn show_analysis_details(
                                             feature.dimension.minus_tolerance
                                         ));
 
-                                        if let Some(dist_type) = feature.distribution {
-                                            ui.label(format!("Distribution: {:?}", dist_type));
+                                        match contrib.distribution.as_ref().filter(|p| !p.calculated) {
+                                            Some(params) => {
+                                                ui.label(format!("Distribution: {:?} (override)", params.dist_type));
+                                            },
+                                            None => {
+                                                let params = feature.effective_distribution();
+                                                ui.label(format!(
+                                                    "Distribution: {:?}{}",
+                                                    params.dist_type,
+                                                    if params.calculated { "" } else { " (custom)" }
+                                                ));
+                                            },
                                         }
                                     } else {
                                         ui.colored_label(
@@ -304,7 +439,7 @@ fn show_analysis_details(
                                 // Add edit/delete buttons on the right
                                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                     if ui.small_button("🗑").clicked() {
-                                        if let Some(analysis) = state.analyses.get_mut(analysis_index) {
+                                        if let Some(analysis) = state.project.analyses.get_mut(analysis_index) {
                                             analysis.contributions.remove(idx);
                                             // Save changes
                                             if let Err(e) = state.save_project() {
@@ -333,7 +468,12 @@ fn show_analysis_details(
 }
 
 
-fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &StackupAnalysis) {
+fn show_analysis_results(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    analysis: &StackupAnalysis,
+    analysis_index: usize,
+) {
     // Main layout - vertical with Latest Results on top, History on bottom
     ui.vertical(|ui| {
         // Top section - Latest Results
@@ -341,7 +481,7 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
             ui.set_width(ui.available_width());
             
             // Clone the results if available (to avoid borrow issues)
-            let results_clone = state.latest_results.get(&analysis.id).cloned();
+            let results_clone = state.project.latest_results.get(&analysis.id).cloned();
             
             // Run Analysis button (outside of any closures to avoid borrow conflicts)
             ui.horizontal(|ui| {
@@ -349,24 +489,20 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("▶ Run Analysis").clicked() {
-                        let new_results = analysis.run_analysis(&state.components);
-                        
-                        if let Err(e) = state.file_manager.analysis_handler.save_analysis(
-                            analysis,
-                            &new_results
-                        ) {
-                            state.error_message = Some(format!("Error saving analysis results: {}", e));
-                        }
-                        
-                        // Update results after saving
-                        state.latest_results.insert(analysis.id.clone(), new_results);
+                        state.run_analysis(analysis_index);
                     }
                 });
             });
+
+            show_analysis_job(ui, state, &analysis.id);
             
             ui.add_space(8.0);
             
             if let Some(results) = results_clone {
+                if results.cancelled {
+                    ui.colored_label(egui::Color32::YELLOW, "Cancelled - Monte Carlo results cover only the samples drawn");
+                }
+
                 // Nominal value
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
@@ -390,28 +526,87 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
                                 ui.label(format!("Range: {:.6}", wc.max - wc.min));
                             });
                         });
+                    }
 
-                        if let Some(rss) = &results.rss {
-                            ui.group(|ui| {
-                                ui.vertical(|ui| {
-                                    ui.heading("RSS Analysis");
-                                    ui.label(format!("Mean: {:.6}", results.nominal));
-                                    ui.label(format!("Std Dev: {:.6}", rss.std_dev));
-                                    ui.label(format!("3σ Range: [{:.6}, {:.6}]", rss.min, rss.max));
-                                });
+                    if let Some(rss) = &results.rss {
+                        ui.group(|ui| {
+                            ui.vertical(|ui| {
+                                ui.heading("RSS Analysis");
+                                ui.label(format!("Mean: {:.6}", rss.mean));
+                                ui.label(format!("Std Dev: {:.6}", rss.std_dev));
+                                ui.label(format!("3σ Range: [{:.6}, {:.6}]", rss.min, rss.max));
                             });
-                        }
+                        });
+                    }
 
-                        if let Some(mc) = &results.monte_carlo {
-                            ui.group(|ui| {
-                                ui.vertical(|ui| {
-                                    ui.heading("Monte Carlo");
-                                    ui.label(format!("Mean: {:.6}", mc.mean));
-                                    ui.label(format!("Std Dev: {:.6}", mc.std_dev));
-                                    ui.label(format!("Range: [{:.6}, {:.6}]", mc.min, mc.max));
-                                });
+                    if let Some(mrss) = &results.modified_rss {
+                        ui.group(|ui| {
+                            ui.vertical(|ui| {
+                                ui.heading("Modified RSS");
+                                ui.label(format!("Correction Factor: {:.3}", mrss.correction_factor));
+                                ui.label(format!("Std Dev: {:.6}", mrss.std_dev));
+                                ui.label(format!("3σ Range: [{:.6}, {:.6}]", mrss.min, mrss.max));
                             });
-                        }
+                        });
+                    }
+
+                    if let Some(six_sigma) = &results.six_sigma {
+                        ui.group(|ui| {
+                            ui.vertical(|ui| {
+                                ui.heading(format!("Six Sigma ({:?})", six_sigma.mode));
+                                ui.label(format!("Mean Drift: ±{:.6}", six_sigma.mean_drift));
+                                ui.label(format!("Std Dev (st/lt): {:.6} / {:.6}",
+                                    six_sigma.short_term_std_dev, six_sigma.long_term_std_dev));
+                                ui.label(format!("Range: [{:.6}, {:.6}]", six_sigma.min, six_sigma.max));
+                                if let Some(cpk) = six_sigma.cpk {
+                                    ui.label(format!("Predicted Cpk: {:.3}", cpk));
+                                }
+                                let ppm = six_sigma.ppm_above.unwrap_or(0.0) + six_sigma.ppm_below.unwrap_or(0.0);
+                                if six_sigma.ppm_above.is_some() || six_sigma.ppm_below.is_some() {
+                                    ui.label(format!("Predicted PPM: {:.1}", ppm));
+                                }
+                            });
+                        });
+                    }
+
+                    if let Some(mc) = &results.monte_carlo {
+                        ui.group(|ui| {
+                            ui.vertical(|ui| {
+                                ui.heading("Monte Carlo");
+                                ui.label(format!("Mean: {:.6}", mc.mean));
+                                ui.label(format!("Std Dev: {:.6}", mc.std_dev));
+                                if let Some(independent) = mc.independent_std_dev {
+                                    ui.label(format!("Independent Std Dev: {:.6} ({:+.1}% from correlation)",
+                                        independent,
+                                        (mc.std_dev / independent - 1.0) * 100.0));
+                                }
+                                ui.label(format!("Range: [{:.6}, {:.6}]", mc.min, mc.max));
+                                match mc.converged {
+                                    Some(true) => ui.label(format!("Samples: {} (converged)", mc.iterations)),
+                                    Some(false) => ui.colored_label(
+                                        egui::Color32::YELLOW,
+                                        format!("Samples: {} (not converged)", mc.iterations)
+                                    ),
+                                    None => ui.label(format!("Samples: {}", mc.iterations)),
+                                };
+                                ui.label(format!("Sampling: {}", mc.sampling.label()));
+                                ui.label(format!("Std Error: mean {:.6}, std dev {:.6}", mc.mean_std_error, mc.std_dev_std_error));
+                                for (label, estimate) in [("below", mc.ppm_below), ("above", mc.ppm_above)] {
+                                    if let Some(estimate) = estimate {
+                                        ui.label(format!(
+                                            "Sample PPM {}: {:.1} (95%: {:.1} – {:.1})",
+                                            label, estimate.ppm, estimate.lower, estimate.upper
+                                        ));
+                                    }
+                                }
+                                for (label, estimate) in [("below", mc.tail_below), ("above", mc.tail_above)] {
+                                    if let Some(PpmEstimate { ppm, lower, upper, method: PpmMethod::ImportanceSampled { samples, shift }, .. }) = estimate {
+                                        ui.label(format!("Tail PPM {}: {:.4} (95%: {:.4} – {:.4})", label, ppm, lower, upper))
+                                            .on_hover_text(format!("Importance sampled: {} samples around the failure point {:.2}σ out", samples, shift));
+                                    }
+                                }
+                            });
+                        });
                     }
                 });
 
@@ -477,11 +672,16 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
                                 ui.horizontal(|ui| {
                                     ui.label("Expected PPM:");
                                     ui.add_space(5.0);
-                                    ui.label(format!("Below: {:.1}", ppm_below));
+                                    let band = |band: Option<(f64, f64)>| band
+                                        .map(|(lower, upper)| format!("95%: {:.4} – {:.4}", lower, upper))
+                                        .unwrap_or_default();
+                                    ui.label(format!("Below: {:.4}", ppm_below))
+                                        .on_hover_text(band(process_cap.ppm_below_band));
                                     ui.add_space(20.0);
-                                    ui.label(format!("Above: {:.1}", ppm_above));
+                                    ui.label(format!("Above: {:.4}", ppm_above))
+                                        .on_hover_text(band(process_cap.ppm_above_band));
                                     ui.add_space(20.0);
-                                    ui.label(format!("Total: {:.1}", ppm_below + ppm_above));
+                                    ui.label(format!("Total: {:.4}", ppm_below + ppm_above));
                                 });
                             }
                             
@@ -501,6 +701,39 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
                     });
                 }
 
+                // Estimated cost of the features at their tolerances
+                if let Some(cost) = &results.cost {
+                    ui.add_space(8.0);
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            ui.heading("Estimated Cost");
+                            ui.strong(format!("Total: {:.3}", cost.total));
+                            egui::Grid::new("cost_estimate")
+                                .num_columns(4)
+                                .spacing([16.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for feature in &cost.features {
+                                        ui.label(format!("{}.{}", feature.component_id, feature.feature_id));
+                                        ui.label(feature.process.label());
+                                        ui.label(format!("±{:.4}", feature.tolerance));
+                                        match feature.cost {
+                                            Some(value) => ui.label(format!("{:.3}", value)),
+                                            None => ui.colored_label(egui::Color32::RED, "Tighter than the process holds"),
+                                        };
+                                        ui.end_row();
+                                    }
+                                });
+                            if !cost.unpriced.is_empty() {
+                                let names: Vec<String> = cost.unpriced.iter()
+                                    .map(|(component, feature)| format!("{}.{}", component, feature))
+                                    .collect();
+                                ui.label(format!("No process, not costed: {}", names.join(", ")));
+                            }
+                        });
+                    });
+                }
+
                 // Confidence Intervals
                 if let Some(mc) = &results.monte_carlo {
                     ui.add_space(8.0);
@@ -508,12 +741,18 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
                         ui.vertical(|ui| {
                             ui.heading("Confidence Intervals");
                             for interval in &mc.confidence_intervals {
-                                ui.label(format!(
+                                let response = ui.label(format!(
                                     "{:.1}%: [{:.6}, {:.6}]",
                                     interval.confidence_level * 100.0,
                                     interval.lower_bound,
                                     interval.upper_bound
                                 ));
+                                if let (Some(lower), Some(upper)) = (interval.lower_band, interval.upper_band) {
+                                    response.on_hover_text(format!(
+                                        "95% bands: lower [{:.6}, {:.6}], upper [{:.6}, {:.6}]",
+                                        lower.0, lower.1, upper.0, upper.1
+                                    ));
+                                }
                             }
                         });
                     });
@@ -534,10 +773,10 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
             
             // Create a separate scope for working with metadata to avoid borrow issues
             let analysis_id = analysis.id.clone();
-            let latest_results_timestamp = state.latest_results.get(&analysis_id)
+            let latest_results_timestamp = state.project.latest_results.get(&analysis_id)
                 .map(|r| r.timestamp.clone());
                 
-            let metadata_result = state.file_manager.analysis_handler.load_metadata(&analysis_id);
+            let metadata_result = state.project.file_manager.analysis_handler.load_metadata(&analysis_id);
             
             match metadata_result {
                 Ok(metadata) => {
@@ -588,13 +827,8 @@ fn show_analysis_results(ui: &mut egui::Ui, state: &mut AppState, analysis: &Sta
                                             if !is_current {
                                                 if ui.button("Load").clicked() {
                                                     // Load the selected results
-                                                    let results_path = state.file_manager.analysis_handler
-                                                        .get_results_file_path(&result_file.path);
-                                                        
-                                                    if let Ok(content) = std::fs::read_to_string(&results_path) {
-                                                        if let Ok(results) = ron::from_str(&content) {
-                                                            state.latest_results.insert(analysis_id.clone(), results);
-                                                        }
+                                                    if let Err(e) = state.project.load_results(&analysis_id, &result_file.path) {
+                                                        state.error_message = Some(format!("{:#}", e));
                                                     }
                                                 }
                                             } else {
@@ -714,9 +948,8 @@ fn show_analysis_visualization(
 
                             // Add bars for each contribution
                             for (i, contrib) in analysis.contributions.iter().enumerate() {
-                                if let Some(feature) = find_feature(&state.components, &contrib.component_id, &contrib.feature_id) {
-                                    let value = contrib.direction * feature.dimension.value 
-                                        * if contrib.half_count { 0.5 } else { 1.0 };
+                                if let Some(feature) = find_feature(&state.project.components, &contrib.component_id, &contrib.feature_id) {
+                                    let value = contrib.sensitivity() * feature.dimension.value;
                                     
                                     running_total += value;
                                     
@@ -746,17 +979,41 @@ fn show_analysis_visualization(
                         // Add contribution statistics
                         ui.group(|ui| {
                             ui.heading("Sensitivities");
-                            for sens in &mc.sensitivity {
-                                ui.horizontal(|ui| {
-                                    ui.label(format!(
-                                        "{}.{}: {:.1}% (correlation: {:.3})",
-                                        sens.component_id,
-                                        sens.feature_id,
-                                        sens.contribution_percent,
-                                        sens.correlation.unwrap_or(0.0)
-                                    ));
+                            egui::Grid::new("mc_sensitivities")
+                                .num_columns(5)
+                                .spacing([16.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.strong("Contributor");
+                                    ui.strong("Contribution");
+                                    ui.strong("Correlation");
+                                    ui.strong("First order")
+                                        .on_hover_text("Share of the stack variance from this contributor alone");
+                                    ui.strong("Total effect")
+                                        .on_hover_text("Share of the stack variance from this contributor including its interactions");
+                                    ui.end_row();
+
+                                    let index = |value: Option<f64>| value
+                                        .map(|v| format!("{:.3}", v))
+                                        .unwrap_or_else(|| "—".to_string());
+                                    for sens in &mc.sensitivity {
+                                        ui.label(format!("{}.{}", sens.component_id, sens.feature_id));
+                                        ui.label(format!("{:.1}%", sens.contribution_percent));
+                                        ui.label(format!("{:.3}", sens.correlation.unwrap_or(0.0)));
+                                        ui.label(index(sens.sobol_first_order));
+                                        ui.label(index(sens.sobol_total));
+                                        ui.end_row();
+                                    }
                                 });
-                            }
+
+                            // First-order indices fall short of 1 by the share of interactions
+                            let first_order: Option<f64> = mc.sensitivity.iter()
+                                .map(|sens| sens.sobol_first_order)
+                                .sum();
+                            match first_order {
+                                Some(sum) => ui.label(format!("Interactions: {:.1}% of the variance", ((1.0 - sum) * 100.0).max(0.0))),
+                                None => ui.label("No Sobol indices: the contributors are correlated or the results predate them"),
+                            };
                         });
                     });
                 });
@@ -766,4 +1023,937 @@ fn show_analysis_visualization(
             ui.label("Run Monte Carlo analysis to see visualizations");
         });
     }
-}
\ No newline at end of file
+}
+
+fn show_tolerance_allocation(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    analysis: &StackupAnalysis,
+    analysis_index: usize,
+) {
+    // Start from the settings saved with the analysis
+    if state.allocation.as_ref().is_none_or(|a| a.analysis_id != analysis.id) {
+        state.allocation = Some(AllocationState {
+            analysis_id: analysis.id.clone(),
+            settings: analysis.allocation.clone().unwrap_or_default(),
+            preview: None,
+        });
+    }
+    let Some(allocation) = state.allocation.as_mut() else { return };
+    let settings = &mut allocation.settings;
+
+    let mut preview_clicked = false;
+    let mut apply_clicked = false;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.group(|ui| {
+            ui.heading("Tolerance Allocation");
+            if analysis.upper_spec_limit.is_none() && analysis.lower_spec_limit.is_none() {
+                ui.colored_label(egui::Color32::YELLOW, "Set spec limits on the analysis to allocate tolerances");
+            }
+
+            egui::Grid::new("allocation_settings")
+                .num_columns(2)
+                .spacing([16.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Target:");
+                    ui.horizontal(|ui| {
+                        let mut cpk = match settings.target {
+                            AllocationTarget::Cpk(cpk) => Some(cpk),
+                            AllocationTarget::WorstCase => None,
+                        };
+                        let cpk_target = Some(cpk.unwrap_or(1.33));
+                        ui.radio_value(&mut cpk, cpk_target, "Cpk ≥");
+                        if let Some(value) = cpk.as_mut() {
+                            ui.add(egui::DragValue::new(value).speed(0.01).clamp_range(0.1..=5.0));
+                        }
+                        ui.radio_value(&mut cpk, None, "Worst case within spec");
+                        settings.target = cpk.map_or(AllocationTarget::WorstCase, AllocationTarget::Cpk);
+                    });
+                    ui.end_row();
+
+                    ui.label("Method:");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut settings.method, AllocationMethod::Proportional, "Proportional scaling")
+                            .on_hover_text("Scale every current tolerance by the same factor");
+                        ui.radio_value(&mut settings.method, AllocationMethod::EqualPrecision, "Equal precision")
+                            .on_hover_text("The same ISO tolerance grade for every feature, sized to its nominal");
+                        ui.radio_value(&mut settings.method, AllocationMethod::MinimumCost, "Minimum cost")
+                            .on_hover_text("Least total cost under the cost model");
+                    });
+                    ui.end_row();
+
+                    ui.label("Cost model:");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut settings.cost_model, CostModel::Reciprocal, "Reciprocal (w / t)");
+                        ui.radio_value(&mut settings.cost_model, CostModel::Exponential, "Exponential (w·e^(-t/t₀))");
+                        ui.radio_value(&mut settings.cost_model, CostModel::Process, "Process curves")
+                            .on_hover_text("Each feature's manufacturing process cost curve; its limits bound the tolerance");
+                    });
+                    ui.end_row();
+                });
+
+            ui.add_space(8.0);
+            ui.strong("Feature bounds");
+            egui::Grid::new("allocation_features")
+                .num_columns(5)
+                .spacing([16.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Feature");
+                    ui.strong("Current ±");
+                    ui.strong("Min tolerance");
+                    ui.strong("Max tolerance");
+                    ui.strong("Cost weight");
+                    ui.end_row();
+
+                    let mut seen = Vec::new();
+                    for contrib in &analysis.contributions {
+                        let key = (contrib.component_id.clone(), contrib.feature_id.clone());
+                        if seen.contains(&key) {
+                            continue;
+                        }
+                        let Some(feature) = find_feature(&state.project.components, &key.0, &key.1) else { continue };
+                        let current = feature.dimension.bilateral_tolerance();
+                        seen.push(key);
+
+                        let bounds = settings.feature_mut(&contrib.component_id, &contrib.feature_id);
+                        ui.label(format!("{}.{}", contrib.component_id, contrib.feature_id));
+                        ui.label(format!("{:.4}", current));
+                        for (id, bound) in [("min", &mut bounds.min_tolerance), ("max", &mut bounds.max_tolerance)] {
+                            ui.horizontal(|ui| {
+                                let mut enabled = bound.is_some();
+                                ui.push_id(id, |ui| {
+                                    if ui.checkbox(&mut enabled, "").changed() {
+                                        *bound = enabled.then_some(current);
+                                    }
+                                });
+                                if let Some(value) = bound.as_mut() {
+                                    ui.add(egui::DragValue::new(value).speed(0.001).clamp_range(0.0..=f64::MAX));
+                                }
+                            });
+                        }
+                        ui.add(egui::DragValue::new(&mut bounds.cost_weight).speed(0.1).clamp_range(0.0..=1000.0));
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(8.0);
+            if ui.button("Preview").clicked() {
+                preview_clicked = true;
+            }
+        });
+
+        if let Some(result) = &allocation.preview {
+            ui.add_space(8.0);
+            ui.group(|ui| {
+                ui.heading("Proposed Tolerances");
+                match (result.met, result.predicted_cpk()) {
+                    (true, Some(cpk)) => ui.colored_label(egui::Color32::GREEN, format!("Target met: predicted Cpk {:.3}", cpk)),
+                    (false, Some(cpk)) => ui.colored_label(
+                        egui::Color32::RED,
+                        format!("Target not met within the bounds: predicted Cpk {:.3}", cpk)
+                    ),
+                    (true, None) => ui.colored_label(
+                        egui::Color32::GREEN,
+                        format!("Target met: worst case ±{:.6} of ±{:.6} margin", result.width, result.budget)
+                    ),
+                    (false, None) => ui.colored_label(
+                        egui::Color32::RED,
+                        format!("Target not met within the bounds: worst case ±{:.6} of ±{:.6} margin", result.width, result.budget)
+                    ),
+                };
+                ui.label(format!("Cost: {:.3} → {:.3}", result.current_cost, result.proposed_cost));
+
+                egui::Grid::new("allocation_preview")
+                    .num_columns(4)
+                    .spacing([16.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Feature");
+                        ui.strong("Current");
+                        ui.strong("Proposed");
+                        ui.strong("Cost");
+                        ui.end_row();
+
+                        for proposal in &result.proposals {
+                            ui.label(format!("{}.{}", proposal.component_id, proposal.feature_id));
+                            ui.label(format!("+{:.4}/-{:.4}", proposal.current_plus, proposal.current_minus));
+                            ui.strong(format!("+{:.4}/-{:.4}", proposal.plus_tolerance, proposal.minus_tolerance));
+                            ui.label(format!("{:.3}", proposal.cost));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(8.0);
+                if ui.button("Apply to features").clicked() {
+                    apply_clicked = true;
+                }
+            });
+        }
+    });
+
+    if preview_clicked {
+        match analysis.allocate_tolerances(&state.project.components, &allocation.settings, state.project.file.units) {
+            Ok(result) => allocation.preview = Some(result),
+            Err(e) => {
+                allocation.preview = None;
+                state.error_message = Some(format!("{:#}", e));
+            },
+        }
+    } else if apply_clicked {
+        if let Some(result) = allocation.preview.take() {
+            result.apply(&mut state.project.components);
+            if let Some(analysis) = state.project.analyses.get_mut(analysis_index) {
+                analysis.allocation = Some(allocation.settings.clone());
+            }
+            if let Err(e) = state.save_project() {
+                state.error_message = Some(e.to_string());
+            }
+            state.run_analysis(analysis_index);
+        }
+    }
+}
+
+/// An axis over `parameter` of a feature, spanning half to one and a half
+/// times its current value, or the tolerance zone for the nominal
+fn default_study_axis(feature: &Feature, component_id: &str, feature_id: &str, parameter: StudyParameter) -> StudyAxis {
+    let tolerance = feature.dimension.bilateral_tolerance();
+    let value = Some(parameter.value(feature))
+        .filter(|value| value.is_finite())
+        .unwrap_or(tolerance / 3.0);
+    let (start, end) = match parameter {
+        StudyParameter::Nominal => (value - tolerance, value + tolerance),
+        _ => (value * 0.5, value * 1.5),
+    };
+
+    StudyAxis {
+        component_id: component_id.to_string(),
+        feature_id: feature_id.to_string(),
+        parameter,
+        start,
+        end,
+        steps: 11,
+    }
+}
+
+/// Blue through yellow to red for `t` in [0, 1]
+fn heat_color(t: f64) -> egui::Color32 {
+    let lerp = |a: u8, b: u8, t: f64| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        let t = t * 2.0;
+        egui::Color32::from_rgb(lerp(50, 240, t), lerp(100, 220, t), lerp(220, 60, t))
+    } else {
+        let t = (t - 0.5) * 2.0;
+        egui::Color32::from_rgb(lerp(240, 220, t), lerp(220, 50, t), lerp(60, 50, t))
+    }
+}
+
+fn show_parameter_study(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    analysis: &StackupAnalysis,
+    analysis_index: usize,
+) {
+    let mut features: Vec<(String, String)> = Vec::new();
+    for contrib in &analysis.contributions {
+        let key = (contrib.component_id.clone(), contrib.feature_id.clone());
+        if !features.contains(&key) {
+            features.push(key);
+        }
+    }
+    let components = &state.project.components;
+    let axis_for = |(component_id, feature_id): &(String, String), parameter| {
+        find_feature(components, component_id, feature_id)
+            .map(|feature| default_study_axis(feature, component_id, feature_id, parameter))
+    };
+
+    // Start from the axes saved with the analysis
+    if state.study.as_ref().is_none_or(|s| s.analysis_id != analysis.id) {
+        if let Some(job) = state.study.as_ref().and_then(|s| s.job.as_ref()) {
+            job.progress.cancel();
+        }
+        let settings = analysis.study.clone().unwrap_or_else(|| StudySettings {
+            axes: features.first().and_then(|key| axis_for(key, StudyParameter::PlusTolerance)).into_iter().collect(),
+        });
+        state.study = Some(StudyState {
+            analysis_id: analysis.id.clone(),
+            settings,
+            job: None,
+            result: None,
+            metric: StudyMetric::Max(AnalysisMethod::WorstCase),
+        });
+    }
+    let Some(study) = state.study.as_mut() else { return };
+
+    let mut run_clicked = false;
+    let mut export_clicked = false;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.group(|ui| {
+            ui.heading("Parameter Study");
+            ui.label("Runs worst case, RSS and Monte Carlo, as selected for the analysis, at every point of the grid");
+
+            let mut changed = None;
+            let mut remove = None;
+            egui::Grid::new("study_axes")
+                .num_columns(6)
+                .spacing([16.0, 6.0])
+                .show(ui, |ui| {
+                    ui.strong("Feature");
+                    ui.strong("Parameter");
+                    ui.strong("Start");
+                    ui.strong("End");
+                    ui.strong("Steps");
+                    ui.label("");
+                    ui.end_row();
+
+                    for (i, axis) in study.settings.axes.iter_mut().enumerate() {
+                        let before = (axis.component_id.clone(), axis.feature_id.clone(), axis.parameter);
+
+                        egui::ComboBox::from_id_source(("study_feature", i))
+                            .selected_text(format!("{}.{}", axis.component_id, axis.feature_id))
+                            .show_ui(ui, |ui| {
+                                for (component_id, feature_id) in &features {
+                                    let selected = axis.component_id == *component_id && axis.feature_id == *feature_id;
+                                    if ui.selectable_label(selected, format!("{}.{}", component_id, feature_id)).clicked() {
+                                        axis.component_id = component_id.clone();
+                                        axis.feature_id = feature_id.clone();
+                                    }
+                                }
+                            });
+                        egui::ComboBox::from_id_source(("study_parameter", i))
+                            .selected_text(axis.parameter.label())
+                            .show_ui(ui, |ui| {
+                                for parameter in StudyParameter::ALL {
+                                    ui.selectable_value(&mut axis.parameter, parameter, parameter.label());
+                                }
+                            });
+                        ui.add(egui::DragValue::new(&mut axis.start).speed(0.001));
+                        ui.add(egui::DragValue::new(&mut axis.end).speed(0.001));
+                        ui.add(egui::DragValue::new(&mut axis.steps).clamp_range(1..=100));
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+
+                        if before != (axis.component_id.clone(), axis.feature_id.clone(), axis.parameter) {
+                            changed = Some(i);
+                        }
+                    }
+                });
+
+            // A new feature or parameter starts from a range about its current value
+            if let Some(i) = changed {
+                let axis = &study.settings.axes[i];
+                let key = (axis.component_id.clone(), axis.feature_id.clone());
+                if let Some(axis) = axis_for(&key, axis.parameter) {
+                    study.settings.axes[i] = axis;
+                }
+            }
+            if let Some(i) = remove {
+                study.settings.axes.remove(i);
+            }
+
+            let can_add = study.settings.axes.len() < 2 && !features.is_empty();
+            if ui.add_enabled(can_add, egui::Button::new("➕ Add Parameter")).clicked() {
+                if let Some(axis) = features.first().and_then(|key| axis_for(key, StudyParameter::Nominal)) {
+                    study.settings.axes.push(axis);
+                }
+            }
+
+            let count: usize = study.settings.axes.iter().map(|axis| axis.steps).product();
+            ui.label(format!("{} points, each a full analysis", count));
+
+            ui.add_space(8.0);
+            match &study.job {
+                Some(job) => {
+                    let progress = &job.progress;
+                    ui.horizontal(|ui| {
+                        let text = if progress.is_cancelled() {
+                            "Cancelling...".to_string()
+                        } else {
+                            format!("{} / {} points", progress.completed(), progress.total())
+                        };
+                        ui.add(egui::ProgressBar::new(progress.fraction())
+                            .desired_width(240.0)
+                            .text(text));
+                        if ui.add_enabled(!progress.is_cancelled(), egui::Button::new("Cancel")).clicked() {
+                            progress.cancel();
+                        }
+                    });
+                },
+                None => {
+                    let can_run = !study.settings.axes.is_empty();
+                    if ui.add_enabled(can_run, egui::Button::new("▶ Run Study")).clicked() {
+                        run_clicked = true;
+                    }
+                },
+            }
+        });
+
+        if let Some(result) = &study.result {
+            ui.add_space(8.0);
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Study Results");
+                    if ui.button("Export CSV").clicked() {
+                        export_clicked = true;
+                    }
+                });
+                if result.cancelled {
+                    ui.colored_label(egui::Color32::YELLOW, format!("Cancelled after {} points", result.points.len()));
+                }
+
+                let metrics = result.metrics();
+                if !metrics.contains(&study.metric) {
+                    if let Some(metric) = metrics.first() {
+                        study.metric = *metric;
+                    }
+                }
+                egui::ComboBox::from_label("Plot")
+                    .selected_text(study.metric.label())
+                    .show_ui(ui, |ui| {
+                        for metric in &metrics {
+                            ui.selectable_value(&mut study.metric, *metric, metric.label());
+                        }
+                    });
+
+                match result.axes.len() {
+                    1 => show_study_line(ui, result, study.metric, analysis),
+                    _ => show_study_heat_map(ui, result, study.metric),
+                }
+
+                ui.add_space(8.0);
+                show_study_table(ui, result, &metrics);
+            });
+        }
+    });
+
+    if run_clicked {
+        if let Some(analysis) = state.project.analyses.get_mut(analysis_index) {
+            analysis.study = Some(study.settings.clone());
+        }
+        if let Err(e) = state.save_project() {
+            state.error_message = Some(e.to_string());
+        }
+        if let Some(study) = state.study.as_mut() {
+            study.job = Some(StudyJob::spawn_study(
+                analysis.clone(),
+                state.project.components.clone(),
+                study.settings.clone(),
+                state.project.file.units,
+            ));
+        }
+    } else if export_clicked {
+        let file_dialog = rfd::FileDialog::new()
+            .add_filter("CSV files", &["csv"])
+            .set_file_name(format!("{}_study.csv", analysis.name))
+            .set_title("Export Study");
+
+        if let (Some(path), Some(result)) = (file_dialog.save_file(), &study.result) {
+            let written = std::fs::File::create(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| result.write_csv(file));
+            if let Err(e) = written {
+                state.error_message = Some(format!("{:#}", e));
+            }
+        }
+    }
+}
+
+/// The metric along the single axis; both ends of the range for a min or max
+fn show_study_line(ui: &mut egui::Ui, result: &StudyResult, metric: StudyMetric, analysis: &StackupAnalysis) {
+    let series = match metric {
+        StudyMetric::Min(method) | StudyMetric::Max(method) => vec![StudyMetric::Min(method), StudyMetric::Max(method)],
+        metric => vec![metric],
+    };
+    let range = series.len() == 2;
+
+    ui.label(result.axes[0].label());
+    Plot::new("study_line")
+        .height(260.0)
+        .legend(egui_plot::Legend::default())
+        .show(ui, |plot_ui| {
+            for metric in series {
+                let points: Vec<[f64; 2]> = result.points.iter()
+                    .filter_map(|point| point.metric(metric).map(|value| [point.values[0], value]))
+                    .collect();
+                plot_ui.line(Line::new(points).name(metric.label()).width(2.0));
+            }
+
+            if range {
+                for (name, limit) in [("USL", analysis.upper_spec_limit), ("LSL", analysis.lower_spec_limit)] {
+                    if let Some(limit) = limit {
+                        plot_ui.hline(egui_plot::HLine::new(limit).name(name).color(egui::Color32::RED));
+                    }
+                }
+            }
+        });
+}
+
+/// The metric over both axes, one colored cell per point
+fn show_study_heat_map(ui: &mut egui::Ui, result: &StudyResult, metric: StudyMetric) {
+    let cells: Vec<(f64, f64, f64)> = result.points.iter()
+        .filter_map(|point| point.metric(metric).map(|value| (point.values[0], point.values[1], value)))
+        .filter(|(_, _, value)| value.is_finite())
+        .collect();
+    let low = cells.iter().map(|c| c.2).fold(f64::INFINITY, f64::min);
+    let high = cells.iter().map(|c| c.2).fold(f64::NEG_INFINITY, f64::max);
+
+    // Half a step each way, or a sliver for an axis with one value
+    let half_step = |axis: &StudyAxis| match axis.steps {
+        0 | 1 => (axis.start.abs() * 0.05).max(1e-6),
+        steps => ((axis.end - axis.start) / (steps - 1) as f64 / 2.0).abs().max(1e-9),
+    };
+    let (hx, hy) = (half_step(&result.axes[0]), half_step(&result.axes[1]));
+
+    ui.label(format!("{} (x) against {} (y)", result.axes[0].label(), result.axes[1].label()));
+    let hover_cells = cells.clone();
+    Plot::new("study_heat_map")
+        .height(320.0)
+        .label_formatter(move |_, point| {
+            hover_cells.iter()
+                .find(|(x, y, _)| (point.x - x).abs() <= hx && (point.y - y).abs() <= hy)
+                .map(|(x, y, value)| format!("x: {:.4}\ny: {:.4}\n{:.4}", x, y, value))
+                .unwrap_or_default()
+        })
+        .show(ui, |plot_ui| {
+            for (x, y, value) in &cells {
+                let t = if high > low { (value - low) / (high - low) } else { 0.5 };
+                let corners = vec![
+                    [x - hx, y - hy],
+                    [x + hx, y - hy],
+                    [x + hx, y + hy],
+                    [x - hx, y + hy],
+                ];
+                plot_ui.polygon(egui_plot::Polygon::new(corners)
+                    .fill_color(heat_color(t))
+                    .stroke(egui::Stroke::NONE));
+            }
+        });
+
+    if !cells.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label(metric.label());
+            ui.colored_label(heat_color(0.0), format!("■ {:.4}", low));
+            ui.colored_label(heat_color(0.5), format!("■ {:.4}", (low + high) / 2.0));
+            ui.colored_label(heat_color(1.0), format!("■ {:.4}", high));
+        });
+    }
+}
+
+fn show_study_table(ui: &mut egui::Ui, result: &StudyResult, metrics: &[StudyMetric]) {
+    egui::ScrollArea::vertical()
+        .id_source("study_table")
+        .max_height(240.0)
+        .show(ui, |ui| {
+            egui::Grid::new("study_points")
+                .num_columns(result.axes.len() + metrics.len())
+                .spacing([16.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for axis in &result.axes {
+                        ui.strong(axis.label());
+                    }
+                    for metric in metrics {
+                        ui.strong(metric.label());
+                    }
+                    ui.end_row();
+
+                    for point in &result.points {
+                        for value in &point.values {
+                            ui.label(format!("{:.4}", value));
+                        }
+                        for metric in metrics {
+                            ui.label(point.metric(*metric)
+                                .map(|value| format!("{:.4}", value))
+                                .unwrap_or_else(|| "—".to_string()));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn show_designed_experiment(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    analysis: &StackupAnalysis,
+    analysis_index: usize,
+) {
+    let mut features: Vec<(String, String)> = Vec::new();
+    for contrib in &analysis.contributions {
+        let key = (contrib.component_id.clone(), contrib.feature_id.clone());
+        if !features.contains(&key) {
+            features.push(key);
+        }
+    }
+    let components = &state.project.components;
+    let factor_for = |(component_id, feature_id): &(String, String), parameter| {
+        find_feature(components, component_id, feature_id).map(|feature| {
+            let axis = default_study_axis(feature, component_id, feature_id, parameter);
+            DoeFactor {
+                component_id: axis.component_id,
+                feature_id: axis.feature_id,
+                parameter,
+                low: axis.start,
+                high: axis.end,
+            }
+        })
+    };
+
+    // Start from the design saved with the analysis
+    if state.doe.as_ref().is_none_or(|d| d.analysis_id != analysis.id) {
+        if let Some(job) = state.doe.as_ref().and_then(|d| d.job.as_ref()) {
+            job.progress.cancel();
+        }
+        let settings = analysis.doe.clone().unwrap_or_else(|| DoeSettings {
+            factors: features.iter().filter_map(|key| factor_for(key, StudyParameter::Nominal)).take(3).collect(),
+            ..Default::default()
+        });
+        state.doe = Some(DoeState {
+            analysis_id: analysis.id.clone(),
+            settings,
+            job: None,
+            result: None,
+        });
+    }
+    let Some(doe) = state.doe.as_mut() else { return };
+
+    let mut run_clicked = false;
+    let mut apply_clicked = false;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.group(|ui| {
+            ui.heading("Designed Experiment");
+            ui.label("Fits response surfaces to the stack mean and variance, then looks for the nominals \
+                that hold the mean on target with the least variance");
+
+            let mut changed = None;
+            let mut remove = None;
+            egui::Grid::new("doe_factors")
+                .num_columns(5)
+                .spacing([16.0, 6.0])
+                .show(ui, |ui| {
+                    ui.strong("Factor");
+                    ui.strong("Parameter");
+                    ui.strong("Low (-1)");
+                    ui.strong("High (+1)");
+                    ui.label("");
+                    ui.end_row();
+
+                    for (i, factor) in doe.settings.factors.iter_mut().enumerate() {
+                        let before = (factor.component_id.clone(), factor.feature_id.clone(), factor.parameter);
+
+                        egui::ComboBox::from_id_source(("doe_feature", i))
+                            .selected_text(format!("{}.{}", factor.component_id, factor.feature_id))
+                            .show_ui(ui, |ui| {
+                                for (component_id, feature_id) in &features {
+                                    let selected = factor.component_id == *component_id && factor.feature_id == *feature_id;
+                                    if ui.selectable_label(selected, format!("{}.{}", component_id, feature_id)).clicked() {
+                                        factor.component_id = component_id.clone();
+                                        factor.feature_id = feature_id.clone();
+                                    }
+                                }
+                            });
+                        egui::ComboBox::from_id_source(("doe_parameter", i))
+                            .selected_text(factor.parameter.label())
+                            .show_ui(ui, |ui| {
+                                for parameter in StudyParameter::ALL {
+                                    ui.selectable_value(&mut factor.parameter, parameter, parameter.label());
+                                }
+                            });
+                        ui.add(egui::DragValue::new(&mut factor.low).speed(0.001));
+                        ui.add(egui::DragValue::new(&mut factor.high).speed(0.001));
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+
+                        if before != (factor.component_id.clone(), factor.feature_id.clone(), factor.parameter) {
+                            changed = Some(i);
+                        }
+                    }
+                });
+
+            // A new feature or parameter starts from levels about its current value
+            if let Some(i) = changed {
+                let factor = &doe.settings.factors[i];
+                let key = (factor.component_id.clone(), factor.feature_id.clone());
+                if let Some(factor) = factor_for(&key, factor.parameter) {
+                    doe.settings.factors[i] = factor;
+                }
+            }
+            if let Some(i) = remove {
+                doe.settings.factors.remove(i);
+            }
+
+            if ui.add_enabled(!features.is_empty(), egui::Button::new("➕ Add Factor")).clicked() {
+                let unused = features.iter()
+                    .find(|(c, f)| !doe.settings.factors.iter().any(|factor| factor.component_id == *c && factor.feature_id == *f))
+                    .or(features.first());
+                if let Some(factor) = unused.and_then(|key| factor_for(key, StudyParameter::Nominal)) {
+                    doe.settings.factors.push(factor);
+                }
+            }
+
+            ui.add_space(8.0);
+            let k = doe.settings.factors.len();
+            egui::Grid::new("doe_design")
+                .num_columns(2)
+                .spacing([16.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Design:");
+                    ui.horizontal(|ui| {
+                        let generators = match doe.settings.design {
+                            DesignType::FractionalFactorial { generators } => generators,
+                            _ => 1,
+                        };
+                        egui::ComboBox::from_id_source("doe_design_type")
+                            .selected_text(doe.settings.design.label())
+                            .show_ui(ui, |ui| {
+                                for design in [
+                                    DesignType::FullFactorial,
+                                    DesignType::FractionalFactorial { generators },
+                                    DesignType::CentralComposite { alpha: CcdAlpha::FaceCentered },
+                                    DesignType::CentralComposite { alpha: CcdAlpha::Rotatable },
+                                ] {
+                                    ui.selectable_value(&mut doe.settings.design, design, design.label());
+                                }
+                            });
+                        if let DesignType::FractionalFactorial { generators } = &mut doe.settings.design {
+                            ui.label("Generated factors:");
+                            ui.add(egui::DragValue::new(generators).clamp_range(1..=k.saturating_sub(2).max(1)));
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Center points:");
+                    ui.add(egui::DragValue::new(&mut doe.settings.center_points).clamp_range(0..=10));
+                    ui.end_row();
+
+                    ui.label("Mean target:");
+                    ui.horizontal(|ui| {
+                        let mut set = doe.settings.target.is_some();
+                        if ui.checkbox(&mut set, "").changed() {
+                            let middle = analysis.upper_spec_limit.zip(analysis.lower_spec_limit)
+                                .map(|(usl, lsl)| (usl + lsl) / 2.0);
+                            let mean = analysis.calculate_mean(components).ok();
+                            doe.settings.target = set.then(|| middle.or(mean).unwrap_or(0.0));
+                        }
+                        match &mut doe.settings.target {
+                            Some(target) => {
+                                ui.add(egui::DragValue::new(target).speed(0.001));
+                            },
+                            None => {
+                                ui.label("Middle of the spec limits, or the current mean");
+                            },
+                        }
+                    });
+                    ui.end_row();
+                });
+
+            let runs = match doe.settings.design {
+                DesignType::FullFactorial => 1usize << k.min(20),
+                DesignType::FractionalFactorial { generators } => 1usize << k.saturating_sub(generators).min(20),
+                DesignType::CentralComposite { .. } => (1usize << k.min(20)) + 2 * k,
+            } + doe.settings.center_points;
+            ui.label(format!("{} runs, each a full analysis", runs));
+
+            ui.add_space(8.0);
+            match &doe.job {
+                Some(job) => {
+                    let progress = &job.progress;
+                    ui.horizontal(|ui| {
+                        let text = if progress.is_cancelled() {
+                            "Cancelling...".to_string()
+                        } else {
+                            format!("{} / {} runs", progress.completed(), progress.total())
+                        };
+                        ui.add(egui::ProgressBar::new(progress.fraction())
+                            .desired_width(240.0)
+                            .text(text));
+                        if ui.add_enabled(!progress.is_cancelled(), egui::Button::new("Cancel")).clicked() {
+                            progress.cancel();
+                        }
+                    });
+                },
+                None => {
+                    if ui.add_enabled(k > 0, egui::Button::new("▶ Run Design")).clicked() {
+                        run_clicked = true;
+                    }
+                },
+            }
+        });
+
+        if let Some(result) = &doe.result {
+            ui.add_space(8.0);
+            show_doe_result(ui, result, components, &mut apply_clicked);
+        }
+    });
+
+    if run_clicked {
+        if let Some(analysis) = state.project.analyses.get_mut(analysis_index) {
+            analysis.doe = Some(doe.settings.clone());
+        }
+        if let Err(e) = state.save_project() {
+            state.error_message = Some(e.to_string());
+        }
+        if let Some(doe) = state.doe.as_mut() {
+            doe.job = Some(DoeJob::spawn_doe(
+                analysis.clone(),
+                state.project.components.clone(),
+                doe.settings.clone(),
+                state.project.file.units,
+            ));
+        }
+    } else if apply_clicked {
+        let Some(result) = &doe.result else { return };
+        let Some(robust) = &result.recommendation else { return };
+
+        let nominals = result.factors.iter().zip(&robust.values)
+            .filter(|(factor, _)| factor.parameter == StudyParameter::Nominal);
+        for (factor, value) in nominals {
+            let set = set_parameter(
+                &mut state.project.components,
+                &factor.component_id,
+                &factor.feature_id,
+                factor.parameter,
+                *value,
+                state.project.file.units,
+            );
+            if let Err(e) = set {
+                state.error_message = Some(format!("{:#}", e));
+            }
+        }
+        if let Err(e) = state.save_project() {
+            state.error_message = Some(e.to_string());
+        }
+        state.run_analysis(analysis_index);
+    }
+}
+
+fn show_doe_result(ui: &mut egui::Ui, result: &DoeResult, components: &[Component], apply_clicked: &mut bool) {
+    let names = result.factor_labels();
+
+    ui.group(|ui| {
+        ui.heading("Runs");
+        if result.cancelled {
+            ui.colored_label(egui::Color32::YELLOW, format!("Cancelled after {} runs", result.runs.len()));
+        }
+        egui::ScrollArea::vertical()
+            .id_source("doe_runs")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("doe_run_table")
+                    .num_columns(names.len() + 3)
+                    .spacing([16.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Run");
+                        for name in &names {
+                            ui.strong(name);
+                        }
+                        ui.strong("Mean");
+                        ui.strong("Std dev");
+                        ui.end_row();
+
+                        for (i, run) in result.runs.iter().enumerate() {
+                            ui.label(format!("{}", i + 1));
+                            for (value, level) in run.actual.iter().zip(&run.coded) {
+                                ui.label(format!("{:.4} ({:+.2})", value, level));
+                            }
+                            ui.label(format!("{:.4}", run.mean));
+                            ui.label(format!("{:.4}", run.std_dev));
+                            ui.end_row();
+                        }
+                    });
+            });
+    });
+
+    ui.add_space(8.0);
+    ui.group(|ui| {
+        ui.heading("Response Surfaces");
+        ui.label(format!(
+            "R²: mean {:.4}, variance {:.4}. Coefficients are per coded unit.",
+            result.mean_model.r_squared, result.variance_model.r_squared
+        ));
+        egui::Grid::new("doe_models")
+            .num_columns(3)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Term");
+                ui.strong("Mean");
+                ui.strong("Variance");
+                ui.end_row();
+
+                // Both models drop the same aliased terms, so the terms line up
+                for ((term, mean), variance) in result.mean_model.terms.iter()
+                    .zip(&result.mean_model.coefficients)
+                    .zip(&result.variance_model.coefficients)
+                {
+                    ui.label(term.label(&names));
+                    ui.label(format!("{:.6}", mean));
+                    ui.label(format!("{:.6e}", variance));
+                    ui.end_row();
+                }
+            });
+        if !result.mean_model.aliased.is_empty() {
+            let aliased: Vec<String> = result.mean_model.aliased.iter().map(|term| term.label(&names)).collect();
+            ui.label(format!("Aliased with earlier terms, left out: {}", aliased.join(", ")));
+        }
+    });
+
+    ui.add_space(8.0);
+    ui.group(|ui| {
+        ui.heading("Robust Design");
+        let Some(robust) = &result.recommendation else {
+            if result.cancelled {
+                ui.label("Run the whole design to get recommended settings");
+            } else {
+                ui.label("Add a nominal as a factor to get recommended settings");
+            }
+            return;
+        };
+
+        ui.label(format!("Target mean: {:.4}", result.target));
+        if !robust.on_target {
+            ui.colored_label(egui::Color32::YELLOW, "The target cannot be reached within the factor levels; closest setting shown");
+        }
+        egui::Grid::new("doe_robust")
+            .num_columns(3)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Factor");
+                ui.strong("Current");
+                ui.strong("Recommended");
+                ui.end_row();
+
+                for ((factor, name), value) in result.factors.iter().zip(&names).zip(&robust.values) {
+                    let current = find_feature(components, &factor.component_id, &factor.feature_id)
+                        .map(|feature| factor.parameter.value(feature));
+                    ui.label(name);
+                    ui.label(current.map(|v| format!("{:.4}", v)).unwrap_or_default());
+                    if factor.parameter == StudyParameter::Nominal {
+                        ui.strong(format!("{:.4}", value));
+                    } else {
+                        ui.label("held");
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.label(format!(
+            "Predicted mean {:.4}, std dev {:.4}",
+            robust.predicted_mean, robust.predicted_std_dev
+        ));
+
+        ui.add_space(8.0);
+        if ui.button("Apply nominals").clicked() {
+            *apply_clicked = true;
+        }
+    });
+}
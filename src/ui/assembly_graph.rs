@@ -0,0 +1,420 @@
+// src/ui/assembly_graph.rs
+use std::collections::HashMap;
+use eframe::egui;
+use crate::state::{AppState, GraphColor, Screen};
+use crate::state::mate_state::MateFilter;
+use crate::ui::dependencies::fit_color;
+use atlas_core::analysis::layout::force_layout_step;
+use atlas_core::utils::find_feature;
+
+/// Distance the force layout keeps between mated components, in points at
+/// zoom 1
+const SPACING: f64 = 140.0;
+/// Force layout steps after the components change or a node is moved
+const LAYOUT_STEPS: usize = 300;
+const STEPS_PER_FRAME: usize = 5;
+/// Largest move of a node in one step, at the start of the layout
+const MAX_STEP: f64 = 25.0;
+const NODE_RADIUS: f32 = 16.0;
+/// Gap between parallel edges of components with several mates
+const EDGE_GAP: f32 = 6.0;
+/// How close the pointer must come to an edge to pick it
+const EDGE_PICK: f32 = 5.0;
+
+const VALID_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 180, 90);
+const INVALID_COLOR: egui::Color32 = egui::Color32::from_rgb(210, 80, 80);
+
+/// A mate between two components, drawn offset from the line between them
+/// when the components share several mates
+struct GraphEdge {
+    /// Index of the mate in the project
+    mate: usize,
+    /// Indices of the two components
+    ends: (usize, usize),
+    offset: f32,
+    color: egui::Color32,
+    is_valid: bool,
+    label: String,
+    details: Vec<String>,
+}
+
+/// Mates between different components that pass the mate filter
+fn collect_edges(state: &AppState) -> Vec<GraphEdge> {
+    let components = &state.project.components;
+    let index_of = |name: &str| components.iter().position(|c| c.name == name);
+    let shown: Vec<&str> = state.mate_state.filtered_mates().iter().map(|mate| mate.id.as_str()).collect();
+
+    let mut edges = Vec::new();
+    let mut per_pair: HashMap<(usize, usize), usize> = HashMap::new();
+    for (index, mate) in state.project.mates.iter().enumerate() {
+        if !shown.contains(&mate.id.as_str()) {
+            continue;
+        }
+        let (Some(a), Some(b)) = (index_of(&mate.component_a), index_of(&mate.component_b)) else {
+            continue;
+        };
+        // Mates within one component are not shown
+        if a == b {
+            continue;
+        }
+
+        let validation = match (
+            find_feature(components, &mate.component_a, &mate.feature_a),
+            find_feature(components, &mate.component_b, &mate.feature_b),
+        ) {
+            (Some(feature_a), Some(feature_b)) => Some(mate.validate(feature_a, feature_b)),
+            _ => None,
+        };
+        let is_valid = validation.as_ref().is_some_and(|v| v.is_valid);
+        let mut details = vec![format!(
+            "{}{} fit",
+            mate.fit.map(|fit| format!("{} ", fit)).unwrap_or_default(),
+            mate.fit_type
+        )];
+        match &validation {
+            Some(v) => {
+                details.push(format!("Fit: {:.3} (min {:.3}, max {:.3})", v.nominal_fit, v.min_fit, v.max_fit));
+                if let Some(error) = &v.error_message {
+                    details.push(error.clone());
+                }
+            },
+            None => details.push("Missing features".to_string()),
+        }
+
+        let color = match state.assembly_graph.color {
+            GraphColor::FitType => fit_color(&mate.fit_type.to_string()),
+            GraphColor::Validity if is_valid => VALID_COLOR,
+            GraphColor::Validity => INVALID_COLOR,
+        };
+
+        let count = per_pair.entry((a.min(b), a.max(b))).or_insert(0);
+        *count += 1;
+        edges.push(GraphEdge {
+            mate: index,
+            ends: (a, b),
+            offset: *count as f32,
+            color,
+            is_valid,
+            label: format!("{}.{} ↔ {}.{}", mate.component_a, mate.feature_a, mate.component_b, mate.feature_b),
+            details,
+        });
+    }
+
+    // Spread the mates of each pair evenly either side of the line between
+    // the components
+    for edge in &mut edges {
+        let count = per_pair[&(edge.ends.0.min(edge.ends.1), edge.ends.0.max(edge.ends.1))] as f32;
+        edge.offset = (edge.offset - 1.0 - (count - 1.0) / 2.0) * EDGE_GAP;
+    }
+    edges
+}
+
+/// Bring the working positions in line with the components: positions in
+/// the project's layout win, and components without one are placed on a
+/// circle for the force layout to settle
+fn sync_positions(state: &mut AppState) {
+    let components = &state.project.components;
+    let graph = &mut state.assembly_graph;
+    graph.positions.retain(|name, _| components.iter().any(|c| &c.name == name));
+
+    let radius = (SPACING * components.len() as f64 / std::f64::consts::TAU).max(SPACING);
+    for (index, component) in components.iter().enumerate() {
+        if let Some(saved) = state.project.file.graph_layout.get(&component.name) {
+            if graph.dragging.as_ref() != Some(&component.name) {
+                graph.positions.insert(component.name.clone(), *saved);
+            }
+        } else if !graph.positions.contains_key(&component.name) {
+            let angle = std::f64::consts::TAU * index as f64 / components.len() as f64;
+            graph.positions.insert(component.name.clone(), [radius * angle.cos(), radius * angle.sin()]);
+            graph.steps_left = LAYOUT_STEPS;
+        }
+    }
+}
+
+/// Run this frame's share of the force layout over every mate, whatever the
+/// filter, so filtering does not move the nodes
+fn step_layout(state: &mut AppState) {
+    let graph = &mut state.assembly_graph;
+    if graph.steps_left == 0 {
+        return;
+    }
+
+    let components = &state.project.components;
+    let index_of = |name: &str| components.iter().position(|c| c.name == name);
+    let edges: Vec<(usize, usize)> = state.project.mates.iter()
+        .filter_map(|mate| Some((index_of(&mate.component_a)?, index_of(&mate.component_b)?)))
+        .collect();
+    let pinned: Vec<bool> = components.iter()
+        .map(|c| state.project.file.graph_layout.contains_key(&c.name) || graph.dragging.as_ref() == Some(&c.name))
+        .collect();
+    let mut positions: Vec<[f64; 2]> = components.iter()
+        .map(|c| graph.positions.get(&c.name).copied().unwrap_or_default())
+        .collect();
+
+    let mut moved = 0.0;
+    for _ in 0..STEPS_PER_FRAME.min(graph.steps_left) {
+        let step = MAX_STEP * graph.steps_left as f64 / LAYOUT_STEPS as f64 + 1.0;
+        moved = force_layout_step(&mut positions, &edges, &pinned, SPACING, step);
+        graph.steps_left -= 1;
+    }
+    if moved < 0.1 {
+        graph.steps_left = 0;
+    }
+
+    for (component, position) in components.iter().zip(positions) {
+        graph.positions.insert(component.name.clone(), position);
+    }
+}
+
+/// Keep the current positions as the project's layout and save the project
+/// when it has a directory
+fn save_layout(state: &mut AppState) {
+    state.project.file.graph_layout = state.assembly_graph.positions.iter()
+        .map(|(name, position)| (name.clone(), *position))
+        .collect();
+    if state.project.dir.is_some() {
+        if let Err(e) = state.save_project() {
+            state.error_message = Some(e.to_string());
+        }
+    }
+}
+
+fn distance_to_segment(point: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let along = b - a;
+    let t = if along.length_sq() > 0.0 {
+        ((point - a).dot(along) / along.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(a + along * t)
+}
+
+pub fn show_assembly_graph(ui: &mut egui::Ui, state: &mut AppState) {
+    sync_positions(state);
+    step_layout(state);
+    if state.assembly_graph.steps_left > 0 {
+        ui.ctx().request_repaint();
+    }
+
+    ui.heading("Assembly Graph");
+    ui.add_space(4.0);
+    show_graph_options(ui, state);
+    show_legend(ui, state.assembly_graph.color);
+    ui.label("Drag components to arrange them; click a component or mate to open it");
+    ui.add_space(4.0);
+
+    if state.project.components.is_empty() {
+        ui.label("No components yet");
+        return;
+    }
+
+    let edges = collect_edges(state);
+    let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::click_and_drag());
+    let rect = response.rect;
+    let visuals = ui.visuals().clone();
+    painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+
+    if response.hovered() {
+        let zoom = ui.input(|i| i.zoom_delta());
+        if zoom != 1.0 {
+            state.assembly_graph.zoom = (state.assembly_graph.zoom * zoom).clamp(0.2, 5.0);
+        }
+    }
+
+    let graph = &state.assembly_graph;
+    let origin = rect.center() + egui::vec2(graph.pan[0], graph.pan[1]);
+    let zoom = graph.zoom;
+    let centers: Vec<egui::Pos2> = state.project.components.iter()
+        .map(|c| {
+            let [x, y] = graph.positions.get(&c.name).copied().unwrap_or_default();
+            origin + egui::vec2(x as f32, y as f32) * zoom
+        })
+        .collect();
+    let edge_ends = |edge: &GraphEdge| {
+        let (a, b) = (centers[edge.ends.0], centers[edge.ends.1]);
+        // Offset to the same side whichever way round the mate was made
+        let normal = if edge.ends.0 < edge.ends.1 { (b - a).normalized().rot90() } else { (a - b).normalized().rot90() };
+        (a + normal * edge.offset, b + normal * edge.offset)
+    };
+    let node_at = |pos: egui::Pos2| centers.iter().position(|center| center.distance(pos) <= NODE_RADIUS + 2.0);
+    let edge_at = |pos: egui::Pos2| edges.iter()
+        .map(|edge| {
+            let (a, b) = edge_ends(edge);
+            (edge, distance_to_segment(pos, a, b))
+        })
+        .filter(|(_, distance)| *distance <= EDGE_PICK)
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .map(|(edge, _)| edge);
+
+    let hover_pos = response.hover_pos();
+    let hovered_node = hover_pos.and_then(node_at);
+    let hovered_edge = if hovered_node.is_none() { hover_pos.and_then(edge_at) } else { None };
+
+    // Components touching a shown mate; the rest are dimmed while filtering
+    let filtering = state.mate_state.filter.is_some();
+    let mut involved = vec![!filtering; centers.len()];
+    for edge in &edges {
+        involved[edge.ends.0] = true;
+        involved[edge.ends.1] = true;
+    }
+
+    for edge in &edges {
+        let (a, b) = edge_ends(edge);
+        let selected = state.selected_mate == Some(edge.mate);
+        let highlighted = selected || hovered_edge.is_some_and(|h| h.mate == edge.mate);
+        let stroke = egui::Stroke::new(if highlighted { 4.0 } else { 2.0 }, edge.color);
+        if edge.is_valid {
+            painter.line_segment([a, b], stroke);
+        } else {
+            painter.extend(egui::Shape::dashed_line(&[a, b], stroke, 8.0, 5.0));
+        }
+    }
+
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    for (index, (component, center)) in state.project.components.iter().zip(&centers).enumerate() {
+        let selected = state.selected_component == Some(index);
+        let fill = if selected {
+            visuals.selection.bg_fill
+        } else if hovered_node == Some(index) {
+            visuals.widgets.hovered.bg_fill
+        } else {
+            visuals.widgets.inactive.bg_fill
+        };
+        let fill = if involved[index] { fill } else { fill.linear_multiply(0.3) };
+        painter.circle(*center, NODE_RADIUS, fill, egui::Stroke::new(1.0, visuals.text_color()));
+        painter.text(
+            *center + egui::vec2(0.0, NODE_RADIUS + 2.0),
+            egui::Align2::CENTER_TOP,
+            &component.name,
+            font.clone(),
+            if involved[index] { visuals.text_color() } else { visuals.weak_text_color() },
+        );
+    }
+
+    if response.drag_started() {
+        let pressed = ui.input(|i| i.pointer.press_origin());
+        state.assembly_graph.dragging = pressed.and_then(node_at)
+            .map(|index| state.project.components[index].name.clone());
+    }
+    if response.dragged() {
+        let delta = response.drag_delta();
+        let graph = &mut state.assembly_graph;
+        match &graph.dragging {
+            Some(name) => {
+                if let Some(position) = graph.positions.get_mut(name) {
+                    position[0] += (delta.x / zoom) as f64;
+                    position[1] += (delta.y / zoom) as f64;
+                }
+                graph.steps_left = LAYOUT_STEPS;
+            },
+            None => {
+                graph.pan[0] += delta.x;
+                graph.pan[1] += delta.y;
+            },
+        }
+    }
+    if response.drag_released() && state.assembly_graph.dragging.take().is_some() {
+        save_layout(state);
+    }
+
+    if response.clicked() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            if let Some(index) = node_at(pos) {
+                state.selected_component = Some(index);
+                state.current_screen = Screen::Components;
+            } else if let Some(edge) = edge_at(pos) {
+                state.selected_mate = Some(edge.mate);
+                state.current_screen = Screen::Mates;
+            }
+        }
+    }
+
+    if let Some(index) = hovered_node {
+        let component = &state.project.components[index];
+        let mates = state.project.mates.iter()
+            .filter(|mate| mate.component_a == component.name || mate.component_b == component.name)
+            .count();
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.strong(&component.name);
+            ui.label(format!("{} feature(s), {} mate(s)", component.features.len(), mates));
+        });
+    } else if let Some(edge) = hovered_edge {
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.strong(&edge.label);
+            for line in &edge.details {
+                ui.label(line);
+            }
+        });
+    }
+}
+
+fn show_graph_options(ui: &mut egui::Ui, state: &mut AppState) {
+    ui.horizontal(|ui| {
+        ui.label("Color by:");
+        let graph = &mut state.assembly_graph;
+        egui::ComboBox::from_id_source("assembly_graph_color")
+            .selected_text(match graph.color {
+                GraphColor::FitType => "Fit type",
+                GraphColor::Validity => "Fit validity",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut graph.color, GraphColor::FitType, "Fit type");
+                ui.selectable_value(&mut graph.color, GraphColor::Validity, "Fit validity");
+            });
+
+        ui.separator();
+        ui.label("Mates of:");
+        let filter_text = match &state.mate_state.filter {
+            Some(MateFilter::Component(name)) => name.clone(),
+            Some(MateFilter::Feature(component, feature)) => format!("{}.{}", component, feature),
+            None => "All components".to_string(),
+        };
+        let mut filter = state.mate_state.filter.clone();
+        egui::ComboBox::from_id_source("assembly_graph_filter")
+            .selected_text(filter_text)
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(filter.is_none(), "All components").clicked() {
+                    filter = None;
+                }
+                for component in &state.project.components {
+                    let selected = matches!(&filter, Some(MateFilter::Component(name)) if *name == component.name);
+                    if ui.selectable_label(selected, &component.name).clicked() {
+                        filter = Some(MateFilter::Component(component.name.clone()));
+                    }
+                }
+            });
+        state.mate_state.filter = filter;
+
+        ui.separator();
+        if ui.button("Reset view").clicked() {
+            state.assembly_graph.pan = [0.0, 0.0];
+            state.assembly_graph.zoom = 1.0;
+        }
+        if ui.button("Re-layout")
+            .on_hover_text("Forget where components were put and lay them all out again")
+            .clicked()
+        {
+            state.project.file.graph_layout.clear();
+            state.assembly_graph.positions.clear();
+            save_layout(state);
+        }
+    });
+}
+
+fn show_legend(ui: &mut egui::Ui, color: GraphColor) {
+    ui.horizontal(|ui| {
+        match color {
+            GraphColor::FitType => {
+                for fit in ["Clearance", "Transition", "Interference"] {
+                    ui.colored_label(fit_color(fit), format!("━ {}", fit));
+                }
+            },
+            GraphColor::Validity => {
+                ui.colored_label(VALID_COLOR, "━ Valid");
+                ui.colored_label(INVALID_COLOR, "━ Invalid");
+            },
+        }
+        ui.separator();
+        ui.label("┅ Invalid fit");
+    });
+}
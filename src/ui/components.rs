@@ -1,7 +1,10 @@
 // src/ui/components.rs
 use eframe::egui;
-use crate::state::{AppState, DialogState, Screen};
-use crate::analysis::stackup::DistributionType;
+use egui_plot::{Line, Plot, Points};
+use crate::state::{AppState, DialogState, FitState, Screen};
+use atlas_core::analysis::stackup::DistributionType;
+use atlas_core::analysis::fitting::{fit_distributions, measured_capability, probability_plot};
+use atlas_core::config::Feature;
 
 pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
     let available_size = ui.available_size();
@@ -33,7 +36,7 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                 egui::ScrollArea::vertical()
                     .id_source("components_list_scroll")
                     .show(ui, |ui| {
-                        let components = state.components.clone(); // Clone components to avoid borrow issues
+                        let components = state.project.components.clone(); // Clone components to avoid borrow issues
                         for (index, component) in components.iter().enumerate() {
                             let is_selected = state.selected_component == Some(index);
                             
@@ -85,11 +88,11 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                                     if delete_clicked {
                                         let state_ptr = state as *mut AppState;
                                         unsafe {
-                                            (*state_ptr).components.remove(index);
-                                            if (*state_ptr).components.is_empty() {
+                                            (*state_ptr).project.components.remove(index);
+                                            if (*state_ptr).project.components.is_empty() {
                                                 (*state_ptr).selected_component = None;
-                                            } else if index >= (*state_ptr).components.len() {
-                                                (*state_ptr).selected_component = Some((*state_ptr).components.len() - 1);
+                                            } else if index >= (*state_ptr).project.components.len() {
+                                                (*state_ptr).selected_component = Some((*state_ptr).project.components.len() - 1);
                                             }
                                             if let Err(e) = (*state_ptr).save_project() {
                                                 (*state_ptr).error_message = Some(e.to_string());
@@ -110,7 +113,7 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                 ui.set_min_height(available_size.y);
 
                 if let Some(selected_idx) = state.selected_component {
-                    if let Some(component) = state.components.get(selected_idx) {
+                    if let Some(component) = state.project.components.get(selected_idx) {
                         let component = component.clone(); // Clone to avoid borrow issues
                         ui.heading(&component.name);
                         if let Some(desc) = &component.description {
@@ -121,15 +124,26 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                         ui.heading("Features");
                         ui.add_space(4.0);
                         
-                        if ui.button("➕ Add Feature").clicked() {
-                            state.current_dialog = DialogState::NewFeature {
-                                component_index: selected_idx,
-                                name: String::new(),
-                                value: 0.0,
-                                plus_tolerance: 0.0,
-                                minus_tolerance: 0.0,
-                            };
-                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("➕ Add Feature").clicked() {
+                                state.current_dialog = DialogState::NewFeature {
+                                    component_index: selected_idx,
+                                    name: String::new(),
+                                    value: 0.0,
+                                    plus_tolerance: 0.0,
+                                    minus_tolerance: 0.0,
+                                };
+                            }
+
+                            if ui.button("📥 Import Measurements...").clicked() {
+                                let file_dialog = rfd::FileDialog::new()
+                                    .add_filter("CSV files", &["csv"])
+                                    .set_title("Import Measurements");
+                                if let Some(path) = file_dialog.pick_file() {
+                                    state.current_dialog = DialogState::ImportMeasurements { path };
+                                }
+                            }
+                        });
 
                         ui.add_space(8.0);
                         ui.separator();
@@ -145,10 +159,11 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                                         ui.set_width(ui.available_width());
                                         
                                         let feature_text = format!(
-                                            "{} ({:?})\n{:.3} [{:+.3}/{:+.3}] {:?}", 
+                                            "{} ({:?})\n{:.3}{} [{:+.3}/{:+.3}] {:?}", 
                                             feature.name, 
                                             feature.feature_type,
                                             feature.dimension.value,
+                                            feature.tolerance_class.map(|c| format!(" {}", c)).unwrap_or_default(),
                                             feature.dimension.plus_tolerance,
                                             feature.dimension.minus_tolerance,
                                             feature.distribution.unwrap_or(DistributionType::Normal)
@@ -191,7 +206,7 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                                             if delete_clicked {
                                                 let state_ptr = state as *mut AppState;
                                                 unsafe {
-                                                    if let Some(component) = (*state_ptr).components.get_mut(selected_idx) {
+                                                    if let Some(component) = (&mut (*state_ptr).project.components).get_mut(selected_idx) {
                                                         component.features.remove(index);
                                                         
                                                         if component.features.is_empty() {
@@ -211,7 +226,7 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
 
                                         // Show related mates if selected
                                         if is_selected {
-                                            let related_mates = state.mates.iter()
+                                            let related_mates = state.project.mates.iter()
                                                 .filter(|m| {
                                                     (m.component_a == component.name && m.feature_a == feature.name) ||
                                                     (m.component_b == component.name && m.feature_b == feature.name)
@@ -238,6 +253,11 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                                                     other_feature
                                                 ));
                                             }
+
+                                            if !feature.measurements.is_empty() {
+                                                ui.add_space(4.0);
+                                                show_measurements(ui, state, selected_idx, &component.name, feature);
+                                            }
                                         }
                                     });
                                     ui.add_space(4.0);
@@ -251,4 +271,111 @@ pub fn show_components_view(ui: &mut egui::Ui, state: &mut AppState) {
                 }
             });
         });
-}
\ No newline at end of file
+}
+
+/// Capability of a feature's measurements against its tolerance, the
+/// distributions fitted to them and a probability plot of one fit
+fn show_measurements(ui: &mut egui::Ui, state: &mut AppState, component_index: usize, component_name: &str, feature: &Feature) {
+    ui.label("Measurements:");
+    match measured_capability(feature) {
+        Some(capability) => {
+            ui.label(format!(
+                "n = {}, mean {:.4}, std dev {:.4}, Cp {:.2}, Cpk {:.2}",
+                capability.count, capability.mean, capability.std_dev, capability.cp, capability.cpk
+            ));
+            if capability.out_of_tolerance > 0 {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    format!("{} of {} out of tolerance", capability.out_of_tolerance, capability.count)
+                );
+            }
+        },
+        None => {
+            ui.label(format!("n = {}", feature.measurements.len()));
+        },
+    }
+
+    // Fitting is too slow to repeat every frame
+    let current = state.measurement_fits.as_ref().is_some_and(|fits| {
+        fits.component_id == component_name
+            && fits.feature_id == feature.name
+            && fits.measurements == feature.measurements
+    });
+    if !current {
+        state.measurement_fits = Some(FitState {
+            component_id: component_name.to_string(),
+            feature_id: feature.name.clone(),
+            measurements: feature.measurements.clone(),
+            fits: fit_distributions(&feature.measurements).map_err(|e| e.to_string()),
+            plotted: 0,
+        });
+    }
+    let Some(fit_state) = state.measurement_fits.as_mut() else { return };
+    let fits = match &fit_state.fits {
+        Ok(fits) => fits.clone(),
+        Err(e) => {
+            ui.colored_label(egui::Color32::YELLOW, e);
+            return;
+        },
+    };
+
+    let mut adopted = None;
+    ui.add_space(4.0);
+    egui::Grid::new("distribution_fits")
+        .num_columns(5)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("Distribution");
+            ui.strong("A²");
+            ui.strong("KS D");
+            ui.strong("KS p");
+            ui.label("");
+            ui.end_row();
+
+            for (i, fit) in fits.iter().enumerate() {
+                ui.radio_value(&mut fit_state.plotted, i, fit.label());
+                ui.label(format!("{:.3}", fit.anderson_darling));
+                ui.label(format!("{:.4}", fit.ks_statistic));
+                ui.label(format!("{:.3}", fit.ks_p_value));
+                if ui.button("Adopt").on_hover_text("Use this distribution for the feature").clicked() {
+                    adopted = Some(fit.params.clone());
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some(fit) = fits.get(fit_state.plotted) {
+        let points = probability_plot(&feature.measurements, &fit.params);
+        let (low, high) = points.iter()
+            .flat_map(|p| p.iter().copied())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+
+        Plot::new("probability_plot")
+            .height(200.0)
+            .data_aspect(1.0)
+            .x_axis_label(format!("{} quantile", fit.label()))
+            .y_axis_label("Measured")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(vec![[low, low], [high, high]])
+                    .color(egui::Color32::GRAY)
+                    .name("Fit"));
+                plot_ui.points(Points::new(points)
+                    .radius(2.0)
+                    .color(egui::Color32::LIGHT_BLUE)
+                    .name("Measurements"));
+            });
+    }
+
+    if let Some(mut params) = adopted {
+        params.calculated = false;
+        let target = state.project.components.get_mut(component_index)
+            .and_then(|c| c.features.iter_mut().find(|f| f.name == feature.name));
+        if let Some(target) = target {
+            target.distribution = Some(params.dist_type);
+            target.distribution_params = Some(params);
+            if let Err(e) = state.save_project() {
+                state.error_message = Some(e.to_string());
+            }
+        }
+    }
+}
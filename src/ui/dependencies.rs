@@ -0,0 +1,500 @@
+// src/ui/dependencies.rs
+use std::collections::HashMap;
+use eframe::egui;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use crate::state::{AppState, DependencyView, DsmColor, DsmLevel, Screen};
+use atlas_core::analysis::dsm::{cluster, DsmClustering};
+
+/// Side of a matrix cell in points
+const CELL: f32 = 20.0;
+
+const CLEARANCE_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 180, 90);
+const TRANSITION_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 180, 60);
+const INTERFERENCE_COLOR: egui::Color32 = egui::Color32::from_rgb(210, 80, 80);
+const MIXED_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 100, 200);
+const STACKUP_ONLY_COLOR: egui::Color32 = egui::Color32::from_gray(140);
+
+/// How two rows of the matrix are coupled
+#[derive(Debug, Clone, Default)]
+struct DsmCell {
+    mates: usize,
+    /// Fit types of the mates, as named on the dependency graph's edges
+    fits: Vec<String>,
+    /// Analyses with contributions from both rows
+    stackups: Vec<String>,
+}
+
+/// Component×component or feature×feature couplings through mates and
+/// shared stackups
+struct DependencyMatrix {
+    /// (component, feature) of each row; no feature at the component level
+    items: Vec<(String, Option<String>)>,
+    cells: Vec<Vec<DsmCell>>,
+}
+
+impl DependencyMatrix {
+    fn build(state: &AppState) -> Self {
+        let level = state.dsm.level;
+        let items: Vec<(String, Option<String>)> = match level {
+            DsmLevel::Component => state.project.components.iter()
+                .map(|component| (component.name.clone(), None))
+                .collect(),
+            DsmLevel::Feature => state.project.components.iter()
+                .flat_map(|component| component.features.iter()
+                    .map(|feature| (component.name.clone(), Some(feature.name.clone()))))
+                .collect(),
+        };
+        let rows: HashMap<(String, Option<String>), usize> = items.iter().cloned()
+            .enumerate()
+            .map(|(i, item)| (item, i))
+            .collect();
+        let row = |component: &str, feature: &str| {
+            let feature = (level == DsmLevel::Feature).then(|| feature.to_string());
+            rows.get(&(component.to_string(), feature)).copied()
+        };
+
+        let mut cells = vec![vec![DsmCell::default(); items.len()]; items.len()];
+
+        let graph = &state.mate_state.dependency_graph;
+        let node_rows: HashMap<NodeIndex, usize> = state.mate_state.feature_nodes.iter()
+            .filter_map(|((component, feature), node)| row(component, feature).map(|r| (*node, r)))
+            .collect();
+        for edge in graph.edge_references() {
+            let (Some(&a), Some(&b)) = (node_rows.get(&edge.source()), node_rows.get(&edge.target())) else {
+                continue;
+            };
+            // Mates within one component are not shown
+            if a == b {
+                continue;
+            }
+            for (i, j) in [(a, b), (b, a)] {
+                let cell = &mut cells[i][j];
+                cell.mates += 1;
+                if !cell.fits.contains(edge.weight()) {
+                    cell.fits.push(edge.weight().clone());
+                }
+            }
+        }
+
+        for analysis in &state.project.analyses {
+            let mut used: Vec<usize> = analysis.contributions.iter()
+                .filter_map(|contribution| row(&contribution.component_id, &contribution.feature_id))
+                .collect();
+            used.sort_unstable();
+            used.dedup();
+            for (k, &i) in used.iter().enumerate() {
+                for &j in &used[k + 1..] {
+                    cells[i][j].stackups.push(analysis.name.clone());
+                    cells[j][i].stackups.push(analysis.name.clone());
+                }
+            }
+        }
+
+        Self { items, cells }
+    }
+
+    fn label(&self, row: usize) -> String {
+        match &self.items[row] {
+            (component, Some(feature)) => format!("{}.{}", component, feature),
+            (component, None) => component.clone(),
+        }
+    }
+
+    /// Mates plus shared stackups between each pair of rows
+    fn weights(&self) -> Vec<Vec<f64>> {
+        self.cells.iter()
+            .map(|row| row.iter().map(|cell| (cell.mates + cell.stackups.len()) as f64).collect())
+            .collect()
+    }
+
+    fn max_count(&self, color: DsmColor) -> usize {
+        self.cells.iter()
+            .flatten()
+            .map(|cell| match color {
+                DsmColor::SharedStackups => cell.stackups.len(),
+                _ => cell.mates,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Light to dark blue for `t` in [0, 1]
+fn count_color(t: f64) -> egui::Color32 {
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t.clamp(0.0, 1.0)).round() as u8;
+    egui::Color32::from_rgb(lerp(170, 30), lerp(200, 70), lerp(240, 170))
+}
+
+pub(super) fn fit_color(fit: &str) -> egui::Color32 {
+    match fit {
+        "Clearance" => CLEARANCE_COLOR,
+        "Transition" => TRANSITION_COLOR,
+        "Interference" => INTERFERENCE_COLOR,
+        _ => STACKUP_ONLY_COLOR,
+    }
+}
+
+fn cell_color(cell: &DsmCell, color: DsmColor, max: usize) -> Option<egui::Color32> {
+    let max = max.max(1) as f64;
+    match color {
+        DsmColor::MateCount => (cell.mates > 0).then(|| count_color(cell.mates as f64 / max)),
+        DsmColor::SharedStackups => (!cell.stackups.is_empty())
+            .then(|| count_color(cell.stackups.len() as f64 / max)),
+        DsmColor::FitType => match cell.fits.as_slice() {
+            [] if cell.stackups.is_empty() => None,
+            [] => Some(STACKUP_ONLY_COLOR),
+            [fit] => Some(fit_color(fit)),
+            _ => Some(MIXED_COLOR),
+        },
+    }
+}
+
+pub fn show_dependencies_view(ui: &mut egui::Ui, state: &mut AppState) {
+    state.update_mate_state();
+
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 10.0;
+        for (view, label) in [(DependencyView::Matrix, "Matrix"), (DependencyView::Graph, "Graph")] {
+            if ui.selectable_label(state.dependency_view == view, label).clicked() {
+                state.dependency_view = view;
+            }
+        }
+    });
+    ui.add_space(4.0);
+
+    if state.dependency_view == DependencyView::Graph {
+        crate::ui::assembly_graph::show_assembly_graph(ui, state);
+        return;
+    }
+
+    let matrix = DependencyMatrix::build(state);
+    let clustering = if state.dsm.clustered {
+        cluster(&matrix.weights(), state.dsm.min_coupling)
+    } else {
+        DsmClustering { order: (0..matrix.items.len()).collect(), clusters: Vec::new() }
+    };
+
+    let available_size = ui.available_size();
+
+    egui::Grid::new("dependencies_grid")
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            // Left panel - Matrix
+            ui.vertical(|ui| {
+                ui.set_min_width(available_size.x * 0.65);
+                ui.set_max_width(available_size.x * 0.65);
+                ui.set_min_height(available_size.y);
+
+                ui.heading("Dependency Matrix");
+                ui.add_space(4.0);
+                show_matrix_options(ui, state);
+                show_legend(ui, state.dsm.color, matrix.max_count(state.dsm.color));
+
+                let blocks = clustering.clusters.iter().filter(|c| c.len() > 1).count();
+                if state.dsm.clustered {
+                    ui.label(format!("{} blocks of coupled items, outlined on the diagonal", blocks));
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.add_space(8.0);
+
+                egui::ScrollArea::both()
+                    .id_source("dependency_matrix_scroll")
+                    .show(ui, |ui| {
+                        show_matrix(ui, state, &matrix, &clustering);
+                    });
+            });
+
+            // Right panel - Drill-down
+            ui.vertical(|ui| {
+                ui.set_min_width(available_size.x * 0.3);
+                ui.set_min_height(available_size.y);
+
+                egui::ScrollArea::vertical()
+                    .id_source("dependency_details_scroll")
+                    .show(ui, |ui| {
+                        show_item_details(ui, state, &matrix);
+                    });
+            });
+
+            ui.end_row();
+        });
+}
+
+fn show_matrix_options(ui: &mut egui::Ui, state: &mut AppState) {
+    let dsm = &mut state.dsm;
+    ui.horizontal(|ui| {
+        ui.label("Rows:");
+        let level = dsm.level;
+        ui.radio_value(&mut dsm.level, DsmLevel::Component, "Components");
+        ui.radio_value(&mut dsm.level, DsmLevel::Feature, "Features");
+        if dsm.level != level {
+            dsm.selected = None;
+        }
+
+        ui.separator();
+        ui.label("Color by:");
+        egui::ComboBox::from_id_source("dsm_color")
+            .selected_text(match dsm.color {
+                DsmColor::MateCount => "Mate count",
+                DsmColor::FitType => "Fit type",
+                DsmColor::SharedStackups => "Shared stackups",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut dsm.color, DsmColor::MateCount, "Mate count");
+                ui.selectable_value(&mut dsm.color, DsmColor::FitType, "Fit type");
+                ui.selectable_value(&mut dsm.color, DsmColor::SharedStackups, "Shared stackups");
+            });
+    });
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut dsm.clustered, "Cluster")
+            .on_hover_text("Reorder the rows so tightly coupled items form blocks");
+        ui.add_enabled_ui(dsm.clustered, |ui| {
+            ui.label("Min. coupling:");
+            ui.add(egui::DragValue::new(&mut dsm.min_coupling)
+                .speed(0.05)
+                .clamp_range(0.01..=10.0))
+                .on_hover_text("Weakest average number of mates and shared stackups between members of a block");
+        });
+    });
+}
+
+fn show_legend(ui: &mut egui::Ui, color: DsmColor, max: usize) {
+    ui.horizontal(|ui| {
+        match color {
+            DsmColor::MateCount | DsmColor::SharedStackups => {
+                if max == 0 {
+                    ui.label(if color == DsmColor::MateCount { "No mates" } else { "No shared stackups" });
+                } else {
+                    ui.colored_label(count_color(1.0 / max as f64), "■ 1");
+                    if max > 1 {
+                        ui.colored_label(count_color(1.0), format!("■ {}", max));
+                    }
+                }
+            },
+            DsmColor::FitType => {
+                ui.colored_label(CLEARANCE_COLOR, "■ Clearance");
+                ui.colored_label(TRANSITION_COLOR, "■ Transition");
+                ui.colored_label(INTERFERENCE_COLOR, "■ Interference");
+                ui.colored_label(MIXED_COLOR, "■ Mixed");
+                ui.colored_label(STACKUP_ONLY_COLOR, "■ Stackups only");
+            },
+        }
+    });
+}
+
+/// Row labels down the left, row numbers across the top and a cell for
+/// every pair, drawn in display order. Clicking a row or cell selects its
+/// row.
+fn show_matrix(ui: &mut egui::Ui, state: &mut AppState, matrix: &DependencyMatrix, clustering: &DsmClustering) {
+    let order = &clustering.order;
+    let n = order.len();
+    if n == 0 {
+        ui.label(match state.dsm.level {
+            DsmLevel::Component => "No components yet",
+            DsmLevel::Feature => "No features yet",
+        });
+        return;
+    }
+
+    let visuals = ui.visuals().clone();
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    let small = egui::TextStyle::Small.resolve(ui.style());
+    let labels: Vec<String> = order.iter().enumerate()
+        .map(|(position, &row)| format!("{} {}", position + 1, matrix.label(row)))
+        .collect();
+    let label_width = labels.iter()
+        .map(|label| ui.painter().layout_no_wrap(label.clone(), font.clone(), visuals.text_color()).size().x)
+        .fold(0.0, f32::max) + 12.0;
+
+    let size = egui::vec2(label_width + n as f32 * CELL, CELL + n as f32 * CELL);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+    let painter = ui.painter_at(rect);
+    let origin = rect.min + egui::vec2(label_width, CELL);
+
+    // (row, column) in display order under the pointer; no column over the
+    // row labels
+    let hovered = response.hover_pos().and_then(|pos| {
+        let offset = pos - origin;
+        let row = (offset.y / CELL).floor();
+        if offset.y < 0.0 || row as usize >= n {
+            return None;
+        }
+        let column = (offset.x >= 0.0).then(|| ((offset.x / CELL).floor() as usize).min(n - 1));
+        Some((row as usize, column))
+    });
+
+    let selected = state.dsm.selected.as_ref()
+        .and_then(|item| order.iter().position(|&row| matrix.items[row] == *item));
+    let max = matrix.max_count(state.dsm.color);
+    let grid_stroke = egui::Stroke::new(0.5, visuals.widgets.noninteractive.bg_stroke.color);
+
+    for (position, &row) in order.iter().enumerate() {
+        let y = origin.y + position as f32 * CELL;
+        if selected == Some(position) || hovered.is_some_and(|(r, _)| r == position) {
+            let band = egui::Rect::from_min_size(egui::pos2(rect.min.x, y), egui::vec2(size.x, CELL));
+            painter.rect_filled(band, 0.0, visuals.selection.bg_fill.linear_multiply(0.4));
+        }
+        painter.text(
+            egui::pos2(rect.min.x + 4.0, y + CELL / 2.0),
+            egui::Align2::LEFT_CENTER,
+            &labels[position],
+            font.clone(),
+            if selected == Some(position) { visuals.strong_text_color() } else { visuals.text_color() },
+        );
+        painter.text(
+            egui::pos2(origin.x + position as f32 * CELL + CELL / 2.0, rect.min.y + CELL / 2.0),
+            egui::Align2::CENTER_CENTER,
+            (position + 1).to_string(),
+            small.clone(),
+            visuals.weak_text_color(),
+        );
+
+        for (column, &other) in order.iter().enumerate() {
+            let cell_rect = egui::Rect::from_min_size(
+                egui::pos2(origin.x + column as f32 * CELL, y),
+                egui::vec2(CELL, CELL),
+            );
+            let cell = &matrix.cells[row][other];
+            if row == other {
+                painter.rect_filled(cell_rect, 0.0, visuals.weak_text_color());
+            } else if let Some(color) = cell_color(cell, state.dsm.color, max) {
+                painter.rect_filled(cell_rect, 0.0, color);
+                let count = match state.dsm.color {
+                    DsmColor::MateCount => cell.mates,
+                    DsmColor::SharedStackups => cell.stackups.len(),
+                    DsmColor::FitType => 0,
+                };
+                if count > 0 {
+                    painter.text(cell_rect.center(), egui::Align2::CENTER_CENTER, count.to_string(), small.clone(), egui::Color32::BLACK);
+                }
+            }
+            painter.rect_stroke(cell_rect, 0.0, grid_stroke);
+        }
+    }
+
+    for block in clustering.clusters.iter().filter(|c| c.len() > 1) {
+        let block_rect = egui::Rect::from_min_max(
+            origin + egui::vec2(block.start as f32 * CELL, block.start as f32 * CELL),
+            origin + egui::vec2(block.end as f32 * CELL, block.end as f32 * CELL),
+        );
+        painter.rect_stroke(block_rect, 0.0, egui::Stroke::new(2.0, visuals.selection.stroke.color));
+    }
+
+    if response.clicked() {
+        if let Some((position, _)) = hovered {
+            state.dsm.selected = Some(matrix.items[order[position]].clone());
+        }
+    }
+
+    if let Some((position, Some(column))) = hovered {
+        let (row, other) = (order[position], order[column]);
+        let cell = &matrix.cells[row][other];
+        if row != other {
+            response.on_hover_ui_at_pointer(|ui| {
+                ui.label(format!("{} ⇄ {}", matrix.label(row), matrix.label(other)));
+                if cell.mates > 0 {
+                    ui.label(format!("{} mate(s): {}", cell.mates, cell.fits.join(", ")));
+                }
+                if !cell.stackups.is_empty() {
+                    ui.label(format!("Shared stackups: {}", cell.stackups.join(", ")));
+                }
+                if cell.mates == 0 && cell.stackups.is_empty() {
+                    ui.label("Not coupled");
+                }
+            });
+        }
+    }
+}
+
+/// Mates, coupled rows and stackups of the selected row
+fn show_item_details(ui: &mut egui::Ui, state: &mut AppState, matrix: &DependencyMatrix) {
+    let Some(row) = state.dsm.selected.as_ref().and_then(|item| matrix.items.iter().position(|i| i == item)) else {
+        ui.heading("Details");
+        ui.label("Click a row or cell to see what depends on it");
+        return;
+    };
+    let (component, feature) = matrix.items[row].clone();
+    let matches = |c: &str, f: &str| c == component && feature.as_deref().is_none_or(|feature| f == feature);
+
+    ui.heading(matrix.label(row));
+    ui.add_space(8.0);
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.strong("Mates");
+        let mates: Vec<_> = state.project.mates.iter()
+            .filter(|mate| matches(&mate.component_a, &mate.feature_a) || matches(&mate.component_b, &mate.feature_b))
+            .collect();
+        if mates.is_empty() {
+            ui.label("None");
+        }
+        for mate in mates {
+            let fit = mate.fit.as_ref().map(|fit| format!(", {}", fit)).unwrap_or_default();
+            ui.label(format!(
+                "{}.{} ⇄ {}.{} ({:?}{})",
+                mate.component_a, mate.feature_a, mate.component_b, mate.feature_b, mate.fit_type, fit
+            ));
+        }
+    });
+
+    ui.add_space(8.0);
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.strong("Coupled with");
+        let mut coupled: Vec<(usize, &DsmCell)> = matrix.cells[row].iter().enumerate()
+            .filter(|(other, cell)| *other != row && (cell.mates > 0 || !cell.stackups.is_empty()))
+            .collect();
+        coupled.sort_by_key(|(other, cell)| (std::cmp::Reverse(cell.mates + cell.stackups.len()), *other));
+        if coupled.is_empty() {
+            ui.label("Nothing");
+        }
+        for (other, cell) in coupled {
+            ui.label(format!(
+                "{}: {} mate(s), {} shared stackup(s)",
+                matrix.label(other), cell.mates, cell.stackups.len()
+            ));
+        }
+    });
+
+    ui.add_space(8.0);
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.strong("Used by stackups");
+        let mut open = None;
+        let mut used = false;
+        for (index, analysis) in state.project.analyses.iter().enumerate() {
+            let contributions: Vec<String> = analysis.contributions.iter()
+                .filter(|contribution| matches(&contribution.component_id, &contribution.feature_id))
+                .map(|contribution| {
+                    let sign = if contribution.direction >= 0.0 { "+" } else { "−" };
+                    match feature {
+                        Some(_) => sign.to_string(),
+                        None => format!("{}{}", sign, contribution.feature_id),
+                    }
+                })
+                .collect();
+            if contributions.is_empty() {
+                continue;
+            }
+            used = true;
+            ui.horizontal(|ui| {
+                if ui.link(&analysis.name).on_hover_text("Open this analysis").clicked() {
+                    open = Some(index);
+                }
+                ui.label(format!("({})", contributions.join(", ")));
+            });
+        }
+        if !used {
+            ui.label("None");
+        }
+        if let Some(index) = open {
+            state.selected_analysis = Some(index);
+            state.current_screen = Screen::Analysis;
+        }
+    });
+}
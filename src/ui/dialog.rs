@@ -7,10 +7,12 @@ use crate::ui::dialog_widgets::{
     MateDialog,
     AnalysisDialog,
     ContributionDialog,
+    MeasurementDialog,
+    LoopDialog,
 };
-use crate::config::Component;
-use crate::config::mate::Mate;
-use crate::analysis::stackup::{StackupAnalysis, StackupContribution};
+use atlas_core::config::Component;
+use atlas_core::config::mate::Mate;
+use atlas_core::analysis::stackup::{StackupAnalysis, StackupContribution};
 
 pub struct DialogManager {
     component_dialog: ComponentDialog,
@@ -18,6 +20,8 @@ pub struct DialogManager {
     mate_dialog: MateDialog,
     analysis_dialog: AnalysisDialog,
     contribution_dialog: ContributionDialog,
+    measurement_dialog: MeasurementDialog,
+    loop_dialog: LoopDialog,
     current_state: DialogState,
 }
 
@@ -29,6 +33,8 @@ impl DialogManager {
             mate_dialog: MateDialog::new(),
             analysis_dialog: AnalysisDialog::new(),
             contribution_dialog: ContributionDialog::new(),
+            measurement_dialog: MeasurementDialog::new(),
+            loop_dialog: LoopDialog::new(),
             current_state: DialogState::None,
         }
     }
@@ -42,7 +48,7 @@ impl DialogManager {
                     self.component_dialog.open(None);
                 },
                 DialogState::EditComponent { index, .. } => {
-                    if let Some(component) = state.components.get(*index) {
+                    if let Some(component) = state.project.components.get(*index) {
                         self.component_dialog.open(Some(component));
                     }
                 },
@@ -50,7 +56,7 @@ impl DialogManager {
                     self.feature_dialog.open(None);
                 },
                 DialogState::EditFeature { component_index, feature_index, .. } => {
-                    if let Some(component) = state.components.get(*component_index) {
+                    if let Some(component) = state.project.components.get(*component_index) {
                         if let Some(feature) = component.features.get(*feature_index) {
                             self.feature_dialog.open(Some(feature));
                         }
@@ -60,7 +66,7 @@ impl DialogManager {
                     self.mate_dialog.open(None);
                 },
                 DialogState::EditMate { index, .. } => {
-                    if let Some(mate) = state.mates.get(*index) {
+                    if let Some(mate) = state.project.mates.get(*index) {
                         self.mate_dialog.open(Some(mate));
                     }
                 },
@@ -68,7 +74,7 @@ impl DialogManager {
                     self.analysis_dialog.open(None);
                 },
                 DialogState::EditAnalysis { index, .. } => {
-                    if let Some(analysis) = state.analyses.get(*index) {
+                    if let Some(analysis) = state.project.analyses.get(*index) {
                         self.analysis_dialog.open(Some(analysis));
                     }
                 },
@@ -76,7 +82,7 @@ impl DialogManager {
                     self.contribution_dialog.open(None);
                 },
                 DialogState::EditContribution { analysis_index, contribution_index, .. } => {
-                    if let Some(analysis) = state.analyses.get(*analysis_index) {
+                    if let Some(analysis) = state.project.analyses.get(*analysis_index) {
                         if let Some(contribution_idx) = contribution_index {
                             if let Some(contribution) = analysis.contributions.get(*contribution_idx) {
                                 self.contribution_dialog.open(Some(contribution));
@@ -84,6 +90,15 @@ impl DialogManager {
                         }
                     }
                 },
+                DialogState::ImportMeasurements { path } => {
+                    if let Err(e) = self.measurement_dialog.open(path, &state.project.components) {
+                        state.error_message = Some(format!("{:#}", e));
+                        state.current_dialog = DialogState::None;
+                    }
+                },
+                DialogState::StackFromMates => {
+                    self.loop_dialog.open();
+                },
                 DialogState::None => {},
             }
             self.current_state = state.current_dialog.clone();
@@ -103,7 +118,7 @@ impl DialogManager {
                 if let Some(changed) = self.component_dialog.show(
                     ctx,
                     edit_index,
-                    &mut state.components,
+                    &mut state.project.components,
                     || { state.current_dialog = DialogState::None }
                 ) {
                     if changed {
@@ -125,7 +140,8 @@ impl DialogManager {
                     ctx,
                     *component_index,
                     feature_index,
-                    &mut state.components,
+                    state.project.file.units,
+                    &mut state.project.components,
                     || { state.current_dialog = DialogState::None }
                 ) {
                     if changed {
@@ -146,8 +162,9 @@ impl DialogManager {
                 if let Some(changed) = self.mate_dialog.show(
                     ctx,
                     edit_index,
-                    &state.components,
-                    &mut state.mates,
+                    state.project.file.units,
+                    &mut state.project.components,
+                    &mut state.project.mates,
                     || { state.current_dialog = DialogState::None }
                 ) {
                     if changed {
@@ -169,16 +186,16 @@ impl DialogManager {
                 if let Some(changed) = self.analysis_dialog.show(
                     ctx,
                     edit_index,
-                    &mut state.analyses,
+                    &mut state.project.analyses,
                     || { state.current_dialog = DialogState::None }
                 ) {
                     if changed {
                         // If editing, preserve the analysis ID to keep history connected
                         if let Some(index) = edit_index {
-                            if let Some(prev_analysis) = state.analyses.get(index) {
+                            if let Some(prev_analysis) = state.project.analyses.get(index) {
                                 let prev_id = prev_analysis.id.clone();
                                 // Update the ID of the edited analysis to match the original
-                                if let Some(edited_analysis) = state.analyses.get_mut(index) {
+                                if let Some(edited_analysis) = state.project.analyses.get_mut(index) {
                                     edited_analysis.id = prev_id;
                                 }
                             }
@@ -203,11 +220,42 @@ impl DialogManager {
                     ctx,
                     *analysis_index,
                     contribution_index,
-                    &state.components,
-                    &mut state.analyses,
+                    &state.project.components,
+                    &mut state.project.analyses,
+                    || { state.current_dialog = DialogState::None }
+                ) {
+                    if changed {
+                        if let Err(e) = state.save_project() {
+                            state.error_message = Some(e.to_string());
+                        }
+                    }
+                }
+            },
+
+            DialogState::ImportMeasurements { .. } => {
+                if let Some(changed) = self.measurement_dialog.show(
+                    ctx,
+                    &mut state.project.components,
+                    || { state.current_dialog = DialogState::None }
+                ) {
+                    if changed {
+                        if let Err(e) = state.save_project() {
+                            state.error_message = Some(e.to_string());
+                        }
+                    }
+                }
+            },
+
+            DialogState::StackFromMates => {
+                if let Some(changed) = self.loop_dialog.show(
+                    ctx,
+                    &state.project.components,
+                    &state.project.mates,
+                    &mut state.project.analyses,
                     || { state.current_dialog = DialogState::None }
                 ) {
                     if changed {
+                        state.selected_analysis = Some(state.project.analyses.len() - 1);
                         if let Err(e) = state.save_project() {
                             state.error_message = Some(e.to_string());
                         }
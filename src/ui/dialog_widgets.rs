@@ -2,13 +2,21 @@
 
 use eframe::egui;
 use uuid::Uuid;
-use crate::config::{Component, Feature, FeatureType};
-use crate::config::mate::{Mate, FitType};
-use crate::analysis::stackup::{
-    AnalysisMethod, DistributionType, MonteCarloSettings,
-    StackupAnalysis, StackupContribution
+use atlas_core::config::{CapabilityTarget, Component, CostCurve, Feature, FeatureProcess, FeatureType, FitDesignation, ManufacturingProcess, ToleranceClass, Units};
+use atlas_core::config::fits::PREFERRED_FITS;
+use atlas_core::config::mate::{Mate, FitType};
+use atlas_core::analysis::stackup::{
+    AnalysisMethod, DistributionParams, DistributionType, ModifiedRssSettings, MonteCarloSettings,
+    MrssCorrection, StackupAnalysis, StackupContribution
 };
-use crate::utils::find_feature;
+use atlas_core::analysis::six_sigma::{MeanShiftMode, SixSigmaSettings};
+use atlas_core::analysis::expression::Expr;
+use atlas_core::analysis::correlation::CorrelationGroup;
+use atlas_core::analysis::convergence::ConvergenceSettings;
+use atlas_core::analysis::sampling::SamplingStrategy;
+use atlas_core::file::measurements::{ColumnMapping, MeasurementTable};
+use atlas_core::analysis::loops::{find_loops, StackLoop};
+use atlas_core::utils::find_feature;
 
 #[derive(Default)]
 pub struct ComponentDialog {
@@ -155,6 +163,14 @@ impl ComponentDialog {
     }
 }
 
+#[derive(Default, Clone, Copy, PartialEq)]
+enum CapabilityMode {
+    #[default]
+    None,
+    CpCpk,
+    MeanShift,
+}
+
 #[derive(Default)]
 pub struct FeatureDialog {
     name: String,
@@ -163,6 +179,12 @@ pub struct FeatureDialog {
     minus_tolerance: String,
     feature_type: FeatureType,
     distribution: DistributionType,
+    custom_params: Option<DistributionParams>, // None while calculated from the tolerances
+    capability_mode: CapabilityMode,
+    capability_cp: String,
+    capability_second: String, // Cpk or shift k, depending on the mode
+    process: Option<FeatureProcess>,
+    tolerance_class: String, // Empty for tolerances entered by hand
     open: bool,
 }
 
@@ -180,6 +202,7 @@ impl FeatureDialog {
         ctx: &egui::Context,
         component_index: usize,
         feature_index: Option<usize>,
+        units: Units,
         components: &mut Vec<Component>,
         on_close: impl FnOnce(),
     ) -> Option<bool> {
@@ -191,12 +214,16 @@ impl FeatureDialog {
             let result = egui::Window::new(if feature_index.is_some() { "Edit Feature" } else { "New Feature" })
                 .collapsible(false)
                 .resizable(false)
-                .fixed_size([320.0, 280.0])
+                .fixed_size([320.0, 520.0])
                 .show(ctx, |ui| {
                     let name_valid = !self.name.trim().is_empty();
                     let value_valid = self.value.parse::<f64>().is_ok();
                     let plus_tol_valid = self.plus_tolerance.parse::<f64>().is_ok();
                     let minus_tol_valid = self.minus_tolerance.parse::<f64>().is_ok();
+                    let capability_valid = self.capability_mode == CapabilityMode::None
+                        || self.capability().is_some();
+                    let class = self.class(units);
+                    let class_valid = class.is_ok();
 
                     ui.horizontal(|ui| {
                         ui.label("Name:");
@@ -221,19 +248,39 @@ impl FeatureDialog {
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("+ Tolerance:");
-                        let response = ui.text_edit_singleline(&mut self.plus_tolerance);
-                        if !plus_tol_valid && response.lost_focus() {
-                            ui.colored_label(egui::Color32::RED, "⚠");
+                        ui.label("Fit class:");
+                        ui.add(egui::TextEdit::singleline(&mut self.tolerance_class)
+                            .hint_text("e.g. H7 or g6")
+                            .desired_width(60.0));
+                        if let Err(e) = &class {
+                            ui.colored_label(egui::Color32::RED, "⚠").on_hover_text(e);
                         }
                     });
 
-                    ui.horizontal(|ui| {
-                        ui.label("- Tolerance:");
-                        let response = ui.text_edit_singleline(&mut self.minus_tolerance);
-                        if !minus_tol_valid && response.lost_focus() {
-                            ui.colored_label(egui::Color32::RED, "⚠");
-                        }
+                    // A class sets the tolerances from the nominal
+                    let by_class = matches!(class, Ok(Some(_)));
+                    if let Ok(Some((class, plus, minus))) = &class {
+                        self.plus_tolerance = plus.to_string();
+                        self.minus_tolerance = minus.to_string();
+                        self.feature_type = if class.hole { FeatureType::Internal } else { FeatureType::External };
+                    }
+
+                    ui.add_enabled_ui(!by_class, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("+ Tolerance:");
+                            let response = ui.text_edit_singleline(&mut self.plus_tolerance);
+                            if !plus_tol_valid && response.lost_focus() {
+                                ui.colored_label(egui::Color32::RED, "⚠");
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label("- Tolerance:");
+                            let response = ui.text_edit_singleline(&mut self.minus_tolerance);
+                            if !minus_tol_valid && response.lost_focus() {
+                                ui.colored_label(egui::Color32::RED, "⚠");
+                            }
+                        });
                     });
 
                     ui.horizontal(|ui| {
@@ -245,8 +292,85 @@ impl FeatureDialog {
                                 ui.selectable_value(&mut self.distribution, DistributionType::Uniform, "Uniform");
                                 ui.selectable_value(&mut self.distribution, DistributionType::Triangular, "Triangular");
                                 ui.selectable_value(&mut self.distribution, DistributionType::LogNormal, "LogNormal");
+                                ui.selectable_value(&mut self.distribution, DistributionType::Weibull, "Weibull");
+                                ui.selectable_value(&mut self.distribution, DistributionType::Beta, "Beta (PERT)");
+                                ui.selectable_value(&mut self.distribution, DistributionType::Rayleigh, "Rayleigh");
+                                ui.selectable_value(&mut self.distribution, DistributionType::FoldedNormal, "Folded Normal");
+                                ui.selectable_value(&mut self.distribution, DistributionType::TruncatedNormal, "Truncated Normal");
+                                ui.selectable_value(&mut self.distribution, DistributionType::Mixture, "Mixture");
+                            });
+                    });
+
+                    let mut custom = self.custom_params.is_some();
+                    if ui.checkbox(&mut custom, "Custom distribution parameters").changed() {
+                        self.custom_params = None;
+                    }
+                    if custom {
+                        // Seed from the tolerances whenever the type changes
+                        if self.custom_params.as_ref().is_none_or(|p| p.dist_type != self.distribution) {
+                            if let Some(feature) = self.feature(None) {
+                                let mut params = StackupAnalysis::calculate_distribution_params(&feature);
+                                params.calculated = false;
+                                self.custom_params = Some(params);
+                            }
+                        }
+                        if let Some(params) = &mut self.custom_params {
+                            distribution_params_editor(ui, params);
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label("Capability:");
+                        ui.radio_value(&mut self.capability_mode, CapabilityMode::None, "None");
+                        ui.radio_value(&mut self.capability_mode, CapabilityMode::CpCpk, "Cp/Cpk");
+                        ui.radio_value(&mut self.capability_mode, CapabilityMode::MeanShift, "Mean shift");
+                    });
+
+                    if self.capability_mode != CapabilityMode::None {
+                        ui.horizontal(|ui| {
+                            ui.label("Cp:");
+                            ui.text_edit_singleline(&mut self.capability_cp);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(if self.capability_mode == CapabilityMode::CpCpk { "Cpk:" } else { "Shift (k·σ):" });
+                            ui.text_edit_singleline(&mut self.capability_second);
+                        });
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label("Process:");
+                        egui::ComboBox::from_id_source("feature_process")
+                            .selected_text(self.process.map_or("None", |p| p.process.label()))
+                            .show_ui(ui, |ui| {
+                                if ui.selectable_label(self.process.is_none(), "None").clicked() {
+                                    self.process = None;
+                                }
+                                for process in ManufacturingProcess::BUILT_IN {
+                                    let selected = self.process.is_some_and(|p| p.process == process);
+                                    if ui.selectable_label(selected, process.label()).clicked() {
+                                        self.process = FeatureProcess::from_table(process, units);
+                                    }
+                                }
+                                let custom = self.process.is_some_and(|p| p.process == ManufacturingProcess::Custom);
+                                if ui.selectable_label(custom, "Custom").clicked() && !custom {
+                                    // Start from the curve already chosen, if any
+                                    let curve = self.process.map(|p| p.curve)
+                                        .or_else(|| ManufacturingProcess::Turning.table_curve(units));
+                                    self.process = curve.map(|curve| FeatureProcess { process: ManufacturingProcess::Custom, curve });
+                                }
                             });
                     });
+                    let tolerance = self.feature(None).map(|f| f.dimension.bilateral_tolerance());
+                    if let Some(process) = &mut self.process {
+                        if cost_curve_editor(ui, &mut process.curve) {
+                            process.process = ManufacturingProcess::Custom;
+                        }
+                        match tolerance.map(|t| process.curve.cost(t)) {
+                            Some(Some(cost)) => ui.label(format!("Cost at current tolerance: {:.3}", cost)),
+                            Some(None) => ui.colored_label(egui::Color32::YELLOW, "Tighter than the process holds"),
+                            None => ui.label(""),
+                        };
+                    }
 
                     ui.add_space(8.0);
 
@@ -255,26 +379,13 @@ impl FeatureDialog {
                             should_close = true;
                         }
 
-                        let can_save = name_valid && value_valid && plus_tol_valid && minus_tol_valid;
+                        let can_save = name_valid && value_valid && plus_tol_valid && minus_tol_valid && capability_valid && class_valid;
                         if ui.add_enabled(can_save, egui::Button::new("Save")).clicked() {
-                            if let (Ok(val), Ok(plus), Ok(minus)) = (
-                                self.value.parse::<f64>(),
-                                self.plus_tolerance.parse::<f64>(),
-                                self.minus_tolerance.parse::<f64>(),
-                            ) {
-                                let new_feature = Feature {
-                                    name: self.name.clone(),
-                                    feature_type: self.feature_type,
-                                    dimension: crate::config::Dimension {
-                                        value: val,
-                                        plus_tolerance: plus,
-                                        minus_tolerance: minus,
-                                    },
-                                    distribution: Some(self.distribution),
-                                    distribution_params: None,
-                                };
-
+                            if let Some(mut new_feature) = self.feature(self.custom_params.clone()) {
                                 if let Some(idx) = feature_index {
+                                    // Measurements are imported, not edited here
+                                    let existing = &mut components[component_index].features[idx];
+                                    new_feature.measurements = std::mem::take(&mut existing.measurements);
                                     components[component_index].features[idx] = new_feature;
                                 } else {
                                     components[component_index].features.push(new_feature);
@@ -286,7 +397,7 @@ impl FeatureDialog {
                         }
                     });
 
-                    if !name_valid || !value_valid || !plus_tol_valid || !minus_tol_valid {
+                    if !name_valid || !value_valid || !plus_tol_valid || !minus_tol_valid || !capability_valid || !class_valid {
                         ui.colored_label(egui::Color32::RED, "All fields must be valid numbers");
                     }
                 });
@@ -312,6 +423,26 @@ impl FeatureDialog {
             self.minus_tolerance = feature.dimension.minus_tolerance.to_string();
             self.feature_type = feature.feature_type;
             self.distribution = feature.distribution.unwrap_or(DistributionType::Normal);
+            self.custom_params = feature.distribution_params.clone().filter(|p| !p.calculated);
+            self.process = feature.process;
+            self.tolerance_class = feature.tolerance_class.map(|c| c.to_string()).unwrap_or_default();
+            match feature.capability {
+                Some(CapabilityTarget::CpCpk { cp, cpk }) => {
+                    self.capability_mode = CapabilityMode::CpCpk;
+                    self.capability_cp = cp.to_string();
+                    self.capability_second = cpk.to_string();
+                },
+                Some(CapabilityTarget::MeanShift { cp, k }) => {
+                    self.capability_mode = CapabilityMode::MeanShift;
+                    self.capability_cp = cp.to_string();
+                    self.capability_second = k.to_string();
+                },
+                None => {
+                    self.capability_mode = CapabilityMode::None;
+                    self.capability_cp.clear();
+                    self.capability_second.clear();
+                },
+            }
         } else {
             self.name.clear();
             self.value = "0.0".to_string();
@@ -319,10 +450,97 @@ impl FeatureDialog {
             self.minus_tolerance = "0.0".to_string();
             self.feature_type = FeatureType::External;
             self.distribution = DistributionType::Normal;
+            self.custom_params = None;
+            self.process = None;
+            self.tolerance_class.clear();
+            self.capability_mode = CapabilityMode::None;
+            self.capability_cp.clear();
+            self.capability_second.clear();
+        }
+    }
+
+    /// The feature as entered, or None while a number does not parse
+    fn feature(&self, custom_params: Option<DistributionParams>) -> Option<Feature> {
+        let mut feature = Feature {
+            name: self.name.clone(),
+            feature_type: self.feature_type,
+            dimension: atlas_core::config::Dimension {
+                value: self.value.parse().ok()?,
+                plus_tolerance: self.plus_tolerance.parse().ok()?,
+                minus_tolerance: self.minus_tolerance.parse().ok()?,
+            },
+            distribution: Some(self.distribution),
+            distribution_params: custom_params,
+            capability: self.capability(),
+            process: self.process,
+            measurements: Vec::new(),
+            tolerance_class: self.tolerance_class.trim().parse().ok(),
+        };
+        if feature.distribution_params.is_none() {
+            feature.distribution_params = Some(StackupAnalysis::calculate_distribution_params(&feature));
+        }
+        Some(feature)
+    }
+
+    /// The tolerance class entered with its plus and minus tolerances at the
+    /// entered nominal, None when there is no class, or why it cannot be used
+    fn class(&self, units: Units) -> Result<Option<(ToleranceClass, f64, f64)>, String> {
+        if self.tolerance_class.trim().is_empty() {
+            return Ok(None);
+        }
+        let class: ToleranceClass = self.tolerance_class.parse().map_err(|e| format!("{:#}", e))?;
+        let Ok(value) = self.value.parse::<f64>() else { return Ok(None) };
+        let (plus, minus) = class.tolerances(value, units).map_err(|e| format!("{:#}", e))?;
+        Ok(Some((class, plus, minus)))
+    }
+
+    fn capability(&self) -> Option<CapabilityTarget> {
+        let cp = self.capability_cp.parse::<f64>().ok().filter(|cp| *cp > 0.0)?;
+        let second = self.capability_second.parse::<f64>().ok()?;
+        match self.capability_mode {
+            CapabilityMode::None => None,
+            CapabilityMode::CpCpk => Some(CapabilityTarget::CpCpk { cp, cpk: second }),
+            CapabilityMode::MeanShift => Some(CapabilityTarget::MeanShift { cp, k: second }),
         }
     }
 }
 
+/// One drag value per parameter of the distribution
+fn distribution_params_editor(ui: &mut egui::Ui, params: &mut DistributionParams) {
+    egui::Grid::new("distribution_params")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (index, (name, mut value)) in params.get_required_params().into_iter().enumerate() {
+                ui.label(format!("{}:", name));
+                if ui.add(egui::DragValue::new(&mut value).speed(0.001).max_decimals(6)).changed() {
+                    params.set_required_param(index, value);
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Cost curve parameters; returns whether any changed
+fn cost_curve_editor(ui: &mut egui::Ui, curve: &mut CostCurve) -> bool {
+    let mut changed = false;
+    egui::Grid::new("cost_curve")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (name, value) in [
+                ("Fixed cost", &mut curve.fixed),
+                ("Cost scale", &mut curve.scale),
+                ("Exponent", &mut curve.exponent),
+                ("Min tolerance", &mut curve.min_tolerance),
+                ("Max tolerance", &mut curve.max_tolerance),
+            ] {
+                ui.label(format!("{}:", name));
+                changed |= ui.add(egui::DragValue::new(value).speed(0.001).max_decimals(6).clamp_range(0.0..=f64::MAX)).changed();
+                ui.end_row();
+            }
+        });
+    changed
+}
+
 #[derive(Default)]
 pub struct MateDialog {
     component_a: String,
@@ -330,6 +548,8 @@ pub struct MateDialog {
     component_b: String,
     feature_b: String,
     fit_type: FitType,
+    fit: String, // ISO 286 designation such as H7/g6, or empty
+    error: Option<String>,
     open: bool,
 }
 
@@ -345,7 +565,8 @@ impl MateDialog {
         &mut self,
         ctx: &egui::Context,
         edit_index: Option<usize>,
-        components: &[Component],
+        units: Units,
+        components: &mut [Component],
         mates: &mut Vec<Mate>,
         on_close: impl FnOnce(),
     ) -> Option<bool> {
@@ -366,7 +587,7 @@ impl MateDialog {
                             egui::ComboBox::from_label("Select Component")
                                 .selected_text(&self.component_a)
                                 .show_ui(ui, |ui| {
-                                    for component in components {
+                                    for component in components.iter() {
                                         ui.selectable_value(
                                             &mut self.component_a,
                                             component.name.clone(),
@@ -401,7 +622,7 @@ impl MateDialog {
                         egui::ComboBox::from_label("Select Component")
                             .selected_text(&self.component_b)
                             .show_ui(ui, |ui| {
-                                for component in components {
+                                for component in components.iter() {
                                     ui.selectable_value(
                                         &mut self.component_b,
                                         component.name.clone(),
@@ -428,14 +649,56 @@ impl MateDialog {
                         ui.add_space(8.0);
     
                         // Fit Type selection
+                        let fit = (!self.fit.trim().is_empty()).then(|| self.fit.parse::<FitDesignation>());
                         ui.group(|ui| {
                             ui.heading("Fit Type");
                             ui.horizontal(|ui| {
-                                ui.radio_value(&mut self.fit_type, FitType::Clearance, "Clearance");
-                                ui.radio_value(&mut self.fit_type, FitType::Transition, "Transition");
-                                ui.radio_value(&mut self.fit_type, FitType::Interference, "Interference");
+                                ui.label("ISO fit:");
+                                ui.add(egui::TextEdit::singleline(&mut self.fit)
+                                    .hint_text("e.g. H7/g6")
+                                    .desired_width(80.0));
+                                egui::ComboBox::from_id_source("preferred_fits")
+                                    .selected_text("Preferred")
+                                    .show_ui(ui, |ui| {
+                                        for (designation, name) in PREFERRED_FITS {
+                                            if ui.selectable_label(self.fit == designation, format!("{} {}", designation, name)).clicked() {
+                                                self.fit = designation.to_string();
+                                            }
+                                        }
+                                    });
+                            });
+
+                            // A fit sets the type along with both features' tolerances
+                            let nominal = components.iter()
+                                .find(|c| c.name == self.component_a)
+                                .and_then(|c| c.features.iter().find(|f| f.name == self.feature_a))
+                                .map(|f| f.dimension.value);
+                            match (&fit, nominal) {
+                                (Some(Ok(fit)), Some(nominal)) => match fit.clearances(nominal, units) {
+                                    Ok((min, max)) => {
+                                        if let Ok(fit_type) = fit.fit_type(nominal, units) {
+                                            self.fit_type = fit_type;
+                                        }
+                                        ui.label(format!("Clearance {:.4} to {:.4}", min, max));
+                                    },
+                                    Err(e) => { ui.colored_label(egui::Color32::RED, format!("{:#}", e)); },
+                                },
+                                (Some(Err(e)), _) => { ui.colored_label(egui::Color32::RED, format!("{:#}", e)); },
+                                _ => {},
+                            }
+
+                            ui.add_enabled_ui(fit.is_none(), |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.radio_value(&mut self.fit_type, FitType::Clearance, "Clearance");
+                                    ui.radio_value(&mut self.fit_type, FitType::Transition, "Transition");
+                                    ui.radio_value(&mut self.fit_type, FitType::Interference, "Interference");
+                                });
                             });
                         });
+
+                        if let Some(error) = &self.error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
     
                         ui.add_space(16.0);
     
@@ -446,26 +709,37 @@ impl MateDialog {
                             }
     
                             let can_save = !self.component_a.is_empty() && !self.feature_a.is_empty() 
-                                && !self.component_b.is_empty() && !self.feature_b.is_empty();
+                                && !self.component_b.is_empty() && !self.feature_b.is_empty()
+                                && !matches!(fit, Some(Err(_)));
     
                             if ui.add_enabled(can_save, egui::Button::new("Save")).clicked() {
-                                let new_mate = Mate {
+                                let mut new_mate = Mate {
                                     id: Uuid::new_v4().to_string(),
                                     component_a: self.component_a.clone(),
                                     feature_a: self.feature_a.clone(),
                                     component_b: self.component_b.clone(),
                                     feature_b: self.feature_b.clone(),
                                     fit_type: self.fit_type.clone(),
+                                    fit: fit.and_then(Result::ok),
                                 };
-    
-                                if let Some(idx) = edit_index {
-                                    mates[idx] = new_mate;
-                                } else {
-                                    mates.push(new_mate);
+
+                                let applied = match new_mate.fit {
+                                    Some(fit) => fit.apply(&new_mate, components, units).map(|fit_type| new_mate.fit_type = fit_type),
+                                    None => Ok(()),
+                                };
+                                match applied {
+                                    Ok(()) => {
+                                        if let Some(idx) = edit_index {
+                                            mates[idx] = new_mate;
+                                        } else {
+                                            mates.push(new_mate);
+                                        }
+
+                                        changed = true;
+                                        should_close = true;
+                                    },
+                                    Err(e) => self.error = Some(format!("{:#}", e)),
                                 }
-    
-                                changed = true;
-                                should_close = true;
                             }
                         });
                     });
@@ -490,13 +764,16 @@ impl MateDialog {
                 self.component_b = mate.component_b.clone();
                 self.feature_b = mate.feature_b.clone();
                 self.fit_type = mate.fit_type.clone();
+                self.fit = mate.fit.map(|fit| fit.to_string()).unwrap_or_default();
             } else {
                 self.component_a.clear();
                 self.feature_a.clear();
                 self.component_b.clear();
                 self.feature_b.clear();
                 self.fit_type = FitType::Clearance;
+                self.fit.clear();
             }
+            self.error = None;
         }
     }
     
@@ -505,6 +782,10 @@ impl MateDialog {
         name: String,
         methods: Vec<AnalysisMethod>,
         monte_carlo_settings: MonteCarloSettings,
+        modified_rss_settings: ModifiedRssSettings,
+        six_sigma_settings: SixSigmaSettings,
+        expression: String,
+        correlation_groups: Vec<CorrelationGroup>,
         upper_spec_limit_str: String,
         lower_spec_limit_str: String, 
         open: bool,
@@ -535,7 +816,7 @@ impl MateDialog {
                 let result = egui::Window::new(if edit_index.is_some() { "Edit Analysis" } else { "New Analysis" })
                     .collapsible(false)
                     .resizable(false)
-                    .fixed_size([400.0, 500.0])
+                    .fixed_size([400.0, 560.0])
                     .show(ctx, |ui| {
                         ui.vertical(|ui| {
                             // Name input
@@ -553,6 +834,8 @@ impl MateDialog {
                                 let all_methods = [
                                     AnalysisMethod::WorstCase,
                                     AnalysisMethod::Rss,
+                                    AnalysisMethod::ModifiedRss,
+                                    AnalysisMethod::SixSigma,
                                     AnalysisMethod::MonteCarlo
                                 ];
     
@@ -568,6 +851,73 @@ impl MateDialog {
                                 }
                             });
     
+                            // Modified RSS settings if enabled
+                            if self.methods.contains(&AnalysisMethod::ModifiedRss) {
+                                ui.add_space(8.0);
+                                ui.group(|ui| {
+                                    ui.heading("Modified RSS Settings");
+
+                                    let mut from_count = self.modified_rss_settings.correction == MrssCorrection::ContributorCount;
+                                    ui.horizontal(|ui| {
+                                        ui.label("Correction:");
+                                        ui.radio_value(&mut from_count, false, "Fixed factor");
+                                        ui.radio_value(&mut from_count, true, "From contributor count");
+                                    });
+
+                                    match (from_count, self.modified_rss_settings.correction) {
+                                        (true, MrssCorrection::Fixed(_)) => {
+                                            self.modified_rss_settings.correction = MrssCorrection::ContributorCount;
+                                        },
+                                        (false, MrssCorrection::ContributorCount) => {
+                                            self.modified_rss_settings.correction = MrssCorrection::Fixed(MrssCorrection::BENDER);
+                                        },
+                                        _ => {}
+                                    }
+
+                                    if let MrssCorrection::Fixed(factor) = &mut self.modified_rss_settings.correction {
+                                        ui.horizontal(|ui| {
+                                            ui.label("Factor:");
+                                            ui.add(egui::DragValue::new(factor)
+                                                .speed(0.01)
+                                                .clamp_range(1.0..=3.0));
+                                            ui.label("(Bender: 1.5)");
+                                        });
+                                    }
+                                });
+                            }
+
+                            // Six Sigma settings if enabled
+                            if self.methods.contains(&AnalysisMethod::SixSigma) {
+                                ui.add_space(8.0);
+                                ui.group(|ui| {
+                                    ui.heading("Six Sigma Settings");
+
+                                    ui.horizontal(|ui| {
+                                        ui.label("Mean shift:");
+                                        ui.radio_value(&mut self.six_sigma_settings.mode, MeanShiftMode::Static, "Static");
+                                        ui.radio_value(&mut self.six_sigma_settings.mode, MeanShiftMode::Dynamic, "Dynamic");
+                                    });
+
+                                    ui.label("Default capability (features without their own):");
+                                    ui.horizontal(|ui| {
+                                        match &mut self.six_sigma_settings.default_capability {
+                                            CapabilityTarget::MeanShift { cp, k } => {
+                                                ui.label("Cp:");
+                                                ui.add(egui::DragValue::new(cp).speed(0.01).clamp_range(0.1..=10.0));
+                                                ui.label("Shift (k·σ):");
+                                                ui.add(egui::DragValue::new(k).speed(0.01).clamp_range(0.0..=6.0));
+                                            },
+                                            CapabilityTarget::CpCpk { cp, cpk } => {
+                                                ui.label("Cp:");
+                                                ui.add(egui::DragValue::new(cp).speed(0.01).clamp_range(0.1..=10.0));
+                                                ui.label("Cpk:");
+                                                ui.add(egui::DragValue::new(cpk).speed(0.01).clamp_range(0.0..=10.0));
+                                            },
+                                        }
+                                    });
+                                });
+                            }
+
                             // Monte Carlo settings if enabled
                             if self.methods.contains(&AnalysisMethod::MonteCarlo) {
                                 ui.add_space(8.0);
@@ -603,8 +953,113 @@ impl MateDialog {
                                             self.monte_carlo_settings.seed = seed_str.parse().ok();
                                         }
                                     });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label("Sampling:");
+                                        egui::ComboBox::from_id_source("mc_sampling")
+                                            .selected_text(self.monte_carlo_settings.sampling.label())
+                                            .show_ui(ui, |ui| {
+                                                for sampling in [
+                                                    SamplingStrategy::Random,
+                                                    SamplingStrategy::LatinHypercube,
+                                                    SamplingStrategy::Sobol,
+                                                    SamplingStrategy::Halton,
+                                                ] {
+                                                    ui.selectable_value(&mut self.monte_carlo_settings.sampling, sampling, sampling.label());
+                                                }
+                                            });
+                                    });
+
+                                    let mut adaptive = self.monte_carlo_settings.convergence.is_some();
+                                    if ui.checkbox(&mut adaptive, "Run until converged").changed() {
+                                        self.monte_carlo_settings.convergence = adaptive.then(ConvergenceSettings::default);
+                                    }
+                                    if let Some(convergence) = &mut self.monte_carlo_settings.convergence {
+                                        ui.horizontal(|ui| {
+                                            ui.label("Batch:");
+                                            ui.add(egui::DragValue::new(&mut convergence.batch_size).speed(100.0).clamp_range(100..=1_000_000));
+                                            ui.label("Max:");
+                                            ui.add(egui::DragValue::new(&mut convergence.max_iterations).speed(1000.0).clamp_range(1000..=100_000_000));
+                                        });
+                                        ui.horizontal(|ui| {
+                                            ui.label("Tolerance (% of σ):");
+                                            let mut percent = convergence.tolerance * 100.0;
+                                            if ui.add(egui::DragValue::new(&mut percent).speed(0.1).clamp_range(0.1..=50.0)).changed() {
+                                                convergence.tolerance = percent / 100.0;
+                                            }
+                                        });
+                                    }
+                                });
+
+                                ui.add_space(8.0);
+                                ui.group(|ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.heading("Correlation Groups");
+                                        if ui.small_button("➕").clicked() {
+                                            let name = format!("Group {}", self.correlation_groups.len() + 1);
+                                            self.correlation_groups.push(CorrelationGroup::new(name, 0.8));
+                                        }
+                                    });
+
+                                    let Some(idx) = edit_index else {
+                                        ui.label("Save the analysis and add contributions to correlate them");
+                                        return;
+                                    };
+                                    let contributions = &analyses[idx].contributions;
+
+                                    let mut remove = None;
+                                    for (group_idx, group) in self.correlation_groups.iter_mut().enumerate() {
+                                        ui.horizontal(|ui| {
+                                            ui.add(egui::TextEdit::singleline(&mut group.name).desired_width(120.0));
+                                            ui.label("ρ:");
+                                            ui.add(egui::DragValue::new(&mut group.coefficient)
+                                                .speed(0.01)
+                                                .clamp_range(-1.0..=1.0));
+                                            if ui.small_button("🗑").clicked() {
+                                                remove = Some(group_idx);
+                                            }
+                                        });
+                                        ui.horizontal_wrapped(|ui| {
+                                            for contrib in contributions {
+                                                let mut member = group.contains(&contrib.component_id, &contrib.feature_id);
+                                                let label = format!("{}.{}", contrib.component_id, contrib.feature_id);
+                                                if ui.checkbox(&mut member, label).changed() {
+                                                    let key = (contrib.component_id.clone(), contrib.feature_id.clone());
+                                                    if member {
+                                                        group.members.push(key);
+                                                    } else {
+                                                        group.members.retain(|m| *m != key);
+                                                    }
+                                                }
+                                            }
+                                        });
+                                    }
+                                    if let Some(group_idx) = remove {
+                                        self.correlation_groups.remove(group_idx);
+                                    }
                                 });
                             }
+                            ui.add_space(8.0);
+                            ui.group(|ui| {
+                                ui.heading("Stack Expression");
+                                ui.add(egui::TextEdit::singleline(&mut self.expression)
+                                    .desired_width(f32::INFINITY)
+                                    .hint_text("e.g. a + b*cos(theta) - d/2 (empty for linear sum)"));
+
+                                // Check the expression against the contribution variables
+                                if let Some(idx) = edit_index {
+                                    if !self.expression.trim().is_empty() {
+                                        let contributions = &analyses[idx].contributions;
+                                        let parsed = Expr::parse(&self.expression, |name| {
+                                            contributions.iter().position(|c| c.variable.as_deref() == Some(name))
+                                        });
+                                        if let Err(e) = parsed {
+                                            ui.colored_label(egui::Color32::RED, e.to_string());
+                                        }
+                                    }
+                                }
+                            });
+
                             ui.add_space(8.0);
                             ui.group(|ui| {
                                 ui.heading("Specification Limits");
@@ -665,6 +1120,23 @@ impl MateDialog {
                                         } else {
                                             None
                                         },
+                                        modified_rss_settings: if self.methods.contains(&AnalysisMethod::ModifiedRss) {
+                                            Some(self.modified_rss_settings.clone())
+                                        } else {
+                                            None
+                                        },
+                                        six_sigma_settings: if self.methods.contains(&AnalysisMethod::SixSigma) {
+                                            Some(self.six_sigma_settings.clone())
+                                        } else {
+                                            None
+                                        },
+                                        expression: Some(self.expression.trim().to_string())
+                                            .filter(|expression| !expression.is_empty()),
+                                        correlation_groups: self.correlation_groups.clone(),
+                                        // Kept from the Allocation, Study and DOE tabs
+                                        allocation: edit_index.and_then(|idx| analyses[idx].allocation.clone()),
+                                        study: edit_index.and_then(|idx| analyses[idx].study.clone()),
+                                        doe: edit_index.and_then(|idx| analyses[idx].doe.clone()),
                                     };
                                 
                                     if let Some(idx) = edit_index {
@@ -699,6 +1171,12 @@ impl MateDialog {
                 self.methods = analysis.methods.clone();
                 self.monte_carlo_settings = analysis.monte_carlo_settings.clone()
                     .unwrap_or_default();
+                self.modified_rss_settings = analysis.modified_rss_settings.clone()
+                    .unwrap_or_default();
+                self.six_sigma_settings = analysis.six_sigma_settings.clone()
+                    .unwrap_or_default();
+                self.expression = analysis.expression.clone().unwrap_or_default();
+                self.correlation_groups = analysis.correlation_groups.clone();
                 self.upper_spec_limit_str = analysis.upper_spec_limit
                     .map(|v| v.to_string())
                     .unwrap_or_default();
@@ -709,6 +1187,10 @@ impl MateDialog {
                 self.name.clear();
                 self.methods = vec![AnalysisMethod::WorstCase];
                 self.monte_carlo_settings = MonteCarloSettings::default();
+                self.modified_rss_settings = ModifiedRssSettings::default();
+                self.six_sigma_settings = SixSigmaSettings::default();
+                self.expression.clear();
+                self.correlation_groups.clear();
                 self.upper_spec_limit_str.clear();
                 self.lower_spec_limit_str.clear();
             }
@@ -721,6 +1203,9 @@ impl MateDialog {
         feature_id: String,
         direction: f64,
         half_count: bool,
+        coefficient: f64,
+        variable: String,
+        distribution: Option<DistributionParams>, // Override of the feature's distribution
         open: bool,
     }
     
@@ -728,6 +1213,7 @@ impl MateDialog {
         pub fn new() -> Self {
             Self {
                 direction: 1.0,
+                coefficient: 1.0,
                 ..Default::default()
             }
         }
@@ -749,7 +1235,7 @@ impl MateDialog {
                 let result = egui::Window::new(if contribution_index.is_some() { "Edit Contribution" } else { "Add Contribution" })
                     .collapsible(false)
                     .resizable(false)
-                    .fixed_size([400.0, 300.0])
+                    .fixed_size([400.0, 440.0])
                     .show(ctx, |ui| {
                         ui.vertical(|ui| {
                             // Component selection
@@ -808,6 +1294,35 @@ impl MateDialog {
                                 });
     
                                 ui.checkbox(&mut self.half_count, "Half Count");
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Coefficient:");
+                                    ui.add(egui::DragValue::new(&mut self.coefficient).speed(0.01));
+                                    ui.label("(lever ratio, cos of angle, ...)");
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Variable:");
+                                    ui.add(egui::TextEdit::singleline(&mut self.variable)
+                                        .desired_width(100.0)
+                                        .hint_text("for stack expression"));
+                                });
+
+                                let mut overridden = self.distribution.is_some();
+                                if ui.checkbox(&mut overridden, "Override feature distribution").changed() {
+                                    self.distribution = None;
+                                    if overridden {
+                                        if let Some(feature) = find_feature(components, &self.component_id, &self.feature_id) {
+                                            let mut params = feature.effective_distribution();
+                                            params.calculated = false;
+                                            self.distribution = Some(params);
+                                        }
+                                    }
+                                }
+                                if let Some(params) = &mut self.distribution {
+                                    ui.label(format!("{:?}", params.dist_type));
+                                    distribution_params_editor(ui, params);
+                                }
                             });
     
                             // Action buttons
@@ -820,13 +1335,16 @@ impl MateDialog {
                                 let can_save = !self.component_id.is_empty() && !self.feature_id.is_empty();
                                 if ui.add_enabled(can_save, egui::Button::new("Save")).clicked() {
                                     if let Some(analysis) = analyses.get_mut(analysis_index) {
-                                        if let Some(feature) = find_feature(components, &self.component_id, &self.feature_id) {
+                                        if find_feature(components, &self.component_id, &self.feature_id).is_some() {
                                             let contribution = StackupContribution {
                                                 component_id: self.component_id.clone(),
                                                 feature_id: self.feature_id.clone(),
                                                 direction: self.direction,
                                                 half_count: self.half_count,
-                                                distribution: Some(StackupAnalysis::calculate_distribution_params(feature)),
+                                                distribution: self.distribution.clone(),
+                                                coefficient: self.coefficient,
+                                                variable: Some(self.variable.trim().to_string())
+                                                    .filter(|name| !name.is_empty()),
                                             };
     
                                             if let Some(idx) = contribution_index {
@@ -863,11 +1381,311 @@ impl MateDialog {
                 self.feature_id = contribution.feature_id.clone();
                 self.direction = contribution.direction;
                 self.half_count = contribution.half_count;
+                self.coefficient = contribution.coefficient;
+                self.variable = contribution.variable.clone().unwrap_or_default();
+                self.distribution = contribution.distribution.clone().filter(|p| !p.calculated);
             } else {
                 self.component_id.clear();
                 self.feature_id.clear();
                 self.direction = 1.0;
                 self.half_count = false;
+                self.coefficient = 1.0;
+                self.variable.clear();
+                self.distribution = None;
+            }
+        }
+    }
+
+    /// Maps the columns of an inspection CSV to features and imports them
+    #[derive(Default)]
+    pub struct MeasurementDialog {
+        file_name: String,
+        table: Option<MeasurementTable>,
+        mappings: Vec<ColumnMapping>,
+        error: Option<String>,
+        open: bool,
+    }
+
+    impl MeasurementDialog {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn show(
+            &mut self,
+            ctx: &egui::Context,
+            components: &mut [Component],
+            on_close: impl FnOnce(),
+        ) -> Option<bool> {
+            let mut changed = false;
+
+            if self.open {
+                let mut should_close = false;
+
+                let result = egui::Window::new("Import Measurements")
+                    .collapsible(false)
+                    .resizable(true)
+                    .default_size([500.0, 400.0])
+                    .show(ctx, |ui| {
+                        let Some(table) = &self.table else { return };
+                        ui.label(format!("{}: {} columns", self.file_name, table.headers.len()));
+                        ui.label("Existing measurements of the chosen features are replaced.");
+                        ui.add_space(8.0);
+
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("measurement_columns")
+                                    .num_columns(3)
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        ui.strong("Column");
+                                        ui.strong("Values");
+                                        ui.strong("Feature");
+                                        ui.end_row();
+
+                                        for (i, (header, mapping)) in table.headers.iter().zip(self.mappings.iter_mut()).enumerate() {
+                                            ui.label(header);
+                                            ui.label(table.columns[i].len().to_string());
+
+                                            let selected = mapping.as_ref()
+                                                .map_or("(ignore)".to_string(), |(c, f)| format!("{}.{}", c, f));
+                                            egui::ComboBox::from_id_source(("measurement_mapping", i))
+                                                .selected_text(selected)
+                                                .show_ui(ui, |ui| {
+                                                    ui.selectable_value(mapping, None, "(ignore)");
+                                                    for component in components.iter() {
+                                                        for feature in &component.features {
+                                                            ui.selectable_value(
+                                                                mapping,
+                                                                Some((component.name.clone(), feature.name.clone())),
+                                                                format!("{}.{}", component.name, feature.name)
+                                                            );
+                                                        }
+                                                    }
+                                                });
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+
+                        if let Some(error) = &self.error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+
+                        ui.add_space(16.0);
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Cancel").clicked() {
+                                should_close = true;
+                            }
+
+                            let can_import = self.mappings.iter().any(Option::is_some);
+                            if ui.add_enabled(can_import, egui::Button::new("Import")).clicked() {
+                                match table.apply(&self.mappings, components) {
+                                    Ok(_) => {
+                                        changed = true;
+                                        should_close = true;
+                                    },
+                                    Err(e) => self.error = Some(e.to_string()),
+                                }
+                            }
+                        });
+                    });
+
+                if should_close {
+                    self.open = false;
+                    self.table = None;
+                    on_close();
+                }
+
+                result.map(|_| changed)
+            } else {
+                None
             }
         }
-    }
\ No newline at end of file
+
+        /// Read the file and map its columns to features by header
+        pub fn open(&mut self, path: &std::path::Path, components: &[Component]) -> anyhow::Result<()> {
+            let table = MeasurementTable::from_path(path)?;
+            self.mappings = table.auto_map(components);
+            self.table = Some(table);
+            self.file_name = path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            self.error = None;
+            self.open = true;
+            Ok(())
+        }
+    }
+
+    /// Finds the loops across a gap through the mates and creates an
+    /// analysis from the one chosen
+    #[derive(Default)]
+    pub struct LoopDialog {
+        name: String,
+        start: (String, String),
+        end: (String, String),
+        loops: Vec<StackLoop>,
+        selected: usize,
+        searched: bool,
+        error: Option<String>,
+        open: bool,
+    }
+
+    impl LoopDialog {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn show(
+            &mut self,
+            ctx: &egui::Context,
+            components: &[Component],
+            mates: &[Mate],
+            analyses: &mut Vec<StackupAnalysis>,
+            on_close: impl FnOnce(),
+        ) -> Option<bool> {
+            let mut changed = false;
+
+            if self.open {
+                let mut should_close = false;
+
+                let result = egui::Window::new("Stack from Mates")
+                    .collapsible(false)
+                    .resizable(true)
+                    .default_size([560.0, 420.0])
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Name:");
+                            ui.text_edit_singleline(&mut self.name);
+                        });
+                        ui.add_space(8.0);
+
+                        let before = (self.start.clone(), self.end.clone());
+                        egui::Grid::new("loop_ends")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.label("Gap start:");
+                                feature_picker(ui, "loop_start", components, &mut self.start);
+                                ui.end_row();
+                                ui.label("Gap end:");
+                                feature_picker(ui, "loop_end", components, &mut self.end);
+                                ui.end_row();
+                            });
+                        if before != (self.start.clone(), self.end.clone()) {
+                            self.loops.clear();
+                            self.searched = false;
+                            self.error = None;
+                        }
+
+                        let can_search = !self.start.1.is_empty() && !self.end.1.is_empty();
+                        if ui.add_enabled(can_search, egui::Button::new("🔍 Find Loops")).clicked() {
+                            let start = (self.start.0.as_str(), self.start.1.as_str());
+                            let end = (self.end.0.as_str(), self.end.1.as_str());
+                            match find_loops(components, mates, start, end) {
+                                Ok(loops) => {
+                                    self.loops = loops;
+                                    self.error = None;
+                                },
+                                Err(e) => {
+                                    self.loops.clear();
+                                    self.error = Some(format!("{:#}", e));
+                                },
+                            }
+                            self.selected = 0;
+                            self.searched = true;
+                            if self.name.trim().is_empty() {
+                                self.name = format!("{} to {}", self.start.1, self.end.1);
+                            }
+                        }
+
+                        if let Some(error) = &self.error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        } else if self.searched && self.loops.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, "No chain of mates joins these features");
+                        }
+
+                        if !self.loops.is_empty() {
+                            ui.add_space(8.0);
+                            ui.label(format!("{} loop(s), fewest contributions first:", self.loops.len()));
+                            egui::ScrollArea::vertical()
+                                .max_height(120.0)
+                                .id_source("loop_choices")
+                                .show(ui, |ui| {
+                                    for (i, stack_loop) in self.loops.iter().enumerate() {
+                                        ui.radio_value(&mut self.selected, i, stack_loop.describe());
+                                    }
+                                });
+
+                            ui.add_space(8.0);
+                            egui::Grid::new("loop_contributions")
+                                .num_columns(2)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.strong("Feature");
+                                    ui.strong("Direction");
+                                    ui.end_row();
+                                    for (component, feature, direction) in self.loops[self.selected].contributions() {
+                                        ui.label(format!("{}.{}", component, feature));
+                                        ui.label(if direction > 0.0 { "+1" } else { "-1" });
+                                        ui.end_row();
+                                    }
+                                });
+                        }
+
+                        ui.add_space(16.0);
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Cancel").clicked() {
+                                should_close = true;
+                            }
+
+                            let can_create = !self.loops.is_empty() && !self.name.trim().is_empty();
+                            if ui.add_enabled(can_create, egui::Button::new("Create Analysis")).clicked() {
+                                analyses.push(self.loops[self.selected].to_analysis(self.name.trim().to_string()));
+                                changed = true;
+                                should_close = true;
+                            }
+                        });
+                    });
+
+                if should_close {
+                    self.open = false;
+                    on_close();
+                }
+
+                result.map(|_| changed)
+            } else {
+                None
+            }
+        }
+
+        pub fn open(&mut self) {
+            *self = Self { open: true, ..Self::default() };
+        }
+    }
+
+    /// Component and feature combo boxes for one (component, feature)
+    fn feature_picker(ui: &mut egui::Ui, id: &str, components: &[Component], selection: &mut (String, String)) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source((id, "component"))
+                .selected_text(&selection.0)
+                .show_ui(ui, |ui| {
+                    for component in components {
+                        if ui.selectable_label(selection.0 == component.name, &component.name).clicked() {
+                            *selection = (component.name.clone(), String::new());
+                        }
+                    }
+                });
+            if let Some(component) = components.iter().find(|c| c.name == selection.0) {
+                egui::ComboBox::from_id_source((id, "feature"))
+                    .selected_text(&selection.1)
+                    .show_ui(ui, |ui| {
+                        for feature in &component.features {
+                            ui.selectable_value(&mut selection.1, feature.name.clone(), &feature.name);
+                        }
+                    });
+            }
+        });
+    }
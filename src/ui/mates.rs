@@ -1,8 +1,12 @@
 // src/ui/mates.rs
 use eframe::egui;
+use egui_plot::{Bar, BarChart, Plot, VLine};
 use crate::state::{AppState, DialogState, Screen};
-use crate::utils::find_feature;
-use crate::config::Mate;
+use crate::state::mate_state::MateFitCache;
+use atlas_core::utils::find_feature;
+use atlas_core::config::{Feature, Mate};
+use atlas_core::config::mate::FitType;
+use atlas_core::analysis::mate_fit::{analyze_fit, MATE_FIT_SAMPLES};
 
 pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
     // Update the mate state first
@@ -41,7 +45,7 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                     }
                 });
                 
-                if !state.components.is_empty() {
+                if !state.project.components.is_empty() {
                     if ui.button("➕ Add Mate").clicked() {
                         state.current_dialog = DialogState::NewMate {
                             component_a: String::new(),
@@ -66,7 +70,7 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                     .id_source("mates_list_scroll")
                     .show(ui, |ui| {
                         // Iterate through all mates but only show filtered ones
-                        let mates = state.mates.clone(); // Clone to avoid borrow checker issues
+                        let mates = state.project.mates.clone(); // Clone to avoid borrow checker issues
                         for (index, mate) in mates.iter().enumerate() {
                             // Skip if not in filtered list
                             if !filtered_mate_ids.contains(&mate.id) {
@@ -74,13 +78,13 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                             }
                             
                             let is_selected = state.selected_mate == Some(index);
-                            let feature_a = find_feature(&state.components, &mate.component_a, &mate.feature_a);
-                            let feature_b = find_feature(&state.components, &mate.component_b, &mate.feature_b);
+                            let feature_a = find_feature(&state.project.components, &mate.component_a, &mate.feature_a);
+                            let feature_b = find_feature(&state.project.components, &mate.component_b, &mate.feature_b);
                             
                             let validation = if let (Some(feat_a), Some(feat_b)) = (feature_a, feature_b) {
                                 mate.validate(feat_a, feat_b)
                             } else {
-                                crate::config::mate::FitValidation {
+                                atlas_core::config::mate::FitValidation {
                                     is_valid: false,
                                     nominal_fit: 0.0,
                                     min_fit: 0.0,
@@ -97,9 +101,10 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                                 let response = ui.selectable_label(
                                     is_selected,
                                     format!(
-                                        "{}.{} ↔ {}.{}\n{:?} Fit",
+                                        "{}.{} ↔ {}.{}\n{}{:?} Fit",
                                         mate.component_a, mate.feature_a,
                                         mate.component_b, mate.feature_b,
+                                        mate.fit.map(|fit| format!("{} ", fit)).unwrap_or_default(),
                                         mate.fit_type
                                     )
                                 );
@@ -121,7 +126,7 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                                     }
                                     
                                     if ui.button("🔍 Show Component A").clicked() {
-                                        if let Some(comp_idx) = state.components
+                                        if let Some(comp_idx) = state.project.components
                                             .iter()
                                             .position(|c| c.name == mate.component_a) 
                                         {
@@ -132,7 +137,7 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                                     }
 
                                     if ui.button("🔍 Show Component B").clicked() {
-                                        if let Some(comp_idx) = state.components
+                                        if let Some(comp_idx) = state.project.components
                                             .iter()
                                             .position(|c| c.name == mate.component_b) 
                                         {
@@ -151,13 +156,13 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                                     if delete_clicked {
                                         let state_ptr = state as *mut AppState;
                                         unsafe {
-                                            (*state_ptr).mates.remove(index);
+                                            (*state_ptr).project.mates.remove(index);
                                             (*state_ptr).update_mate_graph();
                                             
-                                            if (*state_ptr).mates.is_empty() {
+                                            if (*state_ptr).project.mates.is_empty() {
                                                 (*state_ptr).selected_mate = None;
-                                            } else if index >= (*state_ptr).mates.len() {
-                                                (*state_ptr).selected_mate = Some((*state_ptr).mates.len() - 1);
+                                            } else if index >= (*state_ptr).project.mates.len() {
+                                                (*state_ptr).selected_mate = Some((*state_ptr).project.mates.len() - 1);
                                             }
 
                                             if let Err(e) = (*state_ptr).save_project() {
@@ -185,9 +190,9 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                 ui.set_min_height(available_size.y);
 
                 if let Some(selected_idx) = state.selected_mate {
-                    if let Some(mate) = state.mates.get(selected_idx) {
-                        let feature_a = find_feature(&state.components, &mate.component_a, &mate.feature_a);
-                        let feature_b = find_feature(&state.components, &mate.component_b, &mate.feature_b);
+                    if let Some(mate) = state.project.mates.get(selected_idx) {
+                        let feature_a = find_feature(&state.project.components, &mate.component_a, &mate.feature_a);
+                        let feature_b = find_feature(&state.project.components, &mate.component_b, &mate.feature_b);
 
                         ui.heading("Mate Details");
                         ui.add_space(8.0);
@@ -229,7 +234,10 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
 
                             // Fit Analysis
                             ui.group(|ui| {
-                                ui.heading(&format!("Fit Analysis ({:?})", mate.fit_type));
+                                match mate.fit {
+                                    Some(fit) => ui.heading(format!("Fit Analysis ({} {:?})", fit, mate.fit_type)),
+                                    None => ui.heading(format!("Fit Analysis ({:?})", mate.fit_type)),
+                                };
                                 
                                 let nominal_fit = mate.calculate_nominal_fit(feat_a, feat_b);
                                 let min_fit = mate.calculate_min_fit(feat_a, feat_b);
@@ -260,6 +268,12 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                                     ui.colored_label(egui::Color32::RED, format!("⚠ {}", error));
                                 }
                             });
+
+                            ui.add_space(16.0);
+
+                            ui.group(|ui| {
+                                show_statistical_fit(ui, &mut state.mate_state.fit, mate, feat_a, feat_b);
+                            });
                         } else {
                             ui.colored_label(egui::Color32::RED, "One or more features not found");
                             if feature_a.is_none() {
@@ -277,4 +291,85 @@ pub fn show_mates_view(ui: &mut egui::Ui, state: &mut AppState) {
                 }
             });
         });
-}
\ No newline at end of file
+}
+
+/// Clearance of the mate by RSS and Monte Carlo, with the chance it is not
+/// the fit it should be and a histogram of the sampled clearances
+fn show_statistical_fit(ui: &mut egui::Ui, cache: &mut Option<MateFitCache>, mate: &Mate, feat_a: &Feature, feat_b: &Feature) {
+    ui.heading("Statistical Fit");
+
+    // Sampling is too slow to repeat every frame
+    let features = [
+        (feat_a.feature_type, feat_a.effective_distribution()),
+        (feat_b.feature_type, feat_b.effective_distribution()),
+    ];
+    let current = cache.as_ref().is_some_and(|cache| {
+        cache.mate_id == mate.id && cache.fit_type == mate.fit_type && cache.features == features
+    });
+    if !current {
+        *cache = Some(MateFitCache {
+            mate_id: mate.id.clone(),
+            fit_type: mate.fit_type.clone(),
+            result: analyze_fit(mate, feat_a, feat_b, MATE_FIT_SAMPLES, 0).map_err(|e| e.to_string()),
+            features,
+        });
+    }
+    let Some(cache) = cache else { return };
+    let result = match &cache.result {
+        Ok(result) => result,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, e);
+            return;
+        },
+    };
+
+    let failure_label = match mate.fit_type {
+        FitType::Clearance => Some("P(interference)"),
+        FitType::Interference => Some("P(loss of interference)"),
+        FitType::Transition => None,
+    };
+
+    egui::Grid::new("statistical_fit_grid")
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.strong("Mean clearance");
+            ui.strong("Std dev");
+            ui.strong(failure_label.unwrap_or("P(interference)"));
+            ui.end_row();
+
+            for (name, estimate) in [("RSS", &result.rss), ("Monte Carlo", &result.monte_carlo)] {
+                ui.label(name);
+                ui.label(format!("{:.4}", estimate.mean));
+                ui.label(format!("{:.4}", estimate.std_dev));
+                let probability = result.failure_probability(estimate).unwrap_or(estimate.interference);
+                ui.label(format!("{:.4}% ({:.0} ppm)", probability * 100.0, probability * 1e6));
+                ui.end_row();
+            }
+        });
+    ui.label(format!(
+        "{} samples, clearance {:.4} to {:.4}",
+        result.samples, result.min, result.max
+    ));
+
+    let width = result.histogram.get(1).map_or(0.0, |(start, _)| start - result.min);
+    let bars: Vec<Bar> = result.histogram.iter()
+        .map(|(start, count)| {
+            let color = if start + width / 2.0 < 0.0 {
+                egui::Color32::from_rgb(200, 80, 80)
+            } else {
+                egui::Color32::LIGHT_BLUE
+            };
+            Bar::new(start + width / 2.0, *count as f64).width(width).fill(color)
+        })
+        .collect();
+
+    Plot::new("clearance_histogram")
+        .height(180.0)
+        .x_axis_label("Clearance")
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).name("Clearance"));
+            plot_ui.vline(VLine::new(0.0).color(egui::Color32::RED).name("Zero clearance"));
+        });
+}
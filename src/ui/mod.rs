@@ -5,6 +5,8 @@ pub mod project;
 pub mod components;
 pub mod mates;
 pub mod analysis;
+pub mod dependencies;
+pub mod assembly_graph;
 
 // Re-export dialog manager
 pub use dialog::DialogManager;
\ No newline at end of file
@@ -1,12 +1,12 @@
 // src/ui/project.rs
 use eframe::egui;
-use crate::config::Units;
+use atlas_core::config::Units;
 use chrono::prelude::*;
 use crate::state::AppState;
 
 pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
-    let total_components = state.components.len();
-    let total_features: usize = state.components
+    let total_components = state.project.components.len();
+    let total_features: usize = state.project.components
         .iter()
         .map(|c| c.features.len())
         .sum();
@@ -22,7 +22,7 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
             ui.label("Name:");
             ui.add_sized(
                 [ui.available_width(), 20.0],
-                egui::TextEdit::singleline(&mut state.project_file.name)
+                egui::TextEdit::singleline(&mut state.project.file.name)
                     .hint_text("Enter project name")
             );
         });
@@ -30,7 +30,7 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
         // Description with edit
         ui.horizontal(|ui| {
             ui.label("Description:");
-            let desc = state.project_file.description.get_or_insert_with(String::new);
+            let desc = state.project.file.description.get_or_insert_with(String::new);
             ui.add_sized(
                 [ui.available_width(), 60.0],
                 egui::TextEdit::multiline(desc)
@@ -42,12 +42,12 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
         ui.horizontal(|ui| {
             ui.label("Units:");
             ui.radio_value(
-                &mut state.project_file.units,
+                &mut state.project.file.units,
                 Units::Metric,
                 "Metric (mm)"
             );
             ui.radio_value(
-                &mut state.project_file.units,
+                &mut state.project.file.units,
                 Units::Imperial,
                 "Imperial (in)"
             );
@@ -57,7 +57,7 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
     ui.add_space(16.0);
 
     // Project location (read-only)
-    if let Some(dir) = &state.project_dir {
+    if let Some(dir) = &state.project.dir {
         ui.group(|ui| {
             ui.horizontal(|ui| {
                 ui.label("Project Directory:");
@@ -77,8 +77,8 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
                 ui.heading("Components");
                 ui.add_space(8.0);
                 
-                let total_components = state.components.len();
-                let total_features: usize = state.components
+                let total_components = state.project.components.len();
+                let total_features: usize = state.project.components
                     .iter()
                     .map(|c| c.features.len())
                     .sum();
@@ -95,7 +95,7 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
                 if total_components > 0 {
                     ui.add_space(8.0);
                     ui.label("Recent Components:");
-                    for component in state.components.iter().take(3) {
+                    for component in state.project.components.iter().take(3) {
                         ui.label(format!("• {} ({} features)", 
                             component.name, 
                             component.features.len()));
@@ -111,8 +111,8 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
                 ui.heading("Mates");
                 ui.add_space(8.0);
                 
-                let total_mates = state.mates.len();
-                let valid_mates = state.mates.iter()
+                let total_mates = state.project.mates.len();
+                let valid_mates = state.project.mates.iter()
                     .filter(|mate| {
                         if let (Some(feat_a), Some(feat_b)) = (
                             find_feature(state, &mate.component_a, &mate.feature_a),
@@ -156,8 +156,8 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
         ui.horizontal(|ui| {
             // Analysis Statistics
             ui.vertical(|ui| {
-                let total_analyses = state.analyses.len();
-                let total_monte_carlo: usize = state.analyses.iter()
+                let total_analyses = state.project.analyses.len();
+                let total_monte_carlo: usize = state.project.analyses.iter()
                     .filter_map(|analysis| analysis.monte_carlo_settings.as_ref())
                     .map(|settings| settings.iterations)
                     .sum();
@@ -173,7 +173,7 @@ pub fn show_project_view(ui: &mut egui::Ui, state: &mut AppState) {
 
             // Latest Results
             ui.vertical(|ui| {
-                let latest_result = state.latest_results.values()
+                let latest_result = state.project.latest_results.values()
                     .max_by_key(|r| DateTime::parse_from_rfc3339(&r.timestamp).ok());
                 
                 if let Some(result) = latest_result {
@@ -210,8 +210,8 @@ fn find_feature<'a>(
     state: &'a crate::state::AppState,
     component_name: &str,
     feature_name: &str,
-) -> Option<&'a crate::config::Feature> {
-    state.components
+) -> Option<&'a atlas_core::config::Feature> {
+    state.project.components
         .iter()
         .find(|c| c.name == component_name)?
         .features